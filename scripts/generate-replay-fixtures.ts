@@ -0,0 +1,107 @@
+// Regenerates src-tauri/crates/torus-core/tests/fixtures/replay-parity.json
+// from the Edge Function simulator, so the Rust port in
+// src-tauri/crates/torus-core/src/replay.rs can be checked against the
+// TypeScript results tick for tick.
+//
+//   deno run --allow-write scripts/generate-replay-fixtures.ts
+//
+// Each run is a deterministic pseudo-random game played to game over. Its
+// checks also cut the proof short or claim a wrong score, so mid-run and
+// failure states are compared too.
+
+import {
+  verifyReplayProof,
+  type ReplayMove,
+  type ReplayProof,
+} from "../supabase/functions/verify-score/simulator.ts";
+
+const OUTPUT_PATH = "src-tauri/crates/torus-core/tests/fixtures/replay-parity.json";
+const MAX_FINAL_TIME = 2_000_000;
+const MOVES: ReplayMove[] = ["left", "right", "up", "down"];
+
+function mulberry32(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let value = Math.imul(state ^ (state >>> 15), 1 | state);
+    value ^= value + Math.imul(value ^ (value >>> 7), 61 | value);
+    return ((value ^ (value >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+function playToGameOver(
+  seed: number,
+  difficulty: 1 | 2 | 3,
+  maxGap: number,
+): ReplayProof {
+  const random = mulberry32(seed ^ 0x9e3779b9);
+  let inputs = [];
+  let time = 0;
+  for (let index = 0; index < 4_000; index += 1) {
+    time += Math.floor(random() * maxGap);
+    inputs.push({ time, move: MOVES[Math.floor(random() * MOVES.length)] });
+  }
+
+  const proof: ReplayProof = {
+    version: 1,
+    difficulty,
+    seed,
+    finalTime: MAX_FINAL_TIME,
+    finalScore: 0,
+    finalLevel: 0,
+    inputs,
+  };
+  let result = verifyReplayProof(proof);
+  // Drop the inputs the run never reached; fewer inputs can change when it
+  // ends, so repeat until every input lands before game over.
+  while (result.reason === "Replay ended before all inputs were consumed.") {
+    const end = result.actual.time;
+    inputs = inputs.filter((input) => input.time < end);
+    proof.inputs = inputs;
+    result = verifyReplayProof(proof);
+  }
+  if (result.reason !== "Replay final time mismatch.") {
+    throw new Error(`seed ${seed} did not end: ${result.reason}`);
+  }
+  proof.finalTime = result.actual.time;
+  proof.finalScore = result.actual.score;
+  proof.finalLevel = result.actual.level;
+  return proof;
+}
+
+const runs: [number, 1 | 2 | 3, number][] = [
+  [1, 1, 4],
+  [20240601, 1, 3],
+  [4242, 2, 6],
+  [77, 2, 3],
+  [31337, 3, 3],
+];
+const fixtures = runs.map(([seed, difficulty, maxGap]) => {
+  const proof = playToGameOver(seed, difficulty, maxGap);
+  const halfTime = Math.floor(proof.finalTime / 2);
+  const variants = [
+    { ...proof },
+    {
+      ...proof,
+      finalTime: halfTime,
+      inputs: proof.inputs.filter((input) => input.time <= halfTime),
+    },
+    { ...proof, finalScore: proof.finalScore + 300 },
+  ];
+  return {
+    name: `seed ${seed} difficulty ${difficulty}`,
+    proof,
+    // Each check replays the first `inputCount` inputs against other claims.
+    checks: variants.map((variant) => ({
+      finalTime: variant.finalTime,
+      finalScore: variant.finalScore,
+      finalLevel: variant.finalLevel,
+      inputCount: variant.inputs.length,
+      expected: verifyReplayProof(variant),
+    })),
+  };
+});
+// One fixture per line keeps the long input lists out of the way.
+const lines = fixtures.map((fixture) => "  " + JSON.stringify(fixture));
+await Deno.writeTextFile(OUTPUT_PATH, `[\n${lines.join(",\n")}\n]\n`);
+console.log(`wrote ${fixtures.length} runs to ${OUTPUT_PATH}`);
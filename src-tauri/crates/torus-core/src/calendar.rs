@@ -0,0 +1,83 @@
+//! Proleptic Gregorian calendar arithmetic on Unix day numbers, shared by
+//! the daily keys, period keys and timestamps.
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Unix day number of a `YYYY-MM-DD` key, or `None` for an impossible date.
+pub fn challenge_key_to_day_number(challenge_key: &str) -> Option<i64> {
+    if challenge_key.len() != 10 {
+        return None;
+    }
+    let bytes = challenge_key.as_bytes();
+    if bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+
+    let year = parse_i32_digits(&challenge_key[0..4])?;
+    let month = parse_i32_digits(&challenge_key[5..7])?;
+    let day = parse_i32_digits(&challenge_key[8..10])?;
+    if !(1..=12).contains(&month) {
+        return None;
+    }
+
+    let max_day = days_in_month(year, month as u32);
+    if day < 1 || day as u32 > max_day {
+        return None;
+    }
+
+    Some(days_from_civil(year, month as u32, day as u32))
+}
+
+pub fn parse_i32_digits(raw: &str) -> Option<i32> {
+    if raw.is_empty() || !raw.bytes().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    raw.parse::<i32>().ok()
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => {
+            if is_leap_year(year) {
+                29
+            } else {
+                28
+            }
+        }
+        _ => 0,
+    }
+}
+
+pub fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = year - if month <= 2 { 1 } else { 0 };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let month_index = month as i32 + if month > 2 { -3 } else { 9 };
+    let doy = (153 * month_index + 2) / 5 + day as i32 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    (era * 146_097 + doe - 719_468) as i64
+}
+
+pub fn day_number_to_challenge_key(day_number: i64) -> String {
+    let (year, month, day) = civil_from_days(day_number);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+pub fn civil_from_days(day_number: i64) -> (i64, u32, u32) {
+    let z = day_number + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,300 @@
+use crate::calendar::{
+    challenge_key_to_day_number, civil_from_days, day_number_to_challenge_key, days_from_civil,
+    days_in_month, parse_i32_digits, SECONDS_PER_DAY,
+};
+use crate::clock::current_utc_seconds;
+
+const DAILY_MODE: &str = "daily";
+const WEEKLY_MODE: &str = "weekly";
+const MONTHLY_MODE: &str = "monthly";
+/// Upper bound on any attempt limit the server may configure; mirrors the
+/// `scores.attempts_used` check constraint.
+pub const MAX_CHALLENGE_ATTEMPTS: i64 = 20;
+
+/// A seeded challenge that rolls over on a UTC calendar boundary. Every
+/// period shares the start/forfeit/rollback/submit attempt lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengePeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl ChallengePeriod {
+    pub fn from_mode(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            DAILY_MODE => Ok(ChallengePeriod::Daily),
+            WEEKLY_MODE => Ok(ChallengePeriod::Weekly),
+            MONTHLY_MODE => Ok(ChallengePeriod::Monthly),
+            _ => Err("challenge mode must be daily, weekly, or monthly".into()),
+        }
+    }
+
+    pub fn mode(self) -> &'static str {
+        match self {
+            ChallengePeriod::Daily => DAILY_MODE,
+            ChallengePeriod::Weekly => WEEKLY_MODE,
+            ChallengePeriod::Monthly => MONTHLY_MODE,
+        }
+    }
+
+    /// Attempt limit used when the server has no per-challenge override.
+    pub fn default_max_attempts(self) -> i64 {
+        match self {
+            ChallengePeriod::Daily => 3,
+            ChallengePeriod::Weekly => 5,
+            ChallengePeriod::Monthly => 10,
+        }
+    }
+
+    /// Checks an attempt limit reported by the server. Deployments that predate
+    /// configurable limits omit it (decoded as 0), so the default applies.
+    pub fn validate_max_attempts(self, reported: i64) -> Result<i64, String> {
+        if reported == 0 {
+            return Ok(self.default_max_attempts());
+        }
+        if !(1..=MAX_CHALLENGE_ATTEMPTS).contains(&reported) {
+            return Err(format!(
+                "server reported an invalid {} attempt limit: {reported}",
+                self.mode()
+            ));
+        }
+        Ok(reported)
+    }
+
+    fn key_format(self) -> &'static str {
+        match self {
+            ChallengePeriod::Daily => "YYYY-MM-DD",
+            ChallengePeriod::Weekly => "YYYY-Www",
+            ChallengePeriod::Monthly => "YYYY-MM",
+        }
+    }
+
+    /// Key of the period containing the given Unix day number.
+    pub fn key_for_day(self, day_number: i64) -> String {
+        match self {
+            ChallengePeriod::Daily => day_number_to_challenge_key(day_number),
+            ChallengePeriod::Weekly => {
+                let (iso_year, week) = iso_week_of_day(day_number);
+                format!("{iso_year:04}-W{week:02}")
+            }
+            ChallengePeriod::Monthly => day_number_to_challenge_key(day_number)[..7].to_string(),
+        }
+    }
+
+    /// First and last Unix day numbers covered by `key`.
+    pub fn day_range(self, key: &str) -> Option<(i64, i64)> {
+        match self {
+            ChallengePeriod::Daily => challenge_key_to_day_number(key).map(|day| (day, day)),
+            ChallengePeriod::Weekly => {
+                let bytes = key.as_bytes();
+                if key.len() != 8 || bytes[4] != b'-' || bytes[5] != b'W' {
+                    return None;
+                }
+                let year = parse_i32_digits(&key[0..4])?;
+                let week = parse_i32_digits(&key[6..8])? as i64;
+                let start = iso_week_one_monday(year) + (week - 1) * 7;
+                if week < 1 || self.key_for_day(start) != key {
+                    return None;
+                }
+                Some((start, start + 6))
+            }
+            ChallengePeriod::Monthly => {
+                if key.len() != 7 || key.as_bytes()[4] != b'-' {
+                    return None;
+                }
+                let year = parse_i32_digits(&key[0..4])?;
+                let month = parse_i32_digits(&key[5..7])?;
+                if !(1..=12).contains(&month) {
+                    return None;
+                }
+                let start = days_from_civil(year, month as u32, 1);
+                Some((start, start + days_in_month(year, month as u32) as i64 - 1))
+            }
+        }
+    }
+
+    pub fn current_key(self) -> String {
+        self.key_for_day(current_utc_seconds().div_euclid(SECONDS_PER_DAY))
+    }
+
+    /// The period just before the current one, accepted as a grace window for
+    /// runs that straddle the rollover.
+    pub fn grace_key(self) -> String {
+        let current = self.current_key();
+        let (start, _) = self
+            .day_range(&current)
+            .expect("current challenge key is always well-formed");
+        self.key_for_day(start - 1)
+    }
+
+    pub fn seconds_until_rollover(self) -> i64 {
+        let now = current_utc_seconds();
+        let current = self.key_for_day(now.div_euclid(SECONDS_PER_DAY));
+        let (_, end) = self
+            .day_range(&current)
+            .expect("current challenge key is always well-formed");
+        ((end + 1) * SECONDS_PER_DAY - now).max(0)
+    }
+
+    fn parse_key(self, raw: &str) -> Result<(String, i64), String> {
+        let trimmed = raw.trim().to_ascii_uppercase();
+        let (start, _) = self.day_range(&trimmed).ok_or_else(|| {
+            format!(
+                "{} challenge key must be in {} format",
+                self.mode(),
+                self.key_format()
+            )
+        })?;
+        Ok((trimmed, start))
+    }
+
+    /// Accepts only the current period or the grace period before it, so stale
+    /// keys are rejected before any RPC is made.
+    pub fn normalize_active_key(self, raw: &str) -> Result<String, String> {
+        let (key, _) = self.parse_key(raw)?;
+        let current = self.current_key();
+        if key != current && key != self.grace_key() {
+            return Err(format!(
+                "{} challenge key {key} is not active (current is {current})",
+                self.mode()
+            ));
+        }
+        Ok(key)
+    }
+
+    /// Accepts any well-formed key that has already started.
+    pub fn normalize_archived_key(self, raw: &str) -> Result<String, String> {
+        let (key, start) = self.parse_key(raw)?;
+        if start > current_utc_seconds().div_euclid(SECONDS_PER_DAY) {
+            return Err(format!(
+                "{} challenge key {key} is in the future",
+                self.mode()
+            ));
+        }
+        Ok(key)
+    }
+
+    /// Same FNV-1a hash the frontend applies to `torus-<mode>-<key>`.
+    pub fn seed(self, key: &str) -> u32 {
+        let mut hash = 2_166_136_261_u32;
+        for byte in format!("torus-{}-{key}", self.mode()).bytes() {
+            hash ^= byte as u32;
+            hash = hash.wrapping_mul(16_777_619);
+        }
+        hash
+    }
+}
+
+/// Key of the `mode` period containing the given Unix day number, or `None`
+/// for an unknown mode; used by `torus-server`.
+pub fn challenge_key_for_day(mode: &str, day_number: i64) -> Option<String> {
+    let period = ChallengePeriod::from_mode(mode).ok()?;
+    Some(period.key_for_day(day_number))
+}
+
+/// First Unix day number covered by a well-formed `mode` key; used by
+/// `torus-server`.
+pub fn challenge_key_start_day(mode: &str, key: &str) -> Option<i64> {
+    let period = ChallengePeriod::from_mode(mode).ok()?;
+    period.day_range(key).map(|(start, _)| start)
+}
+
+/// Monday = 0 ... Sunday = 6; Unix day 0 was a Thursday.
+fn weekday_from_monday(day_number: i64) -> i64 {
+    (day_number + 3).rem_euclid(7)
+}
+
+fn iso_week_one_monday(iso_year: i32) -> i64 {
+    let jan_fourth = days_from_civil(iso_year, 1, 4);
+    jan_fourth - weekday_from_monday(jan_fourth)
+}
+
+fn iso_week_of_day(day_number: i64) -> (i32, i64) {
+    let thursday = day_number - weekday_from_monday(day_number) + 3;
+    let iso_year = civil_from_days(thursday).0 as i32;
+    let week = (thursday - days_from_civil(iso_year, 1, 1)) / 7 + 1;
+    (iso_year, week)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> i64 {
+        days_from_civil(year, month, day)
+    }
+
+    #[test]
+    fn weeks_follow_iso_8601_across_year_ends() {
+        let weekly = ChallengePeriod::Weekly;
+        assert_eq!(weekly.key_for_day(day(2021, 1, 3)), "2020-W53");
+        assert_eq!(weekly.key_for_day(day(2021, 1, 4)), "2021-W01");
+        assert_eq!(weekly.key_for_day(day(2024, 12, 29)), "2024-W52");
+        assert_eq!(weekly.key_for_day(day(2024, 12, 30)), "2025-W01");
+        assert_eq!(weekly.key_for_day(day(2026, 1, 1)), "2026-W01");
+
+        assert_eq!(
+            weekly.day_range("2020-W53"),
+            Some((day(2020, 12, 28), day(2021, 1, 3)))
+        );
+        assert_eq!(
+            weekly.day_range("2025-W01"),
+            Some((day(2024, 12, 30), day(2025, 1, 5)))
+        );
+        assert_eq!(weekly.day_range("2021-W53"), None);
+        assert_eq!(weekly.day_range("2021-W00"), None);
+        assert_eq!(weekly.day_range("2021-01"), None);
+        assert_eq!(weekly.day_range("2021-w01"), None);
+    }
+
+    #[test]
+    fn months_cover_their_calendar_days() {
+        let monthly = ChallengePeriod::Monthly;
+        assert_eq!(monthly.key_for_day(day(2024, 2, 29)), "2024-02");
+        assert_eq!(
+            monthly.day_range("2024-02"),
+            Some((day(2024, 2, 1), day(2024, 2, 29)))
+        );
+        assert_eq!(
+            monthly.day_range("2023-02"),
+            Some((day(2023, 2, 1), day(2023, 2, 28)))
+        );
+        assert_eq!(
+            monthly.day_range("2025-12"),
+            Some((day(2025, 12, 1), day(2025, 12, 31)))
+        );
+        for bad in ["2025-00", "2025-13", "2025-1", "2025/12", "2025-12-01"] {
+            assert_eq!(monthly.day_range(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn attempt_limits_are_bounded() {
+        let weekly = ChallengePeriod::Weekly;
+        assert_eq!(weekly.validate_max_attempts(0), Ok(5));
+        assert_eq!(weekly.validate_max_attempts(1), Ok(1));
+        assert_eq!(weekly.validate_max_attempts(20), Ok(20));
+        assert!(weekly.validate_max_attempts(21).is_err());
+        assert!(weekly.validate_max_attempts(-1).is_err());
+    }
+
+    #[test]
+    fn grace_key_is_the_period_just_before_the_current_one() {
+        for period in [
+            ChallengePeriod::Daily,
+            ChallengePeriod::Weekly,
+            ChallengePeriod::Monthly,
+        ] {
+            let current = period.current_key();
+            let grace = period.grace_key();
+            let (current_start, _) = period.day_range(&current).unwrap();
+            let (_, grace_end) = period.day_range(&grace).unwrap();
+            assert_eq!(grace_end + 1, current_start, "{}", period.mode());
+            assert_eq!(period.normalize_active_key(&grace), Ok(grace.clone()));
+            let (grace_start, _) = period.day_range(&grace).unwrap();
+            let stale = period.key_for_day(grace_start - 1);
+            assert!(period.normalize_active_key(&stale).is_err());
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! The app's UTC clock, corrected by the offset of the last server `Date`
+//! header so period keys roll over with the server.
+
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::calendar::{day_number_to_challenge_key, SECONDS_PER_DAY};
+
+static SERVER_CLOCK_OFFSET_SECONDS: Mutex<Option<i64>> = Mutex::new(None);
+
+fn local_utc_seconds() -> i64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i64,
+        Err(error) => -(error.duration().as_secs() as i64),
+    }
+}
+
+/// Local UTC time shifted by the last observed Supabase clock offset, so the
+/// daily key follows the server even when the device clock drifts.
+pub fn current_utc_seconds() -> i64 {
+    local_utc_seconds() + server_clock_offset().unwrap_or(0)
+}
+
+pub fn current_challenge_key() -> String {
+    day_number_to_challenge_key(current_utc_seconds().div_euclid(SECONDS_PER_DAY))
+}
+
+pub fn server_clock_offset() -> Option<i64> {
+    SERVER_CLOCK_OFFSET_SECONDS
+        .lock()
+        .map(|value| *value)
+        .unwrap_or(None)
+}
+
+/// Records the server's time, in Unix seconds, as read from a response.
+pub fn record_server_time(server_seconds: i64) {
+    if let Ok(mut offset) = SERVER_CLOCK_OFFSET_SECONDS.lock() {
+        *offset = Some(server_seconds - local_utc_seconds());
+    }
+}
@@ -0,0 +1,8 @@
+//! The parts of the Torus leaderboard logic that need no Tauri runtime: the
+//! replay simulator, challenge period keys and the UTC calendar and clock.
+//! Both the app and `torus-server` build on it.
+
+pub mod calendar;
+pub mod challenge_period;
+pub mod clock;
+pub mod replay;
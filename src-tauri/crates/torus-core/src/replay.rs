@@ -0,0 +1,766 @@
+use serde::{Deserialize, Serialize};
+
+const MAX_COLS: usize = 128;
+const MAX_REPLAY_INPUTS: usize = 20_000;
+const MAX_REPLAY_FINAL_TIME: i64 = 2_000_000;
+const GAUGE_TIME: i64 = 20;
+const BOX_HEIGHT: usize = 20;
+const SCORE_PER_TORUS: i64 = 300;
+const WAITING_TIME: i64 = 10;
+const LEVEL_UP_TIME: i64 = 5;
+const NUM_COLORS: f64 = 5.0;
+const MELTED_ANGLE: i32 = -1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayInputEvent {
+    pub time: i64,
+    #[serde(rename = "move")]
+    pub move_dir: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyReplayProof {
+    pub version: i64,
+    pub difficulty: i64,
+    pub seed: u32,
+    #[serde(rename = "finalTime")]
+    pub final_time: i64,
+    #[serde(rename = "finalScore")]
+    pub final_score: i64,
+    #[serde(rename = "finalLevel")]
+    pub final_level: i64,
+    pub inputs: Vec<ReplayInputEvent>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TorusCell {
+    color: i32,
+    angle: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PoleEntry {
+    Empty,
+    Pole,
+    Torus(TorusCell),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayState {
+    pub score: i64,
+    pub level: i64,
+    pub time: i64,
+    pub game_on: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayVerification {
+    pub ok: bool,
+    pub reason: Option<&'static str>,
+    pub actual: ReplayState,
+    pub rows_melted: i64,
+}
+
+/// Rust port of `supabase/functions/verify-score/simulator.ts`. Both must stay
+/// tick-for-tick identical so local checks agree with the Edge Function.
+struct ReplaySimulator {
+    num_cols: usize,
+    pole_height: usize,
+    pole_pos: usize,
+    boxes: Vec<Vec<Option<TorusCell>>>,
+    pole: Vec<Vec<PoleEntry>>,
+    flying_tori: Vec<Option<i32>>,
+    flying_tori_height: Vec<i64>,
+    flying_tori_waiting: Vec<i64>,
+    num_tori: Vec<usize>,
+    num_tori_in_pole: usize,
+    score: i64,
+    level: i64,
+    level_gauge: i64,
+    time: i64,
+    game_on: bool,
+    random_seed_state: u32,
+    game_over_snapshot: Option<ReplayState>,
+    difficulty: i64,
+    rows_melted: i64,
+}
+
+impl ReplaySimulator {
+    fn new(seed: u32, difficulty: i64) -> Self {
+        let mut simulator = ReplaySimulator {
+            num_cols: 3,
+            pole_height: 3,
+            pole_pos: 0,
+            boxes: Vec::new(),
+            pole: Vec::new(),
+            flying_tori: Vec::new(),
+            flying_tori_height: Vec::new(),
+            flying_tori_waiting: Vec::new(),
+            num_tori: Vec::new(),
+            num_tori_in_pole: 0,
+            score: 0,
+            level: 0,
+            level_gauge: 0,
+            time: 0,
+            game_on: true,
+            random_seed_state: seed,
+            game_over_snapshot: None,
+            difficulty: if difficulty == 2 || difficulty == 3 {
+                difficulty
+            } else {
+                1
+            },
+            rows_melted: 0,
+        };
+        simulator.reset_state();
+        simulator
+    }
+
+    fn state(&self) -> ReplayState {
+        ReplayState {
+            score: self.score,
+            level: self.level,
+            time: self.time,
+            game_on: self.game_on,
+        }
+    }
+
+    fn step_until(&mut self, target_time: i64) {
+        while self.game_on && self.time < target_time {
+            self.update();
+        }
+    }
+
+    fn finalize_at_current_time(&mut self) {
+        if !self.game_on {
+            return;
+        }
+        if self.has_overflow_column() {
+            self.set_game_over();
+        }
+    }
+
+    fn step_one_tick_and_capture_game_over_snapshot(&mut self) -> Option<ReplayState> {
+        self.game_over_snapshot = None;
+        self.update();
+        self.game_over_snapshot
+    }
+
+    fn apply_move(&mut self, move_dir: &str) {
+        if !self.game_on {
+            return;
+        }
+        match move_dir {
+            "left" => self.move_pole_left(),
+            "right" => self.move_pole_right(),
+            "up" => {
+                if self.num_tori[self.pole_pos] < BOX_HEIGHT && self.num_tori_in_pole > 0 {
+                    self.box_insert_from_pole();
+                    self.pole_delete_top();
+                    self.melt_same_rows();
+                }
+            }
+            _ => {
+                if self.num_tori[self.pole_pos] > 0 && self.num_tori_in_pole < self.pole_height {
+                    self.pole_insert();
+                    self.remove_bottom(self.pole_pos);
+                    self.melt_same_rows();
+                }
+            }
+        }
+    }
+
+    fn reset_state(&mut self) {
+        self.num_cols = 3;
+        self.pole_height = self.num_cols;
+        self.pole_pos = 0;
+        self.boxes = vec![vec![None; self.num_cols]; BOX_HEIGHT];
+        self.pole = vec![vec![PoleEntry::Empty; self.num_cols]; self.pole_height];
+        self.flying_tori = vec![None; self.num_cols];
+        self.flying_tori_height = vec![0; self.num_cols];
+        self.flying_tori_waiting = vec![0; self.num_cols];
+        self.num_tori = vec![0; self.num_cols];
+        self.num_tori_in_pole = 0;
+        self.score = 0;
+        self.level = 0;
+        self.level_gauge = 0;
+        self.time = 0;
+        self.init_pole(0);
+    }
+
+    fn next_seeded_random(&mut self) -> f64 {
+        self.random_seed_state = self.random_seed_state.wrapping_add(0x6d2b_79f5);
+        let state = self.random_seed_state;
+        let mut value = (state ^ (state >> 15)).wrapping_mul(1 | state);
+        value ^= value.wrapping_add((value ^ (value >> 7)).wrapping_mul(61 | value));
+        (value ^ (value >> 14)) as f64 / 4_294_967_296.0
+    }
+
+    fn random_torus(&mut self) -> i32 {
+        (self.next_seeded_random() * NUM_COLORS).floor() as i32
+    }
+
+    fn update(&mut self) {
+        if self.has_overflow_column() {
+            self.set_game_over();
+        }
+        if !self.game_on {
+            return;
+        }
+
+        self.delete_melted_tori();
+        self.update_flying_tori();
+        self.melt_same_rows();
+        self.increase_time();
+
+        if self.level_gauge == LEVEL_UP_TIME * self.num_cols as i64 {
+            self.increase_level();
+            self.level_gauge = 0;
+        }
+    }
+
+    fn update_flying_tori(&mut self) {
+        let mut col = 0;
+        while col < self.num_cols {
+            self.update_flying_torus(col);
+            col += 1;
+        }
+    }
+
+    fn update_flying_torus(&mut self, col: usize) {
+        // The TS simulator gates this on `time % flyingTorusSpeedFactor`, which is 1.
+        if let Some(flying) = self.flying_tori[col] {
+            if self.flying_tori_height[col] > 2 + self.num_tori[col] as i64 {
+                self.flying_tori_height[col] -= 1;
+            } else {
+                self.insert_flying_torus(col, flying);
+                self.flying_tori[col] = None;
+            }
+            return;
+        }
+
+        if self.flying_tori_waiting[col] == WAITING_TIME {
+            self.flying_tori[col] = Some(self.random_torus());
+            self.flying_tori_height[col] = BOX_HEIGHT as i64;
+            self.flying_tori_waiting[col] = 0;
+        } else {
+            self.flying_tori_waiting[col] += 1;
+        }
+    }
+
+    fn insert_flying_torus(&mut self, col: usize, color: i32) {
+        let target_row = BOX_HEIGHT as i64 - self.num_tori[col] as i64 - 1;
+        if target_row < 0 {
+            self.set_game_over();
+            return;
+        }
+        let angle = if (3 + self.flying_tori_height[col]) % 4 < 2 {
+            4
+        } else {
+            1
+        };
+        self.boxes[target_row as usize][col] = Some(TorusCell { color, angle });
+        self.num_tori[col] += 1;
+    }
+
+    fn init_pole(&mut self, pos: usize) {
+        self.pole_pos = pos;
+        for row in self.pole.iter_mut() {
+            for (col, cell) in row.iter_mut().enumerate() {
+                *cell = if col == pos {
+                    PoleEntry::Pole
+                } else {
+                    PoleEntry::Empty
+                };
+            }
+        }
+    }
+
+    fn move_pole_left(&mut self) {
+        if self.pole_pos == 0 {
+            return;
+        }
+        self.shift_pole(self.pole_pos - 1);
+    }
+
+    fn move_pole_right(&mut self) {
+        if self.pole_pos + 1 >= self.num_cols {
+            return;
+        }
+        self.shift_pole(self.pole_pos + 1);
+    }
+
+    fn shift_pole(&mut self, new_pos: usize) {
+        let old_pos = self.pole_pos;
+        for row in self.pole.iter_mut() {
+            row[new_pos] = row[old_pos];
+            row[old_pos] = PoleEntry::Empty;
+        }
+        self.pole_pos = new_pos;
+    }
+
+    fn box_get_entry(&self, row: usize, col: usize) -> Option<i32> {
+        match self.boxes[row][col] {
+            Some(cell) if cell.angle != MELTED_ANGLE => Some(cell.color),
+            _ => None,
+        }
+    }
+
+    fn torus_flipped(torus: Option<TorusCell>) -> Option<TorusCell> {
+        torus.map(|cell| TorusCell {
+            color: cell.color,
+            angle: (cell.angle + 3) % 6,
+        })
+    }
+
+    fn torus_rotated_right(torus: Option<TorusCell>) -> Option<TorusCell> {
+        torus.map(|cell| TorusCell {
+            color: cell.color,
+            angle: (cell.angle + 5) % 6,
+        })
+    }
+
+    fn torus_rotated_left(torus: Option<TorusCell>) -> Option<TorusCell> {
+        torus.map(|cell| TorusCell {
+            color: cell.color,
+            angle: (cell.angle + 1) % 6,
+        })
+    }
+
+    fn to_insert_to_pole(&self, torus: Option<TorusCell>) -> Option<TorusCell> {
+        match self.difficulty {
+            2 => Self::torus_rotated_left(torus),
+            3 => Self::torus_flipped(torus),
+            _ => torus,
+        }
+    }
+
+    fn to_insert_from_pole(&self, torus: Option<TorusCell>) -> Option<TorusCell> {
+        match self.difficulty {
+            2 => Self::torus_rotated_right(torus),
+            _ => torus,
+        }
+    }
+
+    fn to_go_down(&self, torus: Option<TorusCell>) -> Option<TorusCell> {
+        match self.difficulty {
+            2 => Self::torus_rotated_left(torus),
+            _ => torus,
+        }
+    }
+
+    fn to_go_up(&self, torus: Option<TorusCell>) -> Option<TorusCell> {
+        match self.difficulty {
+            2 => Self::torus_rotated_right(torus),
+            _ => torus,
+        }
+    }
+
+    fn pole_get_top_torus(&self) -> Option<TorusCell> {
+        let row = self.pole_height.checked_sub(self.num_tori_in_pole)?;
+        match self.pole.get(row).map(|cells| cells[self.pole_pos]) {
+            Some(PoleEntry::Torus(cell)) => self.to_insert_from_pole(Some(cell)),
+            _ => None,
+        }
+    }
+
+    fn pole_insert(&mut self) {
+        let source = self.boxes[BOX_HEIGHT - 1][self.pole_pos];
+        let row = self.pole_height - self.num_tori_in_pole - 1;
+        self.pole[row][self.pole_pos] = match self.to_insert_to_pole(source) {
+            Some(cell) => PoleEntry::Torus(cell),
+            None => PoleEntry::Empty,
+        };
+        self.num_tori_in_pole += 1;
+    }
+
+    fn pole_delete_top(&mut self) {
+        let row = self.pole_height - self.num_tori_in_pole;
+        self.pole[row][self.pole_pos] = PoleEntry::Pole;
+        self.num_tori_in_pole -= 1;
+    }
+
+    fn box_insert_from_pole(&mut self) {
+        let col = self.pole_pos;
+        let count = self.num_tori[col];
+        for offset in 0..count {
+            let row = BOX_HEIGHT - count - 1 + offset;
+            self.boxes[row][col] = self.to_go_up(self.boxes[row + 1][col]);
+        }
+        self.boxes[BOX_HEIGHT - 1][col] = self.pole_get_top_torus();
+        self.num_tori[col] += 1;
+    }
+
+    fn box_remove_torus(&mut self, row: usize, col: usize) {
+        let span = row as i64 + 1 + self.num_tori[col] as i64 - BOX_HEIGHT as i64;
+        let mut offset = 0;
+        while offset < span - 1 {
+            let target = row as i64 - offset;
+            if target >= 1 {
+                let target = target as usize;
+                self.boxes[target][col] = self.to_go_down(self.boxes[target - 1][col]);
+            }
+            offset += 1;
+        }
+        let cleared = row as i64 - (span - 1);
+        if (0..BOX_HEIGHT as i64).contains(&cleared) {
+            self.boxes[cleared as usize][col] = None;
+        }
+        self.num_tori[col] = self.num_tori[col].saturating_sub(1);
+    }
+
+    fn remove_bottom(&mut self, col: usize) {
+        self.box_remove_torus(BOX_HEIGHT - 1, col);
+    }
+
+    fn check_row(&self, row: usize) -> bool {
+        let Some(first) = self.box_get_entry(row, 0) else {
+            return false;
+        };
+        (1..self.num_cols).all(|col| self.box_get_entry(row, col) == Some(first))
+    }
+
+    fn put_melted_torus_row(&mut self, row: usize) {
+        for cell in self.boxes[row].iter_mut().take(self.num_cols).flatten() {
+            cell.angle = MELTED_ANGLE;
+        }
+    }
+
+    fn delete_melted_torus_in_row(&mut self, row: usize) {
+        for col in 0..self.num_cols {
+            if matches!(self.boxes[row][col], Some(cell) if cell.angle == MELTED_ANGLE) {
+                self.box_remove_torus(row, col);
+            }
+        }
+    }
+
+    fn delete_melted_tori(&mut self) {
+        for row in 0..BOX_HEIGHT {
+            self.delete_melted_torus_in_row(row);
+        }
+    }
+
+    fn melt_same_rows(&mut self) {
+        for row in 0..BOX_HEIGHT {
+            if self.check_row(row) {
+                self.score += SCORE_PER_TORUS * self.num_cols as i64;
+                self.rows_melted += 1;
+                self.put_melted_torus_row(row);
+            }
+        }
+    }
+
+    fn increase_time(&mut self) {
+        if self.time % GAUGE_TIME == 0 {
+            self.level_gauge += 1;
+        }
+        self.time += 1;
+    }
+
+    fn increase_level(&mut self) {
+        self.level += 1;
+        if self.pole_height > 2 {
+            self.decrease_pole_height();
+            return;
+        }
+        for row in self.boxes.iter_mut() {
+            row.push(None);
+        }
+        for row in self.pole.iter_mut() {
+            row.push(PoleEntry::Empty);
+        }
+        self.increase_num_cols();
+        self.increase_pole_height();
+    }
+
+    fn increase_num_cols(&mut self) {
+        self.num_cols += 1;
+        if self.num_cols > MAX_COLS {
+            self.set_game_over();
+            return;
+        }
+        self.num_tori.push(0);
+        self.flying_tori.push(None);
+        self.flying_tori_height.push(0);
+        self.flying_tori_waiting.push(0);
+    }
+
+    fn decrease_pole_height(&mut self) {
+        if self.num_tori_in_pole == self.pole_height {
+            if self.num_tori[self.pole_pos] == BOX_HEIGHT {
+                self.set_game_over();
+                return;
+            }
+            if self.num_tori[self.pole_pos] < BOX_HEIGHT && self.num_tori_in_pole > 0 {
+                self.box_insert_from_pole();
+                self.pole_delete_top();
+            }
+        }
+        self.pole_height -= 1;
+        self.pole.remove(0);
+    }
+
+    fn increase_pole_height(&mut self) {
+        let old_height = self.pole_height;
+        let new_height = self.num_cols;
+        let gap = new_height.saturating_sub(old_height);
+        let mut new_pole = vec![vec![PoleEntry::Empty; self.num_cols]; new_height];
+        for (row, cells) in new_pole.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                if row < gap {
+                    if col == self.pole_pos {
+                        *cell = PoleEntry::Pole;
+                    }
+                    continue;
+                }
+                *cell = self
+                    .pole
+                    .get(row - gap)
+                    .and_then(|source| source.get(col))
+                    .copied()
+                    .unwrap_or(PoleEntry::Empty);
+            }
+        }
+        self.pole_height = new_height;
+        self.pole = new_pole;
+    }
+
+    fn has_overflow_column(&self) -> bool {
+        self.num_tori
+            .iter()
+            .take(self.num_cols)
+            .any(|count| *count == BOX_HEIGHT)
+    }
+
+    fn set_game_over(&mut self) {
+        self.game_on = false;
+        if self.game_over_snapshot.is_none() {
+            self.game_over_snapshot = Some(ReplayState {
+                score: self.score,
+                level: self.level,
+                time: self.time,
+                game_on: false,
+            });
+        }
+    }
+}
+
+/// Re-simulates a replay proof and checks it lands on the claimed final state,
+/// mirroring `verifyReplayProof` in the Edge Function.
+pub fn verify_replay_proof(proof: &DailyReplayProof) -> ReplayVerification {
+    if proof.version != 1 {
+        return fail("Unsupported replay proof version.", None, 0);
+    }
+    if proof.difficulty != 1 && proof.difficulty != 2 && proof.difficulty != 3 {
+        return fail("Invalid replay difficulty.", None, 0);
+    }
+    if proof.final_time < 0 {
+        return fail("Invalid replay final time.", None, 0);
+    }
+    if proof.final_time > MAX_REPLAY_FINAL_TIME {
+        return fail("Replay final time exceeds limit.", None, 0);
+    }
+    if proof.final_score < 0 {
+        return fail("Invalid replay final score.", None, 0);
+    }
+    if proof.final_level < 0 {
+        return fail("Invalid replay final level.", None, 0);
+    }
+    if proof.inputs.len() > MAX_REPLAY_INPUTS {
+        return fail("Replay input count exceeds limit.", None, 0);
+    }
+
+    let mut simulator = ReplaySimulator::new(proof.seed, proof.difficulty);
+    let mut last_input_time = -1_i64;
+    for input in &proof.inputs {
+        if input.time < 0 {
+            return fail_with(&simulator, "Replay input time is invalid.");
+        }
+        if !matches!(input.move_dir.as_str(), "left" | "right" | "up" | "down") {
+            return fail_with(&simulator, "Replay input move is invalid.");
+        }
+        if input.time < last_input_time {
+            return fail_with(&simulator, "Replay inputs are out of order.");
+        }
+        if input.time > proof.final_time {
+            return fail_with(&simulator, "Replay input time exceeds final time.");
+        }
+        simulator.step_until(input.time);
+        if !simulator.game_on {
+            return fail_with(&simulator, "Replay ended before all inputs were consumed.");
+        }
+        simulator.apply_move(&input.move_dir);
+        last_input_time = input.time;
+    }
+
+    simulator.step_until(proof.final_time);
+    simulator.finalize_at_current_time();
+    let mut actual = simulator.state();
+    if actual.game_on {
+        if let Some(snapshot) = simulator.step_one_tick_and_capture_game_over_snapshot() {
+            if snapshot.time == proof.final_time
+                && snapshot.score == proof.final_score
+                && snapshot.level == proof.final_level
+            {
+                actual = snapshot;
+            }
+        }
+    }
+
+    let reason = if actual.time != proof.final_time {
+        Some("Replay final time mismatch.")
+    } else if actual.game_on {
+        Some("Replay is still running at final time.")
+    } else if actual.score != proof.final_score {
+        Some("Replay final score mismatch.")
+    } else if actual.level != proof.final_level {
+        Some("Replay final level mismatch.")
+    } else {
+        None
+    };
+
+    ReplayVerification {
+        ok: reason.is_none(),
+        reason,
+        actual,
+        rows_melted: simulator.rows_melted,
+    }
+}
+
+/// Parses a replay proof JSON document and re-simulates it; used by
+/// `torus-server`.
+pub fn verify_replay_json(raw: &str) -> Result<ReplayVerification, String> {
+    let proof = serde_json::from_str::<DailyReplayProof>(raw)
+        .map_err(|error| format!("failed to parse replay proof: {error}"))?;
+    Ok(verify_replay_proof(&proof))
+}
+
+fn fail_with(simulator: &ReplaySimulator, reason: &'static str) -> ReplayVerification {
+    fail(reason, Some(simulator.state()), simulator.rows_melted)
+}
+
+fn fail(reason: &'static str, state: Option<ReplayState>, rows_melted: i64) -> ReplayVerification {
+    ReplayVerification {
+        ok: false,
+        reason: Some(reason),
+        actual: state.unwrap_or(ReplayState {
+            score: 0,
+            level: 0,
+            time: 0,
+            game_on: false,
+        }),
+        rows_melted,
+    }
+}
+
+/// Stable content hash of a replay proof (FNV-1a 64 over its JSON form), used
+/// to recognise the same run when it is presented again.
+pub fn replay_fingerprint(proof: &DailyReplayProof) -> String {
+    let bytes = serde_json::to_vec(proof).unwrap_or_default();
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs recorded from `supabase/functions/verify-score/simulator.ts` by
+    /// `scripts/generate-replay-fixtures.ts`.
+    const PARITY_FIXTURES: &str = include_str!("../tests/fixtures/replay-parity.json");
+
+    #[derive(Deserialize)]
+    struct Fixture {
+        name: String,
+        proof: DailyReplayProof,
+        checks: Vec<Check>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Check {
+        final_time: i64,
+        final_score: i64,
+        final_level: i64,
+        input_count: usize,
+        expected: Expected,
+    }
+
+    #[derive(Deserialize)]
+    struct Expected {
+        ok: bool,
+        reason: Option<String>,
+        actual: ExpectedState,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ExpectedState {
+        score: i64,
+        level: i64,
+        time: i64,
+        game_on: bool,
+    }
+
+    fn fixtures() -> Vec<Fixture> {
+        serde_json::from_str(PARITY_FIXTURES).expect("replay parity fixtures should parse")
+    }
+
+    #[test]
+    fn matches_the_typescript_simulator_on_recorded_runs() {
+        let fixtures = fixtures();
+        assert!(fixtures.iter().any(|fixture| fixture.proof.difficulty == 3));
+        for fixture in fixtures {
+            for check in &fixture.checks {
+                let mut proof = fixture.proof.clone();
+                proof.final_time = check.final_time;
+                proof.final_score = check.final_score;
+                proof.final_level = check.final_level;
+                proof.inputs.truncate(check.input_count);
+
+                let verification = verify_replay_proof(&proof);
+                let expected = &check.expected;
+                let context = format!("{} at {}", fixture.name, check.final_time);
+                assert_eq!(verification.ok, expected.ok, "{context}");
+                assert_eq!(verification.reason, expected.reason.as_deref(), "{context}");
+                assert_eq!(
+                    verification.actual,
+                    ReplayState {
+                        score: expected.actual.score,
+                        level: expected.actual.level,
+                        time: expected.actual.time,
+                        game_on: expected.actual.game_on,
+                    },
+                    "{context}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn recorded_runs_score_and_level_up() {
+        let fixtures = fixtures();
+        assert!(fixtures.iter().any(|fixture| fixture.proof.final_score > 0));
+        assert!(fixtures.iter().all(|fixture| fixture.proof.final_level > 0));
+    }
+
+    #[test]
+    fn an_input_after_game_over_is_refused() {
+        let mut proof = fixtures().remove(0).proof;
+        let last_time = proof.final_time + 1;
+        proof.final_time = last_time;
+        proof.inputs.push(ReplayInputEvent {
+            time: last_time,
+            move_dir: "left".into(),
+        });
+        let verification = verify_replay_proof(&proof);
+        assert_eq!(
+            verification.reason,
+            Some("Replay ended before all inputs were consumed.")
+        );
+    }
+}
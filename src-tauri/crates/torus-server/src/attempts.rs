@@ -0,0 +1,849 @@
+//! Ports of the attempt, submission and history-rank functions in
+//! `supabase/schema.sql`.
+//! Argument checks raise the same exception messages, and every result has
+//! the same JSON shape.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{json, Map, Value};
+use torus_core::challenge_period::{challenge_key_for_day, challenge_key_start_day};
+
+use crate::clock;
+
+const DAILY_MODE: &str = "daily";
+const CLASSIC_MODE: &str = "classic";
+const MAX_FREEZE_TOKENS: i64 = 2;
+/// Ladder used when `badge_tiers` is empty, as in `resources/badge-tiers.json`.
+const BUNDLED_BADGE_TIER_POWERS: std::ops::Range<u32> = 0..10;
+
+#[derive(Debug)]
+pub enum RpcError {
+    /// A `raise exception` in the SQL function; PostgREST reports it as P0001.
+    Raised(&'static str),
+    Storage(rusqlite::Error),
+}
+
+impl From<rusqlite::Error> for RpcError {
+    fn from(error: rusqlite::Error) -> Self {
+        RpcError::Storage(error)
+    }
+}
+
+/// A validated score, passed on by `verify-score` after replay verification.
+pub struct ScoreSubmission<'a> {
+    pub client_uuid: &'a str,
+    pub player_name: &'a str,
+    pub score: i64,
+    pub level: i64,
+    pub created_at: &'a str,
+    pub skill_usage: &'a Value,
+}
+
+struct AttemptRow {
+    id: i64,
+    score: i64,
+    level: i64,
+    attempts_used: i64,
+    active_attempt_token: Option<String>,
+    has_submission: bool,
+}
+
+/// `start_daily_attempt`: resumes the active attempt, or charges a new one
+/// while any are left.
+pub fn start_attempt(connection: &Connection, payload: &Value) -> Result<Value, RpcError> {
+    let client_uuid = text_arg(payload, "p_client_uuid");
+    if client_uuid.chars().count() < 8 {
+        return Err(RpcError::Raised("INVALID_CLIENT_UUID"));
+    }
+    let (mode, challenge_key) = active_period(payload)?;
+    let mut player_name = truncate(
+        payload["p_player_name"]
+            .as_str()
+            .unwrap_or("Pending")
+            .trim(),
+        20,
+    );
+    if player_name.is_empty() {
+        player_name = "Pending".to_string();
+    }
+    let max_attempts = max_attempts(connection, &mode, &challenge_key)?;
+
+    let row = match find_row(connection, &mode, &challenge_key, &client_uuid)? {
+        Some(row) => row,
+        None => {
+            connection.execute(
+                "insert into scores (player_name, client_uuid, score, level, skill_usage, mode,
+                   challenge_key, attempts_used, daily_has_submission, created_at)
+                 values (?1, ?2, 0, 0, '[]', ?3, ?4, 0, 0, ?5)",
+                params![
+                    player_name,
+                    client_uuid,
+                    mode,
+                    challenge_key,
+                    clock::now_timestamp()
+                ],
+            )?;
+            find_row(connection, &mode, &challenge_key, &client_uuid)?
+                .ok_or(RpcError::Storage(rusqlite::Error::QueryReturnedNoRows))?
+        }
+    };
+
+    let attempts_used = row.attempts_used.clamp(0, max_attempts);
+    if let Some(token) = row.active_attempt_token {
+        let mut body = summary(&challenge_key, attempts_used, max_attempts, true);
+        body.insert("accepted".into(), json!(true));
+        body.insert("resumed".into(), json!(true));
+        body.insert("attemptToken".into(), json!(token));
+        return Ok(Value::Object(body));
+    }
+    if attempts_used >= max_attempts {
+        let mut body = summary(&challenge_key, attempts_used, max_attempts, false);
+        body.insert("accepted".into(), json!(false));
+        body.insert("resumed".into(), json!(false));
+        body.insert("attemptToken".into(), Value::Null);
+        return Ok(Value::Object(body));
+    }
+
+    let attempts_used = attempts_used + 1;
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    connection.execute(
+        "update scores
+         set attempts_used = ?1, active_attempt_token = ?2, active_attempt_started_at = ?3
+         where id = ?4",
+        params![attempts_used, token, clock::now_timestamp(), row.id],
+    )?;
+    let mut body = summary(&challenge_key, attempts_used, max_attempts, true);
+    body.insert("accepted".into(), json!(true));
+    body.insert("resumed".into(), json!(false));
+    body.insert("attemptToken".into(), json!(token));
+    Ok(Value::Object(body))
+}
+
+/// `forfeit_daily_attempt` ends the active attempt; `rollback_daily_attempt`
+/// (`refund`) also gives the attempt back.
+pub fn end_attempt(
+    connection: &Connection,
+    payload: &Value,
+    refund: bool,
+) -> Result<Value, RpcError> {
+    let client_uuid = text_arg(payload, "p_client_uuid");
+    if client_uuid.chars().count() < 8 {
+        return Err(RpcError::Raised("INVALID_CLIENT_UUID"));
+    }
+    let token = text_arg(payload, "p_attempt_token");
+    if token.is_empty() {
+        return Err(RpcError::Raised("INVALID_ATTEMPT_TOKEN"));
+    }
+    let (mode, challenge_key) = active_period(payload)?;
+    let max_attempts = max_attempts(connection, &mode, &challenge_key)?;
+
+    let Some(row) = find_row(connection, &mode, &challenge_key, &client_uuid)? else {
+        let mut body = summary(&challenge_key, 0, max_attempts, false);
+        body.insert("accepted".into(), json!(false));
+        return Ok(Value::Object(body));
+    };
+    let mut attempts_used = row.attempts_used.clamp(0, max_attempts);
+    if row.active_attempt_token.as_deref() != Some(token.as_str()) {
+        let has_active_attempt = row.active_attempt_token.is_some();
+        let mut body = summary(
+            &challenge_key,
+            attempts_used,
+            max_attempts,
+            has_active_attempt,
+        );
+        body.insert("accepted".into(), json!(false));
+        return Ok(Value::Object(body));
+    }
+
+    if refund {
+        attempts_used = (attempts_used - 1).max(0);
+    }
+    connection.execute(
+        "update scores
+         set attempts_used = ?1, active_attempt_token = null, active_attempt_started_at = null
+         where id = ?2",
+        params![attempts_used, row.id],
+    )?;
+    let mut body = summary(&challenge_key, attempts_used, max_attempts, false);
+    body.insert("accepted".into(), json!(true));
+    Ok(Value::Object(body))
+}
+
+/// `submit_daily_score`: records a verified run against the active attempt of
+/// any period mode, and updates the streak for Daily.
+pub fn submit_period_score(
+    connection: &Connection,
+    mode: &str,
+    challenge_key: &str,
+    attempt_token: &str,
+    submission: &ScoreSubmission<'_>,
+) -> Result<Value, RpcError> {
+    let client_uuid = submission.client_uuid.trim();
+    if client_uuid.chars().count() < 8 {
+        return Err(RpcError::Raised("INVALID_CLIENT_UUID"));
+    }
+    let player_name = truncate(submission.player_name.trim(), 20);
+    if player_name.is_empty() {
+        return Err(RpcError::Raised("INVALID_PLAYER_NAME"));
+    }
+    let attempt_token = attempt_token.trim();
+    if attempt_token.is_empty() {
+        return Err(RpcError::Raised("INVALID_ATTEMPT_TOKEN"));
+    }
+    let (mode, challenge_key) = check_period(mode, challenge_key)?;
+    let max_attempts = max_attempts(connection, &mode, &challenge_key)?;
+
+    let Some(row) = find_row(connection, &mode, &challenge_key, client_uuid)? else {
+        let mut body = summary(&challenge_key, 0, max_attempts, false);
+        body.insert("accepted".into(), json!(false));
+        body.insert("improved".into(), json!(false));
+        return Ok(Value::Object(body));
+    };
+    let attempts_used = row.attempts_used.clamp(0, max_attempts);
+    if row.active_attempt_token.as_deref() != Some(attempt_token) {
+        let has_active_attempt = row.active_attempt_token.is_some();
+        let mut body = summary(
+            &challenge_key,
+            attempts_used,
+            max_attempts,
+            has_active_attempt,
+        );
+        body.insert("accepted".into(), json!(false));
+        body.insert("improved".into(), json!(false));
+        return Ok(Value::Object(body));
+    }
+
+    let score = submission.score.max(0);
+    let level = submission.level.max(0);
+    let improved = !row.has_submission || (score, level) > (row.score, row.level);
+    connection.execute(
+        "update scores
+         set attempts_used = ?1, active_attempt_token = null, active_attempt_started_at = null,
+           daily_has_submission = 1
+         where id = ?2",
+        params![attempts_used, row.id],
+    )?;
+    if improved {
+        connection.execute(
+            "update scores
+             set player_name = ?1, score = ?2, level = ?3, created_at = ?4, skill_usage = ?5
+             where id = ?6",
+            params![
+                player_name,
+                score,
+                level,
+                submission.created_at,
+                skill_usage_text(submission.skill_usage),
+                row.id
+            ],
+        )?;
+    }
+    // Streaks and freeze tokens only track the Daily period.
+    if mode == DAILY_MODE {
+        record_daily_streak(connection, client_uuid, &challenge_key)?;
+    }
+
+    let mut body = summary(&challenge_key, attempts_used, max_attempts, false);
+    body.insert("accepted".into(), json!(true));
+    body.insert("improved".into(), json!(improved));
+    Ok(Value::Object(body))
+}
+
+/// `submit_global_score`: keeps the best classic run per device and reports
+/// its rank.
+pub fn submit_global_score(
+    connection: &Connection,
+    submission: &ScoreSubmission<'_>,
+) -> Result<Value, RpcError> {
+    let client_uuid = submission.client_uuid.trim();
+    if client_uuid.chars().count() < 8 {
+        return Err(RpcError::Raised("INVALID_CLIENT_UUID"));
+    }
+    let player_name = truncate(submission.player_name.trim(), 20);
+    if player_name.is_empty() {
+        return Err(RpcError::Raised("INVALID_PLAYER_NAME"));
+    }
+    let score = submission.score.max(0);
+    let level = submission.level.max(0);
+    let skill_usage = skill_usage_text(submission.skill_usage);
+
+    let existing = find_row(connection, CLASSIC_MODE, CLASSIC_MODE, client_uuid)?;
+    let previous_best = existing.as_ref().map(|row| (row.score, row.level));
+    let improved = previous_best.is_none_or(|best| (score, level) > best);
+    match &existing {
+        None => {
+            connection.execute(
+                "insert into scores (player_name, client_uuid, score, level, skill_usage, mode,
+                   challenge_key, attempts_used, daily_has_submission, created_at)
+                 values (?1, ?2, ?3, ?4, ?5, 'classic', 'classic', 0, 0, ?6)",
+                params![
+                    player_name,
+                    client_uuid,
+                    score,
+                    level,
+                    skill_usage,
+                    submission.created_at
+                ],
+            )?;
+        }
+        Some(row) if improved => {
+            connection.execute(
+                "update scores
+                 set player_name = ?1, score = ?2, level = ?3, created_at = ?4, skill_usage = ?5,
+                   attempts_used = 0, daily_has_submission = 0, active_attempt_token = null,
+                   active_attempt_started_at = null
+                 where id = ?6",
+                params![
+                    player_name,
+                    score,
+                    level,
+                    submission.created_at,
+                    skill_usage,
+                    row.id
+                ],
+            )?;
+        }
+        Some(_) => {}
+    }
+
+    let (best_score, best_level) = match previous_best {
+        Some(best) if !improved => best,
+        _ => (score, level),
+    };
+    let (ahead, total_players) = connection.query_row(
+        "select
+           count(*) filter (where score > ?1 or (score = ?1 and level > ?2)),
+           count(*)
+         from scores
+         where mode = 'classic' and challenge_key = 'classic'",
+        params![best_score, best_level],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+    )?;
+    Ok(json!({
+        "accepted": true,
+        "improved": improved,
+        "previousBest": previous_best.map(|(score, _)| score),
+        "rank": ahead + 1,
+        "totalPlayers": total_players,
+    }))
+}
+
+/// Extends, freezes or resets the Daily streak for a submission on
+/// `challenge_key`, earning a freeze token per newly reached badge tier.
+fn record_daily_streak(
+    connection: &Connection,
+    client_uuid: &str,
+    challenge_key: &str,
+) -> Result<(), RpcError> {
+    let previous = connection
+        .query_row(
+            "select current_streak, max_streak, last_submission_key, freeze_tokens
+             from daily_streak_states
+             where client_uuid = ?1",
+            params![client_uuid],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    let previous_max = previous.as_ref().map_or(0, |(_, max, _, _)| *max);
+    let (current, max_streak, mut freeze_tokens) = match previous {
+        None => (1, 1, 0),
+        Some((current, max, last_key, tokens)) => {
+            let current = current.max(0);
+            let tokens = tokens.clamp(0, MAX_FREEZE_TOKENS);
+            let gap = last_key.as_deref().and_then(|last| {
+                Some(
+                    challenge_key_start_day(DAILY_MODE, challenge_key)?
+                        - challenge_key_start_day(DAILY_MODE, last)?,
+                )
+            });
+            if last_key.as_deref() == Some(challenge_key) {
+                (current, max.max(current), tokens)
+            } else if gap == Some(1) {
+                (current + 1, max.max(current + 1), tokens)
+            } else if tokens > 0 && gap == Some(2) {
+                // Exactly one missed UTC day: spend a freeze token.
+                (current + 1, max.max(current + 1), tokens - 1)
+            } else {
+                (1, max.max(1), tokens)
+            }
+        }
+    };
+    let new_tiers = badge_tier_days(connection)?
+        .into_iter()
+        .filter(|days| *days > previous_max.max(1) && *days <= max_streak)
+        .count() as i64;
+    freeze_tokens = (freeze_tokens + new_tiers).min(MAX_FREEZE_TOKENS);
+
+    connection.execute(
+        "insert into daily_streak_states
+           (client_uuid, current_streak, max_streak, last_submission_key, freeze_tokens, updated_at)
+         values (?1, ?2, ?3, ?4, ?5, ?6)
+         on conflict (client_uuid) do update set
+           current_streak = excluded.current_streak,
+           max_streak = excluded.max_streak,
+           last_submission_key = excluded.last_submission_key,
+           freeze_tokens = excluded.freeze_tokens,
+           updated_at = excluded.updated_at",
+        params![
+            client_uuid,
+            current,
+            max_streak,
+            challenge_key,
+            freeze_tokens,
+            clock::now_timestamp()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Day thresholds of the badge ladder: `badge_tiers`, or the bundled
+/// `2^0..2^9` ladder when that table is empty.
+fn badge_tier_days(connection: &Connection) -> Result<Vec<i64>, RpcError> {
+    let mut statement = connection.prepare("select days from badge_tiers")?;
+    let days = statement
+        .query_map([], |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    if days.is_empty() {
+        return Ok(BUNDLED_BADGE_TIER_POWERS.map(|power| 1 << power).collect());
+    }
+    Ok(days)
+}
+
+/// `daily_history_ranks`: the caller's rank on each requested daily board
+/// it submitted to.
+pub fn history_ranks(connection: &Connection, payload: &Value) -> Result<Value, RpcError> {
+    let client_uuid = text_arg(payload, "p_client_uuid");
+    let mut statement = connection.prepare(
+        "select mine.challenge_key, 1 + (
+           select count(*) from scores other
+           where other.mode = 'daily'
+             and other.challenge_key = mine.challenge_key
+             and other.daily_has_submission
+             and (other.score, other.level, other.created_at)
+               > (mine.score, mine.level, mine.created_at)
+         )
+         from scores mine
+         where mine.mode = 'daily' and mine.client_uuid = ?1 and mine.challenge_key = ?2
+           and mine.daily_has_submission",
+    )?;
+    let mut ranks = Vec::new();
+    for key in payload["p_challenge_keys"].as_array().into_iter().flatten() {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        let rank = statement
+            .query_row(params![client_uuid, key], |row| {
+                let challenge_key = row.get::<_, String>(0)?;
+                let rank = row.get::<_, i64>(1)?;
+                Ok(json!({ "challenge_key": challenge_key, "rank": rank }))
+            })
+            .optional()?;
+        ranks.extend(rank);
+    }
+    Ok(Value::Array(ranks))
+}
+
+/// Mode and key arguments of the attempt RPCs (`p_mode` defaults to daily).
+fn active_period(payload: &Value) -> Result<(String, String), RpcError> {
+    let mode = payload["p_mode"].as_str().unwrap_or(DAILY_MODE);
+    check_period(mode, &text_arg(payload, "p_challenge_key"))
+}
+
+/// Only the current period of `mode` is open; even the key the client
+/// considers a grace period is refused, as in the SQL functions.
+fn check_period(mode: &str, challenge_key: &str) -> Result<(String, String), RpcError> {
+    let mode = mode.trim().to_ascii_lowercase();
+    let challenge_key = challenge_key.trim().to_string();
+    let today_key =
+        challenge_key_for_day(&mode, clock::today()).ok_or(RpcError::Raised("INVALID_MODE"))?;
+    if !matches_key_pattern(&mode, &challenge_key) {
+        return Err(RpcError::Raised("INVALID_CHALLENGE_KEY"));
+    }
+    if challenge_key != today_key {
+        return Err(RpcError::Raised("CHALLENGE_KEY_MISMATCH"));
+    }
+    Ok((mode, challenge_key))
+}
+
+/// `challenge_key_pattern`: shape only, so impossible dates fall through to
+/// the mismatch check.
+pub fn matches_key_pattern(mode: &str, key: &str) -> bool {
+    let pattern = match mode {
+        "daily" => "0000-00-00",
+        "weekly" => "0000-W00",
+        "monthly" => "0000-00",
+        _ => return false,
+    };
+    key.len() == pattern.len()
+        && key.bytes().zip(pattern.bytes()).all(|(byte, expected)| {
+            if expected == b'0' {
+                byte.is_ascii_digit()
+            } else {
+                byte == expected
+            }
+        })
+}
+
+fn max_attempts(connection: &Connection, mode: &str, challenge_key: &str) -> Result<i64, RpcError> {
+    let configured = connection
+        .query_row(
+            "select max_attempts from challenge_settings where mode = ?1 and challenge_key = ?2",
+            params![mode, challenge_key],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()?;
+    Ok(configured.unwrap_or(match mode {
+        "weekly" => 5,
+        "monthly" => 10,
+        _ => 3,
+    }))
+}
+
+fn find_row(
+    connection: &Connection,
+    mode: &str,
+    challenge_key: &str,
+    client_uuid: &str,
+) -> rusqlite::Result<Option<AttemptRow>> {
+    connection
+        .query_row(
+            "select id, score, level, attempts_used, active_attempt_token, daily_has_submission
+             from scores
+             where mode = ?1 and challenge_key = ?2 and client_uuid = ?3",
+            params![mode, challenge_key, client_uuid],
+            |row| {
+                Ok(AttemptRow {
+                    id: row.get(0)?,
+                    score: row.get(1)?,
+                    level: row.get(2)?,
+                    attempts_used: row.get(3)?,
+                    active_attempt_token: row
+                        .get::<_, Option<String>>(4)?
+                        .map(|token| token.trim().to_string())
+                        .filter(|token| !token.is_empty()),
+                    has_submission: row.get(5)?,
+                })
+            },
+        )
+        .optional()
+}
+
+fn summary(
+    challenge_key: &str,
+    attempts_used: i64,
+    max_attempts: i64,
+    has_active_attempt: bool,
+) -> Map<String, Value> {
+    let attempts_left = (max_attempts - attempts_used).max(0);
+    let mut body = Map::new();
+    body.insert("challengeKey".into(), json!(challenge_key));
+    body.insert("attemptsUsed".into(), json!(attempts_used));
+    body.insert("attemptsLeft".into(), json!(attempts_left));
+    body.insert("maxAttempts".into(), json!(max_attempts));
+    body.insert("canSubmit".into(), json!(attempts_left > 0));
+    body.insert("hasActiveAttempt".into(), json!(has_active_attempt));
+    body
+}
+
+fn text_arg(payload: &Value, name: &str) -> String {
+    payload[name]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+fn truncate(raw: &str, max_chars: usize) -> String {
+    raw.chars().take(max_chars).collect()
+}
+
+fn skill_usage_text(skill_usage: &Value) -> String {
+    if skill_usage.is_array() {
+        skill_usage.to_string()
+    } else {
+        "[]".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+    use std::path::Path;
+
+    const DEVICE: &str = "device-0001";
+
+    fn open() -> Store {
+        Store::open(Path::new(":memory:")).unwrap()
+    }
+
+    fn today_key(mode: &str) -> String {
+        challenge_key_for_day(mode, clock::today()).unwrap()
+    }
+
+    fn attempt_args(mode: &str, token: Option<&Value>) -> Value {
+        let mut args = json!({
+            "p_client_uuid": DEVICE,
+            "p_mode": mode,
+            "p_challenge_key": today_key(mode),
+            "p_player_name": "Tester",
+        });
+        if let Some(token) = token {
+            args["p_attempt_token"] = token.clone();
+        }
+        args
+    }
+
+    fn start(store: &Store, mode: &str) -> Value {
+        store
+            .transaction(|connection| start_attempt(connection, &attempt_args(mode, None)))
+            .unwrap()
+    }
+
+    fn end(store: &Store, mode: &str, token: &Value, refund: bool) -> Value {
+        store
+            .transaction(|connection| {
+                end_attempt(connection, &attempt_args(mode, Some(token)), refund)
+            })
+            .unwrap()
+    }
+
+    fn submit(store: &Store, token: &str, score: i64, level: i64) -> Value {
+        let skill_usage = json!([]);
+        let submission = ScoreSubmission {
+            client_uuid: DEVICE,
+            player_name: "Tester",
+            score,
+            level,
+            created_at: &clock::now_timestamp(),
+            skill_usage: &skill_usage,
+        };
+        store
+            .transaction(|connection| {
+                submit_period_score(connection, "daily", &today_key("daily"), token, &submission)
+            })
+            .unwrap()
+    }
+
+    fn raised(result: Result<Value, RpcError>) -> &'static str {
+        match result {
+            Err(RpcError::Raised(message)) => message,
+            other => panic!("expected a raised exception, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn attempts_resume_and_run_out() {
+        let store = open();
+        let started = start(&store, "daily");
+        assert_eq!(started["accepted"], true);
+        assert_eq!(started["resumed"], false);
+        assert_eq!(started["attemptsUsed"], 1);
+        assert_eq!(started["attemptsLeft"], 2);
+
+        let resumed = start(&store, "daily");
+        assert_eq!(resumed["resumed"], true);
+        assert_eq!(resumed["attemptToken"], started["attemptToken"]);
+        assert_eq!(resumed["attemptsUsed"], 1);
+
+        let stale = end(&store, "daily", &json!("stale"), false);
+        assert_eq!(stale["accepted"], false);
+        assert_eq!(stale["hasActiveAttempt"], true);
+        let forfeited = end(&store, "daily", &started["attemptToken"], false);
+        assert_eq!(forfeited["accepted"], true);
+        assert_eq!(forfeited["hasActiveAttempt"], false);
+
+        for _ in 0..2 {
+            let started = start(&store, "daily");
+            end(&store, "daily", &started["attemptToken"], false);
+        }
+        let refused = start(&store, "daily");
+        assert_eq!(refused["accepted"], false);
+        assert_eq!(refused["canSubmit"], false);
+        assert_eq!(refused["attemptToken"], Value::Null);
+    }
+
+    #[test]
+    fn rollback_refunds_and_settings_set_the_limit() {
+        let store = open();
+        let started = start(&store, "weekly");
+        assert_eq!(started["maxAttempts"], 5);
+        let rolled_back = end(&store, "weekly", &started["attemptToken"], true);
+        assert_eq!(rolled_back["accepted"], true);
+        assert_eq!(rolled_back["attemptsUsed"], 0);
+
+        store
+            .lock()
+            .execute(
+                "insert into challenge_settings (mode, challenge_key, max_attempts)
+                 values ('monthly', ?1, 1)",
+                params![today_key("monthly")],
+            )
+            .unwrap();
+        let started = start(&store, "monthly");
+        assert_eq!(started["maxAttempts"], 1);
+        assert_eq!(started["canSubmit"], false);
+        end(&store, "monthly", &started["attemptToken"], false);
+        assert_eq!(start(&store, "monthly")["accepted"], false);
+    }
+
+    #[test]
+    fn attempt_arguments_are_checked() {
+        let store = open();
+        let connection = store.lock();
+        let mut args = attempt_args("daily", None);
+        args["p_client_uuid"] = json!("short");
+        assert_eq!(
+            raised(start_attempt(&connection, &args)),
+            "INVALID_CLIENT_UUID"
+        );
+
+        let cases = [
+            ("yearly", today_key("daily"), "INVALID_MODE"),
+            ("daily", today_key("weekly"), "INVALID_CHALLENGE_KEY"),
+            ("daily", "1999-01-01".to_string(), "CHALLENGE_KEY_MISMATCH"),
+        ];
+        for (mode, key, expected) in cases {
+            let mut args = attempt_args("daily", None);
+            args["p_mode"] = json!(mode);
+            args["p_challenge_key"] = json!(key);
+            assert_eq!(
+                raised(start_attempt(&connection, &args)),
+                expected,
+                "{mode} {key}"
+            );
+        }
+
+        let args = attempt_args("daily", Some(&json!("")));
+        assert_eq!(
+            raised(end_attempt(&connection, &args, false)),
+            "INVALID_ATTEMPT_TOKEN"
+        );
+    }
+
+    #[test]
+    fn period_scores_keep_the_best_run() {
+        let store = open();
+        let token = start(&store, "daily")["attemptToken"].clone();
+        assert_eq!(submit(&store, "stale", 300, 4)["accepted"], false);
+        let first = submit(&store, token.as_str().unwrap(), 300, 4);
+        assert_eq!(first["accepted"], true);
+        assert_eq!(first["improved"], true);
+        assert_eq!(first["hasActiveAttempt"], false);
+
+        let token = start(&store, "daily")["attemptToken"].clone();
+        let worse = submit(&store, token.as_str().unwrap(), 300, 3);
+        assert_eq!(worse["accepted"], true);
+        assert_eq!(worse["improved"], false);
+
+        let (score, level) = store
+            .lock()
+            .query_row(
+                "select score, level from scores where mode = 'daily'",
+                [],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .unwrap();
+        assert_eq!((score, level), (300, 4));
+    }
+
+    #[test]
+    fn daily_streaks_spend_a_freeze_token_on_one_missed_day() {
+        let store = open();
+        let two_days_ago = challenge_key_for_day("daily", clock::today() - 2).unwrap();
+        store
+            .lock()
+            .execute(
+                "insert into daily_streak_states
+                   (client_uuid, current_streak, max_streak, last_submission_key, freeze_tokens,
+                    updated_at)
+                 values (?1, 4, 4, ?2, 1, '')",
+                params![DEVICE, two_days_ago],
+            )
+            .unwrap();
+        let token = start(&store, "daily")["attemptToken"].clone();
+        submit(&store, token.as_str().unwrap(), 300, 4);
+
+        let (current, max, tokens) = store
+            .lock()
+            .query_row(
+                "select current_streak, max_streak, freeze_tokens from daily_streak_states",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                    ))
+                },
+            )
+            .unwrap();
+        // Reaching 5 days crosses no bundled tier (1, 2, 4, 8, ...), so no token is earned back.
+        assert_eq!((current, max, tokens), (5, 5, 0));
+    }
+
+    #[test]
+    fn global_scores_rank_the_best_run_per_device() {
+        let store = open();
+        let skill_usage = json!([]);
+        let run = |client_uuid, score, level| {
+            let submission = ScoreSubmission {
+                client_uuid,
+                player_name: "Tester",
+                score,
+                level,
+                created_at: "2026-01-01T00:00:00.000+00:00",
+                skill_usage: &skill_usage,
+            };
+            store
+                .transaction(|connection| submit_global_score(connection, &submission))
+                .unwrap()
+        };
+        run("device-rival", 500, 9);
+        let first = run(DEVICE, 300, 4);
+        assert_eq!(first["improved"], true);
+        assert_eq!(first["previousBest"], Value::Null);
+        assert_eq!(
+            (first["rank"].clone(), first["totalPlayers"].clone()),
+            (json!(2), json!(2))
+        );
+
+        let worse = run(DEVICE, 200, 9);
+        assert_eq!(worse["improved"], false);
+        assert_eq!(worse["previousBest"], 300);
+        assert_eq!(worse["rank"], 2);
+        let best = run(DEVICE, 600, 1);
+        assert_eq!(best["rank"], 1);
+    }
+
+    #[test]
+    fn history_ranks_cover_submitted_days_only() {
+        let store = open();
+        let key = today_key("daily");
+        let token = start(&store, "daily")["attemptToken"].clone();
+        submit(&store, token.as_str().unwrap(), 300, 4);
+        store
+            .lock()
+            .execute(
+                "insert into scores (player_name, client_uuid, score, level, mode, challenge_key,
+                   daily_has_submission, created_at)
+                 values ('Rival', 'device-rival', 500, 9, 'daily', ?1, 1, '')",
+                params![key],
+            )
+            .unwrap();
+
+        let ranks = history_ranks(
+            &store.lock(),
+            &json!({ "p_client_uuid": DEVICE, "p_challenge_keys": [key, "2001-01-01", 7] }),
+        )
+        .unwrap();
+        assert_eq!(ranks, json!([{ "challenge_key": key, "rank": 2 }]));
+    }
+}
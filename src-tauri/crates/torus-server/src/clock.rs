@@ -0,0 +1,147 @@
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use torus_core::calendar::{civil_from_days, days_from_civil};
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Current UTC day as a Unix day number, the clock every period key uses.
+pub fn today() -> i64 {
+    now_millis().div_euclid(MILLIS_PER_DAY)
+}
+
+/// `timestamptz` text as PostgREST renders it, e.g.
+/// `2025-03-01T12:34:56.789+00:00`. Fixed width, so it also sorts correctly.
+pub fn timestamp(millis: i64) -> String {
+    let (year, month, day) = civil_from_days(millis.div_euclid(MILLIS_PER_DAY));
+    let clock = millis.rem_euclid(MILLIS_PER_DAY);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}+00:00",
+        clock / 3_600_000,
+        clock / 60_000 % 60,
+        clock / 1_000 % 60,
+        clock % 1_000
+    )
+}
+
+pub fn now_timestamp() -> String {
+    timestamp(now_millis())
+}
+
+/// Parses the ISO 8601 forms Postgres accepts for `timestamptz` input
+/// (`YYYY-MM-DD[( |T)HH:MM[:SS[.fff]]][Z|±HH[:MM]]`), assuming UTC when no
+/// offset is given. Returns Unix milliseconds.
+pub fn parse_timestamp(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    let (year, month, day) = (digits(raw, 0..4)?, digits(raw, 5..7)?, digits(raw, 8..10)?);
+    if raw.get(4..5)? != "-" || raw.get(7..8)? != "-" {
+        return None;
+    }
+    if !(1..=12).contains(&month) || day < 1 {
+        return None;
+    }
+    let days = days_from_civil(year as i32, month as u32, day as u32);
+    if civil_from_days(days) != (year, month as u32, day as u32) {
+        return None;
+    }
+
+    let mut millis = days * MILLIS_PER_DAY;
+    let mut rest = &raw[10..];
+    if let Some(time) = rest.strip_prefix(['T', 't', ' ']) {
+        let hours = digits(time, 0..2)?;
+        let minutes = digits(time, 3..5)?;
+        if time.get(2..3)? != ":" || hours > 23 || minutes > 59 {
+            return None;
+        }
+        millis += (hours * 60 + minutes) * 60_000;
+        rest = &time[5..];
+        if let Some(after) = rest.strip_prefix(':') {
+            let seconds = digits(after, 0..2)?;
+            if seconds > 59 {
+                return None;
+            }
+            millis += seconds * 1_000;
+            rest = &after[2..];
+            if let Some(fraction) = rest.strip_prefix('.') {
+                let length = fraction
+                    .bytes()
+                    .take_while(|byte| byte.is_ascii_digit())
+                    .count();
+                if length == 0 {
+                    return None;
+                }
+                let padded = format!("{:0<3}", &fraction[..length.min(3)]);
+                millis += padded.parse::<i64>().ok()?;
+                rest = &fraction[length..];
+            }
+        }
+    }
+
+    match rest {
+        "" | "Z" | "z" => Some(millis),
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let offset = rest[1..].replace(':', "");
+            let (hours, minutes) = match offset.len() {
+                2 => (digits(&offset, 0..2)?, 0),
+                4 => (digits(&offset, 0..2)?, digits(&offset, 2..4)?),
+                _ => return None,
+            };
+            if minutes > 59 {
+                return None;
+            }
+            Some(millis - sign * (hours * 60 + minutes) * 60_000)
+        }
+    }
+}
+
+fn digits(text: &str, range: Range<usize>) -> Option<i64> {
+    let digits = text.get(range)?;
+    if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_client_and_postgres_timestamps() {
+        let expected = 1_740_832_496_789;
+        for raw in [
+            "2025-03-01T12:34:56.789Z",
+            "2025-03-01 12:34:56.789+00:00",
+            "2025-03-01T14:34:56.789123+02:00",
+        ] {
+            assert_eq!(parse_timestamp(raw), Some(expected), "{raw}");
+        }
+        assert_eq!(parse_timestamp("2025-03-01"), Some(1_740_787_200_000));
+        assert_eq!(timestamp(expected), "2025-03-01T12:34:56.789+00:00");
+    }
+
+    #[test]
+    fn rejects_malformed_timestamps() {
+        for raw in [
+            "",
+            "bogus",
+            "2025-02-30",
+            "2025-03-01T25:00",
+            "2025-03-01T12:00+5",
+        ] {
+            assert_eq!(parse_timestamp(raw), None, "{raw}");
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Self-hostable leaderboard server implementing the REST contract the app
+//! uses against Supabase, backed by SQLite.
+//!
+//! Serves the PostgREST reads of `scores`, `daily_streak_states`,
+//! `challenge_settings` and `badge_tiers`, the `start/forfeit/rollback_daily_attempt`
+//! and `daily_history_ranks` RPCs and the `verify-score` function, which
+//! re-simulates replays with the same engine as the client.
+
+mod attempts;
+mod clock;
+mod rest;
+mod store;
+mod verify;
+
+use std::io::{Cursor, Read};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use attempts::RpcError;
+pub use store::Store;
+
+const VERIFY_SCORE_PATH: &str = "/functions/v1/verify-score";
+const RPC_PATH_PREFIX: &str = "/rest/v1/rpc/";
+const TABLE_PATH_PREFIX: &str = "/rest/v1/";
+/// Replay proofs cap out at 20k inputs, well under this.
+const MAX_BODY_BYTES: u64 = 4 * 1024 * 1024;
+
+/// A listening server whose workers answer requests against one `Store`.
+pub struct TorusServer {
+    address: SocketAddr,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl TorusServer {
+    /// Binds `bind` and starts `workers` threads. Requests must carry
+    /// `anon_key` as both `apikey` and bearer token, as on Supabase.
+    pub fn start(
+        bind: &str,
+        store: Arc<Store>,
+        anon_key: &str,
+        workers: usize,
+    ) -> Result<Self, String> {
+        let server =
+            Server::http(bind).map_err(|error| format!("failed to listen on {bind}: {error}"))?;
+        let address = server
+            .server_addr()
+            .to_ip()
+            .ok_or_else(|| format!("{bind} is not a TCP address"))?;
+        let server = Arc::new(server);
+        let anon_key = Arc::<str>::from(anon_key);
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let server = Arc::clone(&server);
+                let store = Arc::clone(&store);
+                let anon_key = Arc::clone(&anon_key);
+                thread::spawn(move || loop {
+                    match server.recv() {
+                        Ok(request) => handle(&store, &anon_key, request),
+                        Err(error) => {
+                            eprintln!("failed to accept request: {error}");
+                            return;
+                        }
+                    }
+                })
+            })
+            .collect();
+        Ok(TorusServer { address, workers })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Blocks until every worker has stopped.
+    pub fn join(self) {
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// A response body plus the CORS headers the Edge Function sends.
+pub struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+    headers: Vec<(&'static str, String)>,
+}
+
+impl Reply {
+    pub fn json(status: u16, body: Value) -> Self {
+        Reply {
+            status,
+            content_type: "application/json; charset=utf-8",
+            body: body.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// PostgREST's error shape.
+    pub fn postgrest_error(status: u16, code: &str, message: impl Into<String>) -> Self {
+        Reply::json(
+            status,
+            json!({ "code": code, "message": message.into(), "details": null, "hint": null }),
+        )
+    }
+
+    pub fn storage_error(error: rusqlite::Error) -> Self {
+        Reply::postgrest_error(500, "XX000", error.to_string())
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: String) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    fn into_response(self) -> Response<Cursor<Vec<u8>>> {
+        let mut response =
+            Response::from_data(self.body.into_bytes()).with_status_code(self.status);
+        let headers = [
+            ("Content-Type", self.content_type.to_string()),
+            ("Access-Control-Allow-Origin", "*".to_string()),
+            (
+                "Access-Control-Allow-Headers",
+                "authorization, x-client-info, apikey, content-type, prefer".to_string(),
+            ),
+        ];
+        for (name, value) in headers.into_iter().chain(self.headers) {
+            if let Ok(header) = Header::from_bytes(name.as_bytes(), value.as_bytes()) {
+                response.add_header(header);
+            }
+        }
+        response
+    }
+}
+
+impl From<RpcError> for Reply {
+    fn from(error: RpcError) -> Self {
+        match error {
+            RpcError::Raised(message) => Reply::postgrest_error(400, "P0001", message),
+            RpcError::Storage(error) => Reply::storage_error(error),
+        }
+    }
+}
+
+fn handle(store: &Store, anon_key: &str, mut request: Request) {
+    let reply = route(store, anon_key, &mut request);
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    println!("{} {path} {}", request.method(), reply.status);
+    // The client may have hung up already; nothing left to do then.
+    let _ = request.respond(reply.into_response());
+}
+
+fn route(store: &Store, anon_key: &str, request: &mut Request) -> Reply {
+    let method = request.method().clone();
+    if method == Method::Options {
+        return Reply {
+            status: 200,
+            content_type: "text/plain; charset=utf-8",
+            body: "ok".to_string(),
+            headers: Vec::new(),
+        };
+    }
+
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(name))
+            .map(|header| header.value.as_str().to_string())
+    };
+    let Some(apikey) = header("apikey") else {
+        return Reply::json(401, json!({ "message": "No API key found in request" }));
+    };
+    if apikey != anon_key || header("authorization") != Some(format!("Bearer {anon_key}")) {
+        return Reply::json(401, json!({ "message": "Invalid API key" }));
+    }
+    let count_exact = header("prefer").is_some_and(|value| value.contains("count=exact"));
+
+    let url = request.url().to_string();
+    let (path, raw_query) = url.split_once('?').unwrap_or((&url, ""));
+    if path == VERIFY_SCORE_PATH {
+        if method != Method::Post {
+            return Reply::json(405, json!({ "error": "METHOD_NOT_ALLOWED" }));
+        }
+        return match read_body(request) {
+            Ok(body) => verify::verify_score(store, &body),
+            Err(reply) => reply,
+        };
+    }
+    if let Some(function) = path.strip_prefix(RPC_PATH_PREFIX) {
+        if method != Method::Post {
+            return Reply::postgrest_error(405, "PGRST101", "only POST is supported for RPCs");
+        }
+        let body = match read_body(request) {
+            Ok(body) => body,
+            Err(reply) => return reply,
+        };
+        let Ok(payload) = serde_json::from_str::<Value>(&body) else {
+            return Reply::postgrest_error(400, "PGRST102", "Empty or invalid json");
+        };
+        let result = match function {
+            "start_daily_attempt" => {
+                store.transaction(|connection| attempts::start_attempt(connection, &payload))
+            }
+            "forfeit_daily_attempt" => {
+                store.transaction(|connection| attempts::end_attempt(connection, &payload, false))
+            }
+            "rollback_daily_attempt" => {
+                store.transaction(|connection| attempts::end_attempt(connection, &payload, true))
+            }
+            "daily_history_ranks" => attempts::history_ranks(&store.lock(), &payload),
+            _ => {
+                return Reply::postgrest_error(
+                    404,
+                    "PGRST202",
+                    format!("Could not find the function public.{function} in the schema cache"),
+                );
+            }
+        };
+        return match result {
+            Ok(body) => Reply::json(200, body),
+            Err(error) => error.into(),
+        };
+    }
+    if let Some(table) = path.strip_prefix(TABLE_PATH_PREFIX) {
+        if method != Method::Get && method != Method::Head {
+            return rest::reject_write(table);
+        }
+        let query = parse_query(raw_query);
+        return rest::select(&store.lock(), table, &query, count_exact);
+    }
+    Reply::json(404, json!({ "message": format!("no route for {path}") }))
+}
+
+fn read_body(request: &mut Request) -> Result<String, Reply> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|error| {
+            Reply::json(
+                400,
+                json!({ "message": format!("unreadable body: {error}") }),
+            )
+        })?;
+    Ok(body)
+}
+
+fn parse_query(raw: &str) -> Vec<(String, String)> {
+    raw.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' if index + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        index += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,84 @@
+//! Usage: `torus-server --anon-key <key> [--bind <addr>] [--db <path>] [--workers <n>]`
+//!
+//! Point `VITE_SUPABASE_URL` at `http://<host>:<port>` and
+//! `VITE_SUPABASE_ANON_KEY` at the key given here.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::thread;
+
+use torus_server::{Store, TorusServer};
+
+const DEFAULT_BIND: &str = "0.0.0.0:54321";
+const DEFAULT_DB_PATH: &str = "torus-server.sqlite3";
+const ANON_KEY_ENV: &str = "TORUS_SERVER_ANON_KEY";
+const USAGE: &str =
+    "usage: torus-server --anon-key <key> [--bind <addr>] [--db <path>] [--workers <n>]";
+
+fn main() -> ExitCode {
+    let mut bind = DEFAULT_BIND.to_string();
+    let mut db_path = PathBuf::from(DEFAULT_DB_PATH);
+    let mut anon_key = std::env::var(ANON_KEY_ENV).ok();
+    let mut workers = thread::available_parallelism().map_or(4, |count| count.get());
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                println!("The anon key may also be set with {ANON_KEY_ENV}.");
+                return ExitCode::SUCCESS;
+            }
+            "--bind" | "--db" | "--anon-key" | "--workers" => args.next(),
+            _ => {
+                eprintln!("unknown argument {arg}\n{USAGE}");
+                return ExitCode::from(2);
+            }
+        };
+        let Some(value) = value else {
+            eprintln!("{arg} needs a value\n{USAGE}");
+            return ExitCode::from(2);
+        };
+        match arg.as_str() {
+            "--bind" => bind = value,
+            "--db" => db_path = PathBuf::from(value),
+            "--anon-key" => anon_key = Some(value),
+            _ => match value.parse::<usize>() {
+                Ok(count) if count > 0 => workers = count,
+                _ => {
+                    eprintln!("--workers needs a positive number");
+                    return ExitCode::from(2);
+                }
+            },
+        }
+    }
+    let Some(anon_key) = anon_key
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    else {
+        eprintln!("an anon key is required (--anon-key or {ANON_KEY_ENV})\n{USAGE}");
+        return ExitCode::from(2);
+    };
+
+    let store = match Store::open(&db_path) {
+        Ok(store) => Arc::new(store),
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let server = match TorusServer::start(&bind, store, &anon_key, workers) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "torus-server listening on http://{} (database {})",
+        server.address(),
+        db_path.display()
+    );
+    server.join();
+    ExitCode::FAILURE
+}
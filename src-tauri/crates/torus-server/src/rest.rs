@@ -0,0 +1,650 @@
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection, Row};
+use serde_json::{Map, Value};
+
+use crate::Reply;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Integer,
+    Text,
+    Boolean,
+    Json,
+}
+
+struct Table {
+    name: &'static str,
+    columns: &'static [(&'static str, Kind)],
+}
+
+const TABLES: &[Table] = &[
+    Table {
+        name: "scores",
+        columns: &[
+            ("id", Kind::Integer),
+            ("player_name", Kind::Text),
+            ("client_uuid", Kind::Text),
+            ("score", Kind::Integer),
+            ("level", Kind::Integer),
+            ("skill_usage", Kind::Json),
+            ("mode", Kind::Text),
+            ("challenge_key", Kind::Text),
+            ("attempts_used", Kind::Integer),
+            ("daily_has_submission", Kind::Boolean),
+            ("active_attempt_token", Kind::Text),
+            ("active_attempt_started_at", Kind::Text),
+            ("created_at", Kind::Text),
+        ],
+    },
+    Table {
+        name: "daily_streak_states",
+        columns: &[
+            ("client_uuid", Kind::Text),
+            ("current_streak", Kind::Integer),
+            ("max_streak", Kind::Integer),
+            ("last_submission_key", Kind::Text),
+            ("freeze_tokens", Kind::Integer),
+            ("updated_at", Kind::Text),
+        ],
+    },
+    Table {
+        name: "challenge_settings",
+        columns: &[
+            ("mode", Kind::Text),
+            ("challenge_key", Kind::Text),
+            ("max_attempts", Kind::Integer),
+        ],
+    },
+    Table {
+        name: "badge_tiers",
+        columns: &[
+            ("power", Kind::Integer),
+            ("days", Kind::Integer),
+            ("name", Kind::Text),
+            ("icon_id", Kind::Text),
+        ],
+    },
+];
+
+impl Table {
+    fn find(name: &str) -> Result<&'static Table, Reply> {
+        TABLES
+            .iter()
+            .find(|table| table.name == name)
+            .ok_or_else(|| {
+                Reply::postgrest_error(
+                    404,
+                    "PGRST205",
+                    format!("Could not find the table 'public.{name}' in the schema cache"),
+                )
+            })
+    }
+
+    fn column(&self, name: &str) -> Result<(&'static str, Kind), Reply> {
+        self.columns
+            .iter()
+            .find(|(column, _)| *column == name)
+            .copied()
+            .ok_or_else(|| {
+                Reply::postgrest_error(
+                    400,
+                    "42703",
+                    format!("column {}.{name} does not exist", self.name),
+                )
+            })
+    }
+}
+
+/// Rows are written only through the RPCs and `verify-score`, as RLS enforces
+/// on Supabase.
+pub fn reject_write(table: &str) -> Reply {
+    match Table::find(table) {
+        Ok(table) => Reply::postgrest_error(
+            401,
+            "42501",
+            format!("permission denied for table {}", table.name),
+        ),
+        Err(reply) => reply,
+    }
+}
+
+/// PostgREST reads: `col=op.value` filters, `or=(...)`, `select`, `order`,
+/// `limit`, `offset` and `Prefer: count=exact`.
+pub fn select(
+    connection: &Connection,
+    table: &str,
+    query: &[(String, String)],
+    count_exact: bool,
+) -> Reply {
+    run_select(connection, table, query, count_exact).unwrap_or_else(|reply| reply)
+}
+
+fn run_select(
+    connection: &Connection,
+    table: &str,
+    query: &[(String, String)],
+    count_exact: bool,
+) -> Result<Reply, Reply> {
+    let table = Table::find(table)?;
+    let mut columns = table.columns.to_vec();
+    let mut filters = Vec::new();
+    let mut order = None;
+    let mut limit = None;
+    let mut offset = 0;
+    for (key, value) in query {
+        match key.as_str() {
+            "select" if value.trim() != "*" => {
+                columns = value
+                    .split(',')
+                    .map(|column| table.column(column.trim()))
+                    .collect::<Result<_, _>>()?;
+            }
+            "select" => {}
+            "order" => order = Some(parse_order(table, value)?),
+            "limit" => limit = Some(parse_count("limit", value)?),
+            "offset" => offset = parse_count("offset", value)?,
+            "or" => {
+                let list = value
+                    .strip_prefix('(')
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .ok_or_else(|| parse_error(key, value))?;
+                filters.push(Condition::Any(parse_condition_list(table, list)?));
+            }
+            column => filters.push(parse_filter(table, column, value)?),
+        }
+    }
+
+    let mut params = Vec::new();
+    let where_clause = Condition::All(filters).to_sql(&mut params);
+    let column_list = columns
+        .iter()
+        .map(|(name, _)| format!("\"{name}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sql = format!(
+        "select {column_list} from \"{}\" where {where_clause}",
+        table.name
+    );
+    if let Some(order) = order {
+        sql.push_str(&format!(" order by {order}"));
+    }
+    sql.push_str(&format!(
+        " limit {} offset {offset}",
+        limit.map_or(-1, |limit| limit as i64)
+    ));
+
+    let rows = connection
+        .prepare(&sql)
+        .and_then(|mut statement| {
+            statement
+                .query_map(params_from_iter(params.iter()), |row| {
+                    read_row(row, &columns)
+                })?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(Reply::storage_error)?;
+
+    let total = if count_exact {
+        let count_sql = format!(
+            "select count(*) from \"{}\" where {where_clause}",
+            table.name
+        );
+        let total = connection
+            .query_row(&count_sql, params_from_iter(params.iter()), |row| {
+                row.get::<_, i64>(0)
+            })
+            .map_err(Reply::storage_error)?;
+        total.to_string()
+    } else {
+        "*".to_string()
+    };
+    let range = if rows.is_empty() {
+        format!("*/{total}")
+    } else {
+        format!("{offset}-{}/{total}", offset + rows.len() - 1)
+    };
+    Ok(Reply::json(200, Value::Array(rows)).with_header("Content-Range", range))
+}
+
+fn read_row(row: &Row<'_>, columns: &[(&'static str, Kind)]) -> rusqlite::Result<Value> {
+    let mut object = Map::new();
+    for (index, (name, kind)) in columns.iter().enumerate() {
+        let value = match kind {
+            Kind::Integer => row.get::<_, Option<i64>>(index)?.into(),
+            Kind::Text => row.get::<_, Option<String>>(index)?.into(),
+            Kind::Boolean => row.get::<_, Option<bool>>(index)?.into(),
+            Kind::Json => row
+                .get::<_, Option<String>>(index)?
+                .map(|raw| serde_json::from_str(&raw).unwrap_or(Value::String(raw)))
+                .unwrap_or(Value::Null),
+        };
+        object.insert(name.to_string(), value);
+    }
+    Ok(Value::Object(object))
+}
+
+enum Condition {
+    Compare {
+        column: &'static str,
+        operator: &'static str,
+        value: SqlValue,
+    },
+    IsNull {
+        column: &'static str,
+    },
+    Any(Vec<Condition>),
+    All(Vec<Condition>),
+}
+
+impl Condition {
+    fn to_sql(&self, params: &mut Vec<SqlValue>) -> String {
+        match self {
+            Condition::Compare {
+                column,
+                operator,
+                value,
+            } => {
+                params.push(value.clone());
+                format!("\"{column}\" {operator} ?")
+            }
+            Condition::IsNull { column } => format!("\"{column}\" is null"),
+            Condition::Any(items) if items.is_empty() => "0".to_string(),
+            Condition::All(items) if items.is_empty() => "1".to_string(),
+            Condition::Any(items) => join_sql(items, " or ", params),
+            Condition::All(items) => join_sql(items, " and ", params),
+        }
+    }
+}
+
+fn join_sql(items: &[Condition], separator: &str, params: &mut Vec<SqlValue>) -> String {
+    let parts = items
+        .iter()
+        .map(|item| item.to_sql(params))
+        .collect::<Vec<_>>();
+    format!("({})", parts.join(separator))
+}
+
+/// `op.value` on `column`, including `in.(...)` lists and `is.null`.
+fn parse_filter(table: &Table, column: &str, raw: &str) -> Result<Condition, Reply> {
+    let (column, kind) = table.column(column)?;
+    let (operator, value) = raw
+        .split_once('.')
+        .ok_or_else(|| parse_error(column, raw))?;
+    let operator = match operator {
+        "eq" => "=",
+        "neq" => "<>",
+        "gt" => ">",
+        "gte" => ">=",
+        "lt" => "<",
+        "lte" => "<=",
+        "in" => {
+            let list = value
+                .strip_prefix('(')
+                .and_then(|rest| rest.strip_suffix(')'))
+                .ok_or_else(|| parse_error(column, raw))?;
+            return split_top_level(list)
+                .iter()
+                .map(|item| {
+                    Ok(Condition::Compare {
+                        column,
+                        operator: "=",
+                        value: literal(column, kind, &unquote(item))?,
+                    })
+                })
+                .collect::<Result<_, _>>()
+                .map(Condition::Any);
+        }
+        "is" if value == "null" => return Ok(Condition::IsNull { column }),
+        "is" if kind == Kind::Boolean => "=",
+        _ => return Err(parse_error(column, raw)),
+    };
+    Ok(Condition::Compare {
+        column,
+        operator,
+        value: literal(column, kind, &unquote(value))?,
+    })
+}
+
+/// Items of an `or=(...)` list: `col.op.value`, `and(...)` or `or(...)`.
+fn parse_condition_list(table: &Table, raw: &str) -> Result<Vec<Condition>, Reply> {
+    split_top_level(raw)
+        .iter()
+        .map(|item| {
+            if let Some(inner) = item
+                .strip_prefix("and(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                return parse_condition_list(table, inner).map(Condition::All);
+            }
+            if let Some(inner) = item
+                .strip_prefix("or(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                return parse_condition_list(table, inner).map(Condition::Any);
+            }
+            let (column, rest) = item
+                .split_once('.')
+                .ok_or_else(|| parse_error("or", item))?;
+            parse_filter(table, column, rest)
+        })
+        .collect()
+}
+
+/// Converts a filter literal to the column's storage type, failing the way
+/// Postgres rejects a bad cast.
+fn literal(column: &str, kind: Kind, raw: &str) -> Result<SqlValue, Reply> {
+    let invalid = |type_name: &str| {
+        Reply::postgrest_error(
+            400,
+            "22P02",
+            format!("invalid input syntax for type {type_name}: \"{raw}\" (column {column})"),
+        )
+    };
+    match kind {
+        Kind::Text => Ok(SqlValue::Text(raw.to_string())),
+        Kind::Integer => raw
+            .parse::<i64>()
+            .map(SqlValue::Integer)
+            .map_err(|_| invalid("integer")),
+        Kind::Boolean => match raw {
+            "true" => Ok(SqlValue::Integer(1)),
+            "false" => Ok(SqlValue::Integer(0)),
+            _ => Err(invalid("boolean")),
+        },
+        Kind::Json => Err(Reply::postgrest_error(
+            400,
+            "42883",
+            format!("operator does not exist: jsonb filter on {column}"),
+        )),
+    }
+}
+
+/// `col[.asc|.desc][.nullsfirst|.nullslast]`, comma separated. Defaults match
+/// Postgres: NULLs sort last ascending and first descending.
+fn parse_order(table: &Table, raw: &str) -> Result<String, Reply> {
+    raw.split(',')
+        .map(|item| {
+            let mut parts = item.trim().split('.');
+            let (column, _) = table.column(parts.next().unwrap_or_default())?;
+            let mut descending = false;
+            let mut nulls_first = None;
+            for modifier in parts {
+                match modifier {
+                    "asc" => descending = false,
+                    "desc" => descending = true,
+                    "nullsfirst" => nulls_first = Some(true),
+                    "nullslast" => nulls_first = Some(false),
+                    _ => return Err(parse_error("order", raw)),
+                }
+            }
+            Ok(format!(
+                "\"{column}\" {} nulls {}",
+                if descending { "desc" } else { "asc" },
+                if nulls_first.unwrap_or(descending) {
+                    "first"
+                } else {
+                    "last"
+                }
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|items| items.join(", "))
+}
+
+fn parse_count(key: &str, raw: &str) -> Result<usize, Reply> {
+    raw.trim()
+        .parse::<usize>()
+        .map_err(|_| parse_error(key, raw))
+}
+
+fn parse_error(key: &str, raw: &str) -> Reply {
+    Reply::postgrest_error(
+        400,
+        "PGRST100",
+        format!("failed to parse filter ({key}={raw})"),
+    )
+}
+
+/// Splits on commas outside parentheses and double quotes.
+fn split_top_level(raw: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for ch in raw.chars() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' && quoted {
+            escaped = true;
+        } else if ch == '"' {
+            quoted = !quoted;
+        } else if !quoted && ch == '(' {
+            depth += 1;
+        } else if !quoted && ch == ')' {
+            depth -= 1;
+        } else if !quoted && depth == 0 && ch == ',' {
+            items.push(std::mem::take(&mut current));
+            continue;
+        }
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        items.push(current);
+    }
+    items
+}
+
+fn unquote(raw: &str) -> String {
+    let Some(inner) = raw
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return raw.to_string();
+    };
+    let mut value = String::new();
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            value.extend(chars.next());
+        } else {
+            value.push(ch);
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+    use rusqlite::params;
+    use std::path::Path;
+
+    fn store_with_scores() -> Store {
+        let store = Store::open(Path::new(":memory:")).unwrap();
+        let rows = [
+            ("Ada", "device-a", 500, 9, "daily", "2026-01-01", true, None),
+            (
+                "Bob",
+                "device-b",
+                300,
+                4,
+                "daily",
+                "2026-01-01",
+                true,
+                Some("token"),
+            ),
+            (
+                "Cy, Jr.",
+                "device-c",
+                300,
+                6,
+                "daily",
+                "2026-01-02",
+                false,
+                None,
+            ),
+            (
+                "Dee", "device-d", 900, 12, "classic", "classic", false, None,
+            ),
+        ];
+        for (name, uuid, score, level, mode, key, submitted, token) in rows {
+            store
+                .lock()
+                .execute(
+                    "insert into scores (player_name, client_uuid, score, level, mode,
+                       challenge_key, daily_has_submission, active_attempt_token, created_at)
+                     values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, '2026-01-01T00:00:00.000+00:00')",
+                    params![name, uuid, score, level, mode, key, submitted, token],
+                )
+                .unwrap();
+        }
+        store
+    }
+
+    fn query(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn read(store: &Store, pairs: &[(&str, &str)]) -> Reply {
+        select(&store.lock(), "scores", &query(pairs), true)
+    }
+
+    fn names(reply: &Reply) -> Vec<String> {
+        assert_eq!(reply.status, 200, "{}", reply.body);
+        serde_json::from_str::<Vec<Value>>(&reply.body)
+            .unwrap()
+            .iter()
+            .map(|row| row["player_name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    fn error_code(reply: &Reply) -> String {
+        let body = serde_json::from_str::<Value>(&reply.body).unwrap();
+        body["code"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn filters_compare_typed_literals() {
+        let store = store_with_scores();
+        let reply = read(
+            &store,
+            &[
+                ("mode", "eq.daily"),
+                ("score", "gte.300"),
+                ("level", "neq.6"),
+                ("order", "score.desc"),
+            ],
+        );
+        assert_eq!(names(&reply), ["Ada", "Bob"]);
+
+        let submitted = read(&store, &[("daily_has_submission", "is.false")]);
+        assert_eq!(names(&submitted), ["Cy, Jr.", "Dee"]);
+        let idle = read(
+            &store,
+            &[("active_attempt_token", "is.null"), ("order", "id")],
+        );
+        assert_eq!(names(&idle), ["Ada", "Cy, Jr.", "Dee"]);
+    }
+
+    #[test]
+    fn in_lists_split_outside_quotes() {
+        let store = store_with_scores();
+        let reply = read(
+            &store,
+            &[
+                ("player_name", r#"in.("Cy, Jr.",Dee,"Q\"uoted")"#),
+                ("order", "id.asc"),
+            ],
+        );
+        assert_eq!(names(&reply), ["Cy, Jr.", "Dee"]);
+        assert_eq!(
+            names(&read(&store, &[("score", "in.()")])),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn or_groups_nest_and_groups() {
+        let store = store_with_scores();
+        let reply = read(
+            &store,
+            &[
+                (
+                    "or",
+                    "(score.gt.800,and(score.eq.300,level.gt.4),player_name.eq.\"Ada\")",
+                ),
+                ("order", "id"),
+            ],
+        );
+        assert_eq!(names(&reply), ["Ada", "Cy, Jr.", "Dee"]);
+    }
+
+    #[test]
+    fn order_limit_and_offset_set_the_content_range() {
+        let store = store_with_scores();
+        let reply = read(
+            &store,
+            &[
+                ("select", "player_name,score"),
+                ("order", "score.desc,level.asc"),
+                ("limit", "2"),
+                ("offset", "1"),
+            ],
+        );
+        assert_eq!(names(&reply), ["Ada", "Bob"]);
+        let range = reply
+            .headers
+            .iter()
+            .find(|(name, _)| *name == "Content-Range");
+        assert_eq!(range.map(|(_, value)| value.as_str()), Some("1-2/4"));
+        let row = serde_json::from_str::<Vec<Value>>(&reply.body)
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            row,
+            serde_json::json!({ "player_name": "Ada", "score": 500 })
+        );
+
+        let tokens_first = read(
+            &store,
+            &[("order", "active_attempt_token.asc.nullsfirst,id")],
+        );
+        assert_eq!(names(&tokens_first), ["Ada", "Cy, Jr.", "Dee", "Bob"]);
+        let tokens_last = read(&store, &[("order", "active_attempt_token.desc.nullslast")]);
+        assert_eq!(names(&tokens_last)[0], "Bob");
+    }
+
+    #[test]
+    fn malformed_reads_fail_like_postgrest() {
+        let store = store_with_scores();
+        let cases = [
+            (vec![("score", "gt")], "PGRST100"),
+            (vec![("score", "like.3%")], "PGRST100"),
+            (vec![("or", "score.gt.1")], "PGRST100"),
+            (vec![("order", "score.sideways")], "PGRST100"),
+            (vec![("limit", "many")], "PGRST100"),
+            (vec![("secret", "eq.1")], "42703"),
+            (vec![("select", "player_name,secret")], "42703"),
+            (vec![("score", "eq.high")], "22P02"),
+            (vec![("daily_has_submission", "is.maybe")], "22P02"),
+            (vec![("skill_usage", "eq.[]")], "42883"),
+        ];
+        for (pairs, code) in cases {
+            let reply = read(&store, &pairs);
+            assert_eq!(reply.status, 400, "{pairs:?}");
+            assert_eq!(error_code(&reply), code, "{pairs:?}");
+        }
+
+        let missing = select(&store.lock(), "profiles", &[], false);
+        assert_eq!(missing.status, 404);
+        assert_eq!(error_code(&missing), "PGRST205");
+        let write = reject_write("scores");
+        assert_eq!(write.status, 401);
+        assert_eq!(error_code(&write), "42501");
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use rusqlite::Connection;
+
+/// The subset of `supabase/schema.sql` the app reads and writes. Booleans are
+/// stored as 0/1, `jsonb` as JSON text and `timestamptz` as fixed-width UTC
+/// text (see `clock::timestamp`).
+const SCHEMA: &str = "
+create table if not exists scores (
+  id integer primary key autoincrement,
+  player_name text not null,
+  client_uuid text not null,
+  score integer not null default 0 check (score >= 0),
+  level integer not null default 0 check (level >= 0),
+  skill_usage text not null default '[]',
+  mode text not null default 'classic',
+  challenge_key text not null default 'classic',
+  attempts_used integer not null default 0 check (attempts_used between 0 and 20),
+  daily_has_submission integer not null default 0,
+  active_attempt_token text,
+  active_attempt_started_at text,
+  created_at text not null,
+  unique (mode, challenge_key, client_uuid)
+);
+
+create index if not exists idx_scores_rank
+  on scores (mode, challenge_key, score desc, level desc, created_at desc);
+
+create table if not exists daily_streak_states (
+  client_uuid text primary key,
+  current_streak integer not null default 0 check (current_streak >= 0),
+  max_streak integer not null default 0 check (max_streak >= 0),
+  last_submission_key text,
+  freeze_tokens integer not null default 0 check (freeze_tokens between 0 and 2),
+  updated_at text not null
+);
+
+create table if not exists challenge_settings (
+  mode text not null check (mode in ('daily', 'weekly', 'monthly')),
+  challenge_key text not null,
+  max_attempts integer not null check (max_attempts between 1 and 20),
+  primary key (mode, challenge_key)
+);
+
+create table if not exists badge_tiers (
+  power integer primary key check (power >= 0),
+  days integer not null unique check (days >= 1),
+  name text not null,
+  icon_id text not null
+);
+";
+
+/// One SQLite connection shared by every worker. Each RPC runs in its own
+/// transaction while holding the lock, which serializes writes the way the
+/// `for update` row locks do in Postgres.
+pub struct Store {
+    connection: Mutex<Connection>,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let connection = Connection::open(path)
+            .map_err(|error| format!("failed to open {}: {error}", path.display()))?;
+        connection
+            .pragma_update_and_check(None, "journal_mode", "wal", |_| Ok(()))
+            .and_then(|_| connection.execute_batch(SCHEMA))
+            .map_err(|error| format!("failed to prepare {}: {error}", path.display()))?;
+        Ok(Store {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs `work` in one transaction that is committed only when it succeeds,
+    /// so a raised error leaves no partial writes behind.
+    pub fn transaction<T, E>(&self, work: impl FnOnce(&Connection) -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<rusqlite::Error>,
+    {
+        let mut connection = self.lock();
+        let transaction = connection.transaction()?;
+        let value = work(&transaction)?;
+        transaction.commit()?;
+        Ok(value)
+    }
+}
@@ -0,0 +1,399 @@
+//! Port of `supabase/functions/verify-score`: validates the payload, re-runs
+//! the replay with the app's engine and records the score through the
+//! submission functions in `attempts`.
+
+use serde_json::{json, Value};
+use torus_core::replay::verify_replay_json;
+
+use crate::attempts::{self, RpcError, ScoreSubmission};
+use crate::clock;
+use crate::store::Store;
+use crate::Reply;
+
+const PERIOD_MODES: [&str; 3] = ["daily", "weekly", "monthly"];
+const CLASSIC_MODE: &str = "classic";
+
+pub fn verify_score(store: &Store, raw: &str) -> Reply {
+    let Ok(payload) = serde_json::from_str::<Value>(raw) else {
+        return error(400, "INVALID_JSON");
+    };
+    if let Err(code) = validate_payload(&payload) {
+        return error(400, code);
+    }
+    let mode = payload["mode"].as_str().unwrap_or_default();
+    let challenge_key = payload["challengeKey"].as_str().unwrap_or_default();
+    let entry = &payload["entry"];
+    let proof = &payload["replayProof"];
+
+    let Ok(verification) = verify_replay_json(&proof.to_string()) else {
+        return error(400, "INVALID_REPLAY_PROOF");
+    };
+    if !verification.ok {
+        let actual = verification.actual;
+        return Reply::json(
+            400,
+            json!({
+                "error": "REPLAY_VERIFICATION_FAILED",
+                "reason": verification.reason,
+                "actual": {
+                    "score": actual.score,
+                    "level": actual.level,
+                    "time": actual.time,
+                    "gameOn": actual.game_on,
+                },
+                "expected": {
+                    "score": truncated(&proof["finalScore"]),
+                    "level": truncated(&proof["finalLevel"]),
+                    "time": truncated(&proof["finalTime"]),
+                    "difficulty": proof["difficulty"],
+                    "inputCount": proof["inputs"].as_array().map_or(0, Vec::len),
+                },
+            }),
+        );
+    }
+
+    let (rpc_name, failure_code) = if mode == CLASSIC_MODE {
+        ("submit_global_score", "RPC_SUBMIT_GLOBAL_SCORE_FAILED")
+    } else {
+        ("submit_daily_score", "RPC_SUBMIT_DAILY_SCORE_FAILED")
+    };
+    let date = entry["date"].as_str().unwrap_or_default();
+    let Some(created_at) = clock::parse_timestamp(date) else {
+        let detail = format!("invalid input syntax for type timestamp with time zone: \"{date}\"");
+        eprintln!("[verify-score] {rpc_name} failed: {detail}");
+        return rpc_failure(failure_code, &detail, "22007");
+    };
+    let created_at = clock::timestamp(created_at);
+    let skill_usage = normalize_skill_usage(&entry["skillUsage"]);
+    let submission = ScoreSubmission {
+        client_uuid: payload["clientUuid"].as_str().unwrap_or_default(),
+        player_name: entry["user"].as_str().unwrap_or_default(),
+        score: truncated(&entry["score"]).as_i64().unwrap_or(0),
+        level: truncated(&entry["level"]).as_i64().unwrap_or(0),
+        created_at: &created_at,
+        skill_usage: &skill_usage,
+    };
+
+    let result = store.transaction(|connection| {
+        if mode == CLASSIC_MODE {
+            attempts::submit_global_score(connection, &submission)
+        } else {
+            let attempt_token = payload["attemptToken"].as_str().unwrap_or_default();
+            attempts::submit_period_score(
+                connection,
+                mode,
+                challenge_key,
+                attempt_token,
+                &submission,
+            )
+        }
+    });
+    match result {
+        Ok(body) => Reply::json(200, body),
+        Err(RpcError::Raised(message)) => {
+            eprintln!("[verify-score] {rpc_name} failed: {message}");
+            rpc_failure(failure_code, message, "P0001")
+        }
+        Err(RpcError::Storage(storage)) => {
+            eprintln!("[verify-score] {rpc_name} failed: {storage}");
+            rpc_failure(failure_code, &storage.to_string(), "XX000")
+        }
+    }
+}
+
+/// `validatePayload`, in the same order so the first failing check wins.
+fn validate_payload(payload: &Value) -> Result<(), &'static str> {
+    if !payload.is_object() {
+        return Err("INVALID_PAYLOAD");
+    }
+    let mode = payload["mode"].as_str().unwrap_or_default();
+    if mode != CLASSIC_MODE && !PERIOD_MODES.contains(&mode) {
+        return Err("INVALID_MODE");
+    }
+    if !is_text(&payload["clientUuid"], 8, 80) {
+        return Err("INVALID_CLIENT_UUID");
+    }
+    let entry = &payload["entry"];
+    if !entry.is_object() {
+        return Err("INVALID_ENTRY");
+    }
+    if !is_text(&entry["user"], 1, 20) {
+        return Err("INVALID_PLAYER_NAME");
+    }
+    if entry["score"].as_f64().is_none_or(|score| score < 0.0) {
+        return Err("INVALID_SCORE");
+    }
+    if entry["level"].as_f64().is_none_or(|level| level < 0.0) {
+        return Err("INVALID_LEVEL");
+    }
+    if !is_text(&entry["date"], 1, 64) {
+        return Err("INVALID_CREATED_AT");
+    }
+    let proof = &payload["replayProof"];
+    if !proof.is_object() {
+        return Err("INVALID_REPLAY_PROOF");
+    }
+    let finite = |value: &Value| value.as_f64().map(f64::trunc);
+    if finite(&entry["score"]) != finite(&proof["finalScore"])
+        || finite(&entry["level"]) != finite(&proof["finalLevel"])
+    {
+        return Err("ENTRY_REPLAY_MISMATCH");
+    }
+
+    if mode == CLASSIC_MODE {
+        if payload["challengeKey"].as_str() != Some(CLASSIC_MODE) {
+            return Err("INVALID_CHALLENGE_KEY");
+        }
+    } else {
+        let key = payload["challengeKey"].as_str().unwrap_or_default().trim();
+        if !attempts::matches_key_pattern(mode, key) {
+            return Err("INVALID_CHALLENGE_KEY");
+        }
+        if !is_text(&payload["attemptToken"], 1, 256) {
+            return Err("INVALID_ATTEMPT_TOKEN");
+        }
+    }
+    Ok(())
+}
+
+fn is_text(value: &Value, min: usize, max: usize) -> bool {
+    value
+        .as_str()
+        .map(|text| text.trim().chars().count())
+        .is_some_and(|length| (min..=max).contains(&length))
+}
+
+/// `Math.trunc` of a JSON number, keeping integers as integers.
+fn truncated(value: &Value) -> Value {
+    match value {
+        Value::Number(number) if number.is_i64() || number.is_u64() => value.clone(),
+        Value::Number(number) => number
+            .as_f64()
+            .map(|float| json!(float.trunc() as i64))
+            .unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+/// `normalizeSkillUsage`: trims and caps names, hotkeys and commands, drops
+/// unnamed entries and keeps at most 20.
+fn normalize_skill_usage(raw: &Value) -> Value {
+    let text = |value: &Value, max_chars: usize| -> Value {
+        let text = match value {
+            Value::Null | Value::Bool(false) => return Value::Null,
+            Value::String(text) if text.is_empty() => return Value::Null,
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        json!(text.trim().chars().take(max_chars).collect::<String>())
+    };
+    let items = raw
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|item| item.is_object())
+        .map(|item| {
+            let name = match &item["name"] {
+                Value::Null => String::new(),
+                Value::String(name) => name.clone(),
+                other => other.to_string(),
+            };
+            json!({
+                "name": name.trim().chars().take(20).collect::<String>(),
+                "hotkey": text(&item["hotkey"], 16),
+                "command": text(&item["command"], 120),
+            })
+        })
+        .filter(|item| item["name"].as_str().is_some_and(|name| !name.is_empty()))
+        .take(20)
+        .collect();
+    Value::Array(items)
+}
+
+fn error(status: u16, code: &str) -> Reply {
+    Reply::json(status, json!({ "error": code }))
+}
+
+fn rpc_failure(error: &str, detail: &str, code: &str) -> Reply {
+    Reply::json(
+        400,
+        json!({ "error": error, "detail": detail, "hint": null, "code": code }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use torus_core::challenge_period::challenge_key_for_day;
+
+    /// An idle run on `seed` with the final state the simulator reaches.
+    fn finished_run(seed: u32) -> Value {
+        let mut run = json!({
+            "version": 1,
+            "difficulty": 1,
+            "seed": seed,
+            "finalTime": 2_000_000,
+            "finalScore": 0,
+            "finalLevel": 0,
+            "inputs": [],
+        });
+        let actual = verify_replay_json(&run.to_string()).unwrap().actual;
+        run["finalTime"] = json!(actual.time);
+        run["finalScore"] = json!(actual.score);
+        run["finalLevel"] = json!(actual.level);
+        run
+    }
+
+    fn classic_payload(proof: &Value) -> Value {
+        json!({
+            "mode": "classic",
+            "challengeKey": "classic",
+            "clientUuid": "device-0001",
+            "entry": {
+                "user": "  Tester  ",
+                "score": proof["finalScore"],
+                "level": proof["finalLevel"],
+                "date": "2026-01-01T00:00:00.000Z",
+                "skillUsage": [{ "name": " Melt ", "hotkey": "", "command": 5 }, { "name": "" }],
+            },
+            "replayProof": proof,
+        })
+    }
+
+    fn post(store: &Store, payload: &Value) -> (u16, Value) {
+        let reply = verify_score(store, &payload.to_string());
+        (reply.status, serde_json::from_str(&reply.body).unwrap())
+    }
+
+    #[test]
+    fn verified_classic_runs_are_recorded() {
+        let store = Store::open(Path::new(":memory:")).unwrap();
+        let proof = finished_run(7);
+        let (status, body) = post(&store, &classic_payload(&proof));
+        assert_eq!(status, 200, "{body}");
+        assert_eq!(body["accepted"], true);
+        assert_eq!(body["rank"], 1);
+
+        let (name, skill_usage, created_at) = store
+            .lock()
+            .query_row(
+                "select player_name, skill_usage, created_at from scores",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(name, "Tester");
+        assert_eq!(
+            serde_json::from_str::<Value>(&skill_usage).unwrap(),
+            json!([{ "name": "Melt", "hotkey": null, "command": "5" }])
+        );
+        assert_eq!(created_at, "2026-01-01T00:00:00.000+00:00");
+    }
+
+    #[test]
+    fn forged_runs_fail_replay_verification() {
+        let store = Store::open(Path::new(":memory:")).unwrap();
+        let mut proof = finished_run(7);
+        proof["finalScore"] = json!(proof["finalScore"].as_i64().unwrap() + 300);
+        let (status, body) = post(&store, &classic_payload(&proof));
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "REPLAY_VERIFICATION_FAILED");
+        assert_eq!(body["reason"], "Replay final score mismatch.");
+        assert_eq!(body["expected"]["score"], proof["finalScore"]);
+        assert_eq!(body["actual"]["gameOn"], false);
+        let rows = store
+            .lock()
+            .query_row("select count(*) from scores", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .unwrap();
+        assert_eq!(rows, 0);
+    }
+
+    type Edit = fn(&mut Value);
+
+    #[test]
+    fn payloads_are_validated_in_order() {
+        let store = Store::open(Path::new(":memory:")).unwrap();
+        let valid = classic_payload(&finished_run(7));
+        let edits: [(&str, Edit); 7] = [
+            ("INVALID_MODE", |payload| payload["mode"] = json!("yearly")),
+            ("INVALID_CLIENT_UUID", |payload| {
+                payload["clientUuid"] = json!("short")
+            }),
+            ("INVALID_PLAYER_NAME", |payload| {
+                payload["entry"]["user"] = json!("   ")
+            }),
+            ("INVALID_SCORE", |payload| {
+                payload["entry"]["score"] = json!(-1)
+            }),
+            ("ENTRY_REPLAY_MISMATCH", |payload| {
+                payload["entry"]["level"] = json!(99)
+            }),
+            ("INVALID_CHALLENGE_KEY", |payload| {
+                payload["challengeKey"] = json!("daily")
+            }),
+            ("INVALID_ATTEMPT_TOKEN", |payload| {
+                payload["mode"] = json!("weekly");
+                payload["challengeKey"] = json!("2026-W01");
+            }),
+        ];
+        for (code, edit) in edits {
+            let mut payload = valid.clone();
+            edit(&mut payload);
+            let (status, body) = post(&store, &payload);
+            assert_eq!((status, body["error"].as_str()), (400, Some(code)));
+        }
+
+        let reply = verify_score(&store, "{");
+        assert_eq!(reply.status, 400);
+        assert!(reply.body.contains("INVALID_JSON"));
+
+        let mut bad_date = valid.clone();
+        bad_date["entry"]["date"] = json!("yesterday");
+        let (status, body) = post(&store, &bad_date);
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "RPC_SUBMIT_GLOBAL_SCORE_FAILED");
+        assert_eq!(body["code"], "22007");
+    }
+
+    #[test]
+    fn period_runs_need_the_active_attempt() {
+        let store = Store::open(Path::new(":memory:")).unwrap();
+        let key = challenge_key_for_day("daily", clock::today()).unwrap();
+        let mut payload = classic_payload(&finished_run(7));
+        payload["mode"] = json!("daily");
+        payload["challengeKey"] = json!(key);
+        payload["attemptToken"] = json!("missing");
+        let (status, body) = post(&store, &payload);
+        assert_eq!(status, 200);
+        assert_eq!(body["accepted"], false);
+
+        let started = store
+            .transaction(|connection| {
+                attempts::start_attempt(
+                    connection,
+                    &json!({ "p_client_uuid": "device-0001", "p_challenge_key": key }),
+                )
+            })
+            .unwrap();
+        payload["attemptToken"] = started["attemptToken"].clone();
+        let (status, body) = post(&store, &payload);
+        assert_eq!(status, 200);
+        assert_eq!(body["accepted"], true);
+        assert_eq!(body["improved"], true);
+
+        payload["challengeKey"] = json!("2001-01-01");
+        let (status, body) = post(&store, &payload);
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "RPC_SUBMIT_DAILY_SCORE_FAILED");
+        assert_eq!(body["detail"], "CHALLENGE_KEY_MISMATCH");
+    }
+}
@@ -0,0 +1,454 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Runtime};
+use torus_core::clock::{current_challenge_key, current_utc_seconds};
+use torus_core::replay::{verify_replay_proof, DailyReplayProof, ReplayVerification};
+
+use crate::scoreboard::{app_data_dir, ScoreEntry};
+
+const ACHIEVEMENTS_FILE_NAME: &str = "achievements-v1.json";
+const ACHIEVEMENTS_UNLOCKED_EVENT: &str = "achievements-unlocked";
+const DAILY_MODE: &str = "daily";
+
+enum AchievementRule {
+    ReachLevel { level: i64, difficulty: Option<i64> },
+    MeltRowsInRun { rows: i64 },
+    ScoreInRun { score: i64 },
+    ReachDailyTopWithoutSkills { count: i64 },
+    PlayDistinctDays { days: i64 },
+}
+
+struct AchievementDefinition {
+    id: &'static str,
+    title: &'static str,
+    description: &'static str,
+    rule: AchievementRule,
+}
+
+const ACHIEVEMENTS: &[AchievementDefinition] = &[
+    AchievementDefinition {
+        id: "reach-level-5",
+        title: "Warming Up",
+        description: "Reach level 5 on any difficulty.",
+        rule: AchievementRule::ReachLevel {
+            level: 5,
+            difficulty: None,
+        },
+    },
+    AchievementDefinition {
+        id: "reach-level-10-difficulty-3",
+        title: "Flip Master",
+        description: "Reach level 10 on difficulty 3.",
+        rule: AchievementRule::ReachLevel {
+            level: 10,
+            difficulty: Some(3),
+        },
+    },
+    AchievementDefinition {
+        id: "melt-50-rows",
+        title: "Meltdown",
+        description: "Melt 50 rows in one run.",
+        rule: AchievementRule::MeltRowsInRun { rows: 50 },
+    },
+    AchievementDefinition {
+        id: "score-50000",
+        title: "High Roller",
+        description: "Score 50,000 points in one run.",
+        rule: AchievementRule::ScoreInRun { score: 50_000 },
+    },
+    AchievementDefinition {
+        id: "daily-zero-skills",
+        title: "Bare Hands",
+        description: "Reach #1 on a Daily Challenge board with a run that used no skills.",
+        rule: AchievementRule::ReachDailyTopWithoutSkills { count: 1 },
+    },
+    AchievementDefinition {
+        id: "play-7-days",
+        title: "Regular",
+        description: "Play on 7 different UTC days.",
+        rule: AchievementRule::PlayDistinctDays { days: 7 },
+    },
+    AchievementDefinition {
+        id: "play-30-days",
+        title: "Resident",
+        description: "Play on 30 different UTC days.",
+        rule: AchievementRule::PlayDistinctDays { days: 30 },
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AchievementProgress {
+    id: String,
+    title: String,
+    description: String,
+    progress: i64,
+    target: i64,
+    unlocked: bool,
+    #[serde(rename = "unlockedAt")]
+    unlocked_at: Option<i64>,
+}
+
+/// Local history the rules are evaluated against. Only runs whose replay
+/// re-simulates cleanly are folded in.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AchievementLedger {
+    #[serde(rename = "bestLevelByDifficulty", default)]
+    best_level_by_difficulty: BTreeMap<i64, i64>,
+    #[serde(rename = "bestRowsMelted", default)]
+    best_rows_melted: i64,
+    #[serde(rename = "bestScore", default)]
+    best_score: i64,
+    /// Daily keys where a run without skills was #1 when it was submitted;
+    /// a day counts once, even if the run is passed later that day.
+    #[serde(rename = "skillFreeDailyWins", default)]
+    skill_free_daily_wins: BTreeSet<String>,
+    #[serde(rename = "playedDays", default)]
+    played_days: BTreeSet<String>,
+    #[serde(default)]
+    unlocked: BTreeMap<String, i64>,
+}
+
+pub(crate) struct RunRecord<'a> {
+    pub mode: &'a str,
+    pub challenge_key: Option<&'a str>,
+    pub entry: &'a ScoreEntry,
+    pub replay_proof: &'a DailyReplayProof,
+    /// Board rank of the run right after submission, when it was looked up.
+    pub daily_rank: Option<i64>,
+}
+
+#[tauri::command]
+pub async fn fetch_achievements<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<AchievementProgress>, String> {
+    let ledger = read_ledger(&app)?;
+    Ok(ACHIEVEMENTS
+        .iter()
+        .map(|definition| progress_for(definition, &ledger))
+        .collect())
+}
+
+/// Folds a finished run into the ledger, persists newly unlocked achievements
+/// and emits them to the frontend. Failures are logged, never surfaced, so
+/// achievements cannot break score submission. The replay is re-simulated on
+/// the blocking pool so long runs do not stall the async runtime.
+pub(crate) async fn record_run<R: Runtime>(app: &AppHandle<R>, run: RunRecord<'_>) {
+    let proof = run.replay_proof.clone();
+    let verification =
+        match tauri::async_runtime::spawn_blocking(move || verify_replay_proof(&proof)).await {
+            Ok(verification) => verification,
+            Err(error) => {
+                eprintln!("Failed to re-simulate run for achievements. {error}");
+                return;
+            }
+        };
+    match apply_run(app, &run, verification) {
+        Ok(unlocked) if !unlocked.is_empty() => {
+            if let Err(error) = app.emit(ACHIEVEMENTS_UNLOCKED_EVENT, &unlocked) {
+                eprintln!("Failed to emit achievement unlocks. {error}");
+            }
+        }
+        Ok(_) => {}
+        Err(error) => {
+            eprintln!("Failed to record achievements for run. {error}");
+        }
+    }
+}
+
+fn apply_run<R: Runtime>(
+    app: &AppHandle<R>,
+    run: &RunRecord<'_>,
+    verification: ReplayVerification,
+) -> Result<Vec<AchievementProgress>, String> {
+    if !verification.ok {
+        return Err(format!(
+            "replay did not verify locally: {}",
+            verification.reason.unwrap_or("unknown reason")
+        ));
+    }
+
+    let mut ledger = read_ledger(app)?;
+    let unlocked = fold_run(&mut ledger, run, verification, current_utc_seconds());
+    write_ledger(app, &ledger)?;
+    Ok(unlocked)
+}
+
+/// Folds a verified run into `ledger` and returns the achievements it unlocks.
+fn fold_run(
+    ledger: &mut AchievementLedger,
+    run: &RunRecord<'_>,
+    verification: ReplayVerification,
+    now: i64,
+) -> Vec<AchievementProgress> {
+    let actual = verification.actual;
+    let difficulty = run.replay_proof.difficulty;
+    let best_level = ledger
+        .best_level_by_difficulty
+        .entry(difficulty)
+        .or_insert(0);
+    *best_level = (*best_level).max(actual.level);
+    ledger.best_rows_melted = ledger.best_rows_melted.max(verification.rows_melted);
+    ledger.best_score = ledger.best_score.max(actual.score);
+    let played_key = match run.challenge_key {
+        Some(key) if run.mode == DAILY_MODE => key.to_string(),
+        _ => current_challenge_key(),
+    };
+    if run.mode == DAILY_MODE && run.entry.skill_usage.is_empty() && run.daily_rank == Some(1) {
+        ledger.skill_free_daily_wins.insert(played_key.clone());
+    }
+    ledger.played_days.insert(played_key);
+
+    let mut unlocked = Vec::new();
+    for definition in ACHIEVEMENTS {
+        if ledger.unlocked.contains_key(definition.id) {
+            continue;
+        }
+        let (progress, target) = rule_progress(&definition.rule, ledger);
+        if progress >= target {
+            ledger.unlocked.insert(definition.id.to_string(), now);
+            unlocked.push(progress_for(definition, ledger));
+        }
+    }
+    unlocked
+}
+
+fn rule_progress(rule: &AchievementRule, ledger: &AchievementLedger) -> (i64, i64) {
+    match rule {
+        AchievementRule::ReachLevel { level, difficulty } => {
+            let best = match difficulty {
+                Some(value) => ledger
+                    .best_level_by_difficulty
+                    .get(value)
+                    .copied()
+                    .unwrap_or(0),
+                None => ledger
+                    .best_level_by_difficulty
+                    .values()
+                    .copied()
+                    .max()
+                    .unwrap_or(0),
+            };
+            (best, *level)
+        }
+        AchievementRule::MeltRowsInRun { rows } => (ledger.best_rows_melted, *rows),
+        AchievementRule::ScoreInRun { score } => (ledger.best_score, *score),
+        AchievementRule::ReachDailyTopWithoutSkills { count } => {
+            (ledger.skill_free_daily_wins.len() as i64, *count)
+        }
+        AchievementRule::PlayDistinctDays { days } => (ledger.played_days.len() as i64, *days),
+    }
+}
+
+fn progress_for(
+    definition: &AchievementDefinition,
+    ledger: &AchievementLedger,
+) -> AchievementProgress {
+    let (progress, target) = rule_progress(&definition.rule, ledger);
+    let unlocked_at = ledger.unlocked.get(definition.id).copied();
+    AchievementProgress {
+        id: definition.id.to_string(),
+        title: definition.title.to_string(),
+        description: definition.description.to_string(),
+        progress: progress.clamp(0, target),
+        target,
+        unlocked: unlocked_at.is_some(),
+        unlocked_at,
+    }
+}
+
+fn ledger_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(ACHIEVEMENTS_FILE_NAME);
+    Ok(dir)
+}
+
+fn read_ledger<R: Runtime>(app: &AppHandle<R>) -> Result<AchievementLedger, String> {
+    let path = ledger_path(app)?;
+    if !path.exists() {
+        return Ok(AchievementLedger::default());
+    }
+
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read achievements: {error}"))?;
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn write_ledger<R: Runtime>(app: &AppHandle<R>, ledger: &AchievementLedger) -> Result<(), String> {
+    let path = ledger_path(app)?;
+    let body = serde_json::to_string(ledger)
+        .map_err(|error| format!("failed to serialize achievements: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write achievements: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use torus_core::replay::ReplayState;
+
+    fn entry(skills: usize) -> ScoreEntry {
+        let skill_usage = vec![serde_json::json!({ "name": "flip", "hotkey": null }); skills];
+        serde_json::from_value(serde_json::json!({
+            "user": "Tester",
+            "score": 0,
+            "level": 0,
+            "date": "2026-01-01T00:00:00.000Z",
+            "skillUsage": skill_usage,
+        }))
+        .unwrap()
+    }
+
+    fn proof(difficulty: i64) -> DailyReplayProof {
+        DailyReplayProof {
+            version: 1,
+            difficulty,
+            seed: 1,
+            final_time: 0,
+            final_score: 0,
+            final_level: 0,
+            inputs: Vec::new(),
+        }
+    }
+
+    fn verified(score: i64, level: i64, rows_melted: i64) -> ReplayVerification {
+        ReplayVerification {
+            ok: true,
+            reason: None,
+            actual: ReplayState {
+                score,
+                level,
+                time: 0,
+                game_on: false,
+            },
+            rows_melted,
+        }
+    }
+
+    fn daily<'a>(
+        key: &'a str,
+        entry: &'a ScoreEntry,
+        proof: &'a DailyReplayProof,
+        rank: Option<i64>,
+    ) -> RunRecord<'a> {
+        RunRecord {
+            mode: DAILY_MODE,
+            challenge_key: Some(key),
+            entry,
+            replay_proof: proof,
+            daily_rank: rank,
+        }
+    }
+
+    fn unlocked_ids(unlocked: &[AchievementProgress]) -> Vec<&str> {
+        unlocked
+            .iter()
+            .map(|progress| progress.id.as_str())
+            .collect()
+    }
+
+    fn progress_of(id: &str, ledger: &AchievementLedger) -> (i64, i64) {
+        let definition = ACHIEVEMENTS.iter().find(|item| item.id == id).unwrap();
+        rule_progress(&definition.rule, ledger)
+    }
+
+    #[test]
+    fn run_thresholds_unlock_exactly_at_their_target() {
+        let mut ledger = AchievementLedger::default();
+        let entry = entry(0);
+        let easy = proof(1);
+        let run = daily("2026-01-01", &entry, &easy, None);
+
+        let unlocked = fold_run(&mut ledger, &run, verified(49_999, 4, 49), 10);
+        assert!(unlocked.is_empty());
+        assert_eq!(progress_of("reach-level-5", &ledger), (4, 5));
+        assert_eq!(progress_of("melt-50-rows", &ledger), (49, 50));
+        assert_eq!(progress_of("score-50000", &ledger), (49_999, 50_000));
+
+        let unlocked = fold_run(&mut ledger, &run, verified(50_000, 10, 50), 20);
+        assert_eq!(
+            unlocked_ids(&unlocked),
+            ["reach-level-5", "melt-50-rows", "score-50000"]
+        );
+        assert!(unlocked
+            .iter()
+            .all(|progress| progress.unlocked_at == Some(20)));
+        assert_eq!(progress_of("reach-level-10-difficulty-3", &ledger), (0, 10));
+
+        let hard = proof(3);
+        let run = daily("2026-01-01", &entry, &hard, None);
+        let unlocked = fold_run(&mut ledger, &run, verified(0, 10, 0), 30);
+        assert_eq!(unlocked_ids(&unlocked), ["reach-level-10-difficulty-3"]);
+        assert!(fold_run(&mut ledger, &run, verified(0, 12, 0), 40).is_empty());
+    }
+
+    #[test]
+    fn played_days_count_each_day_once() {
+        let mut ledger = AchievementLedger::default();
+        let entry = entry(0);
+        let proof = proof(1);
+        for _ in 0..3 {
+            let run = daily("2026-01-01", &entry, &proof, None);
+            fold_run(&mut ledger, &run, verified(0, 0, 0), 0);
+        }
+        assert_eq!(progress_of("play-7-days", &ledger), (1, 7));
+
+        let keys = (2..=7)
+            .map(|day| format!("2026-01-{day:02}"))
+            .collect::<Vec<_>>();
+        let mut unlocked = Vec::new();
+        for key in &keys {
+            let run = daily(key, &entry, &proof, None);
+            unlocked.extend(fold_run(&mut ledger, &run, verified(0, 0, 0), 0));
+        }
+        assert_eq!(unlocked_ids(&unlocked), ["play-7-days"]);
+        assert_eq!(progress_of("play-30-days", &ledger), (7, 30));
+    }
+
+    #[test]
+    fn bare_hands_needs_a_skill_free_daily_run_at_the_top() {
+        let mut ledger = AchievementLedger::default();
+        let skilled = entry(2);
+        let bare = entry(0);
+        let proof = proof(1);
+
+        let runs = [
+            daily("2026-01-01", &skilled, &proof, Some(1)),
+            daily("2026-01-01", &bare, &proof, Some(2)),
+            daily("2026-01-01", &bare, &proof, None),
+        ];
+        for run in &runs {
+            assert!(fold_run(&mut ledger, run, verified(0, 0, 0), 0).is_empty());
+        }
+        let classic = RunRecord {
+            mode: "classic",
+            challenge_key: None,
+            entry: &bare,
+            replay_proof: &proof,
+            daily_rank: Some(1),
+        };
+        assert!(fold_run(&mut ledger, &classic, verified(0, 0, 0), 0).is_empty());
+        assert_eq!(progress_of("daily-zero-skills", &ledger), (0, 1));
+
+        let top = daily("2026-01-01", &bare, &proof, Some(1));
+        let unlocked = fold_run(&mut ledger, &top, verified(0, 0, 0), 0);
+        assert_eq!(unlocked_ids(&unlocked), ["daily-zero-skills"]);
+        fold_run(&mut ledger, &top, verified(0, 0, 0), 0);
+        assert_eq!(ledger.skill_free_daily_wins.len(), 1);
+    }
+
+    #[test]
+    fn progress_is_clamped_to_the_target() {
+        let ledger = AchievementLedger {
+            best_score: 90_000,
+            ..AchievementLedger::default()
+        };
+        let definition = ACHIEVEMENTS
+            .iter()
+            .find(|item| item.id == "score-50000")
+            .unwrap();
+        let progress = progress_for(definition, &ledger);
+        assert_eq!((progress.progress, progress.target), (50_000, 50_000));
+        assert!(!progress.unlocked);
+    }
+}
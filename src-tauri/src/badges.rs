@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::scoreboard::{observe_server_date, SupabaseConfig};
+
+const BUNDLED_BADGE_TIERS: &str = include_str!("../resources/badge-tiers.json");
+const BADGE_TIERS_TABLE_NAME: &str = "badge_tiers";
+
+static BADGE_TIERS: RwLock<Option<Arc<BadgeTierTable>>> = RwLock::new(None);
+static SERVER_TIERS_CHECKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BadgeTier {
+    pub power: i64,
+    pub days: i64,
+    pub name: String,
+    #[serde(rename = "iconId", alias = "icon_id")]
+    pub icon_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BadgeTierFile {
+    tiers: Vec<BadgeTier>,
+}
+
+/// Badge ladder sorted by `days`, lowest tier first.
+#[derive(Debug)]
+pub struct BadgeTierTable {
+    tiers: Vec<BadgeTier>,
+}
+
+impl BadgeTierTable {
+    fn from_tiers(mut tiers: Vec<BadgeTier>) -> Result<Self, String> {
+        if tiers.is_empty() {
+            return Err("badge tier table is empty".into());
+        }
+        tiers.sort_by_key(|tier| tier.days);
+        for (index, tier) in tiers.iter().enumerate() {
+            if tier.days < 1 {
+                return Err(format!(
+                    "badge tier {} must require at least one day",
+                    tier.power
+                ));
+            }
+            if tier.name.trim().is_empty() || tier.icon_id.trim().is_empty() {
+                return Err(format!(
+                    "badge tier {} is missing a name or icon",
+                    tier.power
+                ));
+            }
+            if index > 0 {
+                let previous = &tiers[index - 1];
+                if previous.days == tier.days || previous.power >= tier.power {
+                    return Err(format!(
+                        "badge tiers {} and {} are not strictly increasing",
+                        previous.power, tier.power
+                    ));
+                }
+            }
+        }
+        Ok(BadgeTierTable { tiers })
+    }
+
+    /// Highest tier whose threshold `streak` has reached.
+    pub fn highest_for(&self, streak: i64) -> Option<&BadgeTier> {
+        self.tiers.iter().rev().find(|tier| tier.days <= streak)
+    }
+
+    /// Tier that follows `current`, or the first tier when nothing is earned.
+    pub fn next_after(&self, current: Option<&BadgeTier>) -> Option<&BadgeTier> {
+        match current {
+            None => self.tiers.first(),
+            Some(tier) => self
+                .tiers
+                .iter()
+                .find(|candidate| candidate.days > tier.days),
+        }
+    }
+}
+
+pub fn badge_tiers() -> Arc<BadgeTierTable> {
+    if let Ok(guard) = BADGE_TIERS.read() {
+        if let Some(table) = guard.as_ref() {
+            return table.clone();
+        }
+    }
+
+    let table = Arc::new(bundled_badge_tiers());
+    if let Ok(mut guard) = BADGE_TIERS.write() {
+        *guard = Some(table.clone());
+    }
+    table
+}
+
+fn bundled_badge_tiers() -> BadgeTierTable {
+    serde_json::from_str::<BadgeTierFile>(BUNDLED_BADGE_TIERS)
+        .map_err(|error| format!("failed to parse bundled badge tiers: {error}"))
+        .and_then(|file| BadgeTierTable::from_tiers(file.tiers))
+        .expect("bundled badge-tiers.json must be a valid tier table")
+}
+
+/// Replaces the bundled ladder with the server's `badge_tiers` rows, once per
+/// process. A missing table or invalid rows keep the bundled ladder; a failed
+/// request is retried on the next call.
+pub(crate) async fn ensure_server_badge_tiers(config: &SupabaseConfig) {
+    if SERVER_TIERS_CHECKED.load(Ordering::SeqCst) {
+        return;
+    }
+
+    match fetch_remote_badge_tiers(config).await {
+        Ok(tiers) => {
+            SERVER_TIERS_CHECKED.store(true, Ordering::SeqCst);
+            if tiers.is_empty() {
+                return;
+            }
+            match BadgeTierTable::from_tiers(tiers) {
+                Ok(table) => {
+                    if let Ok(mut guard) = BADGE_TIERS.write() {
+                        *guard = Some(Arc::new(table));
+                    }
+                }
+                Err(error) => {
+                    eprintln!("Ignoring invalid badge tiers from Supabase. {error}");
+                }
+            }
+        }
+        Err(error) => {
+            eprintln!("Failed to load badge tiers from Supabase. Using bundled tiers. {error}");
+        }
+    }
+}
+
+/// Server tier rows, or none when the table does not exist (404).
+async fn fetch_remote_badge_tiers(config: &SupabaseConfig) -> Result<Vec<BadgeTier>, String> {
+    let endpoint = format!(
+        "{}/rest/v1/{}",
+        config.url.trim_end_matches('/'),
+        BADGE_TIERS_TABLE_NAME
+    );
+    let client = &config.client;
+    let response = client
+        .get(endpoint)
+        .query(&[("select", "power,days,name,icon_id"), ("order", "days.asc")])
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .send()
+        .await
+        .map_err(|error| format!("supabase badge tier fetch failed: {error}"))?;
+    observe_server_date(&response);
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "supabase badge tier fetch failed with {status}: {body}"
+        ));
+    }
+
+    response
+        .json::<Vec<BadgeTier>>()
+        .await
+        .map_err(|error| format!("failed to decode badge tier response: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(power: i64, days: i64) -> BadgeTier {
+        BadgeTier {
+            power,
+            days,
+            name: format!("{days} Days"),
+            icon_id: format!("badge-{power}"),
+        }
+    }
+
+    #[test]
+    fn bundled_tiers_form_a_valid_ladder() {
+        let table = bundled_badge_tiers();
+        assert_eq!(table.highest_for(0).map(|tier| tier.power), None);
+        assert_eq!(table.highest_for(1).map(|tier| tier.power), Some(0));
+        assert_eq!(table.highest_for(3).map(|tier| tier.power), Some(1));
+        assert_eq!(
+            table.next_after(None).map(|tier| tier.days),
+            table.highest_for(1).map(|tier| tier.days)
+        );
+    }
+
+    #[test]
+    fn tiers_are_sorted_by_days() {
+        let table = BadgeTierTable::from_tiers(vec![tier(2, 7), tier(0, 1), tier(1, 3)]).unwrap();
+        let powers = table
+            .tiers
+            .iter()
+            .map(|tier| tier.power)
+            .collect::<Vec<_>>();
+        assert_eq!(powers, [0, 1, 2]);
+        let current = table.highest_for(5);
+        assert_eq!(current.map(|tier| tier.power), Some(1));
+        assert_eq!(table.next_after(current).map(|tier| tier.power), Some(2));
+        assert!(table.next_after(table.highest_for(30)).is_none());
+    }
+
+    #[test]
+    fn invalid_ladders_are_rejected() {
+        let mut unnamed = tier(1, 3);
+        unnamed.name = " ".into();
+        let mut no_icon = tier(1, 3);
+        no_icon.icon_id = String::new();
+        let cases = [
+            ("empty", vec![]),
+            ("duplicate days", vec![tier(0, 1), tier(1, 1)]),
+            ("duplicate power", vec![tier(0, 1), tier(0, 3)]),
+            ("power falls as days rise", vec![tier(1, 1), tier(0, 3)]),
+            ("zero days", vec![tier(0, 0), tier(1, 3)]),
+            ("missing name", vec![tier(0, 1), unnamed]),
+            ("missing icon", vec![tier(0, 1), no_icon]),
+        ];
+        for (label, tiers) in cases {
+            assert!(BadgeTierTable::from_tiers(tiers).is_err(), "{label}");
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Audits replay proofs for implausible input timing.
+//!
+//! Usage: `replay-audit [--json] [--min-suspicion <0..1>] [<file-or-dir>...]
+//! [--supabase <url> [--board <mode>[:<challenge-key>]] [--limit <n>]]`
+//! Directories are scanned for `*.json` replay proofs (for example the saved
+//! practice replays); `-` reads one proof from stdin. `--supabase` audits the
+//! top of a board instead, reading the `score_replays` rows kept by
+//! `verify-score`; those are readable with the service role key only, given by
+//! `--service-key` or `SUPABASE_SERVICE_ROLE_KEY`. Exits with status 1 when
+//! any proof would be refused.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use serde::Deserialize;
+use serde_json::Value;
+use torus_app_lib::plausibility::{analyze_replay_json, PlausibilityReport, PlausibilityVerdict};
+
+const USAGE: &str = "usage: replay-audit [--json] [--min-suspicion <0..1>] [<file-or-dir>...] \
+[--supabase <url> [--service-key <key>] [--board <mode>[:<challenge-key>]] [--limit <n>]]";
+const SERVICE_KEY_ENV: &str = "SUPABASE_SERVICE_ROLE_KEY";
+const SCORE_REPLAYS_TABLE_NAME: &str = "score_replays";
+const DEFAULT_BOARD_LIMIT: usize = 20;
+const MAX_BOARD_LIMIT: usize = 1000;
+
+/// A board on Supabase to audit from the top.
+struct RemoteBoard {
+    url: String,
+    service_key: String,
+    mode: String,
+    challenge_key: String,
+    limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScoreReplayRow {
+    client_uuid: String,
+    score: i64,
+    level: i64,
+    replay_proof: Value,
+}
+
+fn main() -> ExitCode {
+    let mut json_output = false;
+    let mut min_suspicion = 0.0_f64;
+    let mut targets = Vec::new();
+    let mut supabase_url = None;
+    let mut service_key = std::env::var(SERVICE_KEY_ENV).ok();
+    let mut board = "classic".to_string();
+    let mut limit = DEFAULT_BOARD_LIMIT;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json_output = true,
+            "--min-suspicion" => {
+                let Some(value) = args.next().and_then(|raw| raw.parse::<f64>().ok()) else {
+                    eprintln!("--min-suspicion needs a number between 0 and 1");
+                    return ExitCode::from(2);
+                };
+                min_suspicion = value;
+            }
+            "--limit" => {
+                let Some(value) = args
+                    .next()
+                    .and_then(|raw| raw.parse::<usize>().ok())
+                    .filter(|value| (1..=MAX_BOARD_LIMIT).contains(value))
+                else {
+                    eprintln!("--limit needs a number between 1 and {MAX_BOARD_LIMIT}");
+                    return ExitCode::from(2);
+                };
+                limit = value;
+            }
+            "--supabase" | "--service-key" | "--board" => {
+                let Some(value) = args.next() else {
+                    eprintln!("{arg} needs a value\n{USAGE}");
+                    return ExitCode::from(2);
+                };
+                match arg.as_str() {
+                    "--supabase" => supabase_url = Some(value),
+                    "--service-key" => service_key = Some(value),
+                    _ => board = value,
+                }
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => targets.push(arg),
+        }
+    }
+
+    let mut replays = Vec::new();
+    if let Some(url) = supabase_url {
+        let Some(service_key) = service_key.filter(|key| !key.trim().is_empty()) else {
+            eprintln!("--supabase needs the service role key (--service-key or {SERVICE_KEY_ENV})");
+            return ExitCode::from(2);
+        };
+        let (mode, challenge_key) = match board.split_once(':') {
+            Some((mode, key)) => (mode.to_string(), key.to_string()),
+            None => (board.clone(), board.clone()),
+        };
+        let remote = RemoteBoard {
+            url,
+            service_key,
+            mode,
+            challenge_key,
+            limit,
+        };
+        match tauri::async_runtime::block_on(fetch_board_replays(&remote)) {
+            Ok(rows) => replays.extend(rows),
+            Err(error) => {
+                eprintln!("{error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    } else if targets.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    }
+    for target in targets {
+        replays.extend(read_target(&target));
+    }
+
+    let mut any_refused = false;
+    for (label, raw) in replays {
+        let report = match raw.and_then(|raw| analyze_replay_json(&raw)) {
+            Ok(report) => report,
+            Err(error) => {
+                eprintln!("{label}: {error}");
+                continue;
+            }
+        };
+        any_refused |= report.verdict == PlausibilityVerdict::Refused;
+        if report.suspicion < min_suspicion {
+            continue;
+        }
+        print_report(&label, &report, json_output);
+    }
+
+    if any_refused {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// The board's top rows that have a stored replay, best first, labelled with
+/// their rank among those rows.
+async fn fetch_board_replays(
+    board: &RemoteBoard,
+) -> Result<Vec<(String, Result<String, String>)>, String> {
+    let endpoint = format!(
+        "{}/rest/v1/{}",
+        board.url.trim_end_matches('/'),
+        SCORE_REPLAYS_TABLE_NAME
+    );
+    let response = reqwest::Client::new()
+        .get(endpoint)
+        .query(&[
+            ("select", "client_uuid,score,level,replay_proof".to_string()),
+            ("mode", format!("eq.{}", board.mode)),
+            ("challenge_key", format!("eq.{}", board.challenge_key)),
+            ("order", "score.desc,level.desc,created_at.asc".to_string()),
+            ("limit", board.limit.to_string()),
+        ])
+        .header("apikey", &board.service_key)
+        .header("Authorization", format!("Bearer {}", board.service_key))
+        .send()
+        .await
+        .map_err(|error| format!("supabase replay fetch failed: {error}"))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "supabase replay fetch failed with {status}: {body}\n\
+Ensure /supabase/schema.sql has been applied (including table {SCORE_REPLAYS_TABLE_NAME})."
+        ));
+    }
+
+    let rows = response
+        .json::<Vec<ScoreReplayRow>>()
+        .await
+        .map_err(|error| format!("failed to decode replay rows: {error}"))?;
+    Ok(rows
+        .into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let label = format!(
+                "{}/{} #{} {} score={} level={}",
+                board.mode,
+                board.challenge_key,
+                index + 1,
+                row.client_uuid,
+                row.score,
+                row.level
+            );
+            (label, Ok(row.replay_proof.to_string()))
+        })
+        .collect())
+}
+
+fn read_target(target: &str) -> Vec<(String, Result<String, String>)> {
+    if target == "-" {
+        let mut raw = String::new();
+        let result = std::io::stdin()
+            .read_to_string(&mut raw)
+            .map(|_| raw)
+            .map_err(|error| format!("failed to read stdin: {error}"));
+        return vec![("stdin".to_string(), result)];
+    }
+
+    let path = Path::new(target);
+    let mut files = Vec::<PathBuf>::new();
+    if path.is_dir() {
+        match fs::read_dir(path) {
+            Ok(entries) => {
+                files.extend(
+                    entries
+                        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                        .filter(|path| path.extension().is_some_and(|ext| ext == "json")),
+                );
+                files.sort();
+            }
+            Err(error) => {
+                return vec![(
+                    target.to_string(),
+                    Err(format!("failed to read dir: {error}")),
+                )];
+            }
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+
+    files
+        .into_iter()
+        .map(|file| {
+            let result =
+                fs::read_to_string(&file).map_err(|error| format!("failed to read file: {error}"));
+            (file.display().to_string(), result)
+        })
+        .collect()
+}
+
+fn print_report(label: &str, report: &PlausibilityReport, json_output: bool) {
+    if json_output {
+        let body = serde_json::json!({ "replay": label, "report": report });
+        println!("{body}");
+        return;
+    }
+    let flags = if report.flags.is_empty() {
+        "-".to_string()
+    } else {
+        report.flags.join("; ")
+    };
+    println!(
+        "{label}: {:?} suspicion={:.2} moves={} maxPerTick={} maxPerSecond={} interval={:.2}±{:.2} ({} samples) flags={flags}",
+        report.verdict,
+        report.suspicion,
+        report.total_moves,
+        report.max_moves_per_tick,
+        report.max_moves_per_second,
+        report.interval_mean,
+        report.interval_std_dev,
+        report.interval_samples,
+    );
+}
@@ -0,0 +1,612 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+use torus_core::clock::current_utc_seconds;
+use torus_core::replay::{verify_replay_proof, DailyReplayProof};
+use uuid::Uuid;
+
+use crate::friends::friend_code_for_device;
+use crate::scoreboard::{
+    app_data_dir, get_or_create_device_uuid, sanitize_daily_replay_proof, sanitize_entry,
+    ScoreEntry,
+};
+
+const CHALLENGE_CODES_FILE_NAME: &str = "friend-challenges-v1.json";
+const CHALLENGE_CODE_PREFIX: &str = "TORUS";
+const CHALLENGE_CODE_VERSION: u8 = 2;
+const CHALLENGE_CODE_GROUP_LEN: usize = 5;
+const CHALLENGE_CODE_MAX_NAME_BYTES: usize = 80;
+const CHALLENGE_CODE_CHECKSUM_LEN: usize = 4;
+/// The challenger's friend code without its dash.
+const CHALLENGER_ID_LEN: usize = 10;
+const RULE_NO_SKILLS: u8 = 0b0000_0001;
+/// Crockford base32: no I, L, O or U, so codes survive being read aloud.
+const CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ChallengeRules {
+    #[serde(rename = "noSkills", default)]
+    pub no_skills: bool,
+}
+
+/// Everything a challenge code carries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChallengeCodePayload {
+    seed: u32,
+    difficulty: u8,
+    rules: u8,
+    target_score: u32,
+    target_level: u16,
+    /// Friend code of the device that created the challenge. Names can change
+    /// or collide, so head-to-head records are keyed by this instead.
+    challenger_id: String,
+    challenger_name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedChallengeCode {
+    code: String,
+    seed: u32,
+    difficulty: i64,
+    rules: ChallengeRules,
+    #[serde(rename = "targetScore")]
+    target_score: i64,
+    #[serde(rename = "targetLevel")]
+    target_level: i64,
+    #[serde(rename = "challengerName")]
+    challenger_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptedChallenge {
+    #[serde(rename = "challengeId")]
+    challenge_id: String,
+    seed: u32,
+    difficulty: i64,
+    rules: ChallengeRules,
+    #[serde(rename = "targetScore")]
+    target_score: i64,
+    #[serde(rename = "targetLevel")]
+    target_level: i64,
+    #[serde(rename = "challengerId")]
+    challenger_id: String,
+    #[serde(rename = "challengerName")]
+    challenger_name: String,
+    #[serde(rename = "acceptedAt")]
+    accepted_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeadToHeadRecord {
+    /// Friend code of the challenger, the key of the record.
+    #[serde(rename = "friendId")]
+    friend_id: String,
+    /// Name from the friend's latest code.
+    #[serde(rename = "friendName")]
+    friend_name: String,
+    wins: i64,
+    losses: i64,
+    ties: i64,
+    #[serde(rename = "lastPlayedAt")]
+    last_played_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChallengeRunOutcome {
+    /// `win`, `loss` or `tie`, from the accepting player's side.
+    result: &'static str,
+    score: i64,
+    level: i64,
+    #[serde(rename = "targetScore")]
+    target_score: i64,
+    #[serde(rename = "targetLevel")]
+    target_level: i64,
+    record: HeadToHeadRecord,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ChallengeLedger {
+    #[serde(default)]
+    pending: BTreeMap<String, AcceptedChallenge>,
+    /// Keyed by the challenger's friend code.
+    #[serde(rename = "headToHead", default)]
+    head_to_head: BTreeMap<String, HeadToHeadRecord>,
+}
+
+/// Turns a finished run into a code a friend can paste to play the same seed
+/// and try to beat the score.
+#[tauri::command]
+pub async fn create_challenge_code<R: Runtime>(
+    app: AppHandle<R>,
+    entry: ScoreEntry,
+    replay_proof: DailyReplayProof,
+    rules: Option<ChallengeRules>,
+) -> Result<CreatedChallengeCode, String> {
+    let rules = rules.unwrap_or_default();
+    let entry = sanitize_entry(entry)?;
+    let replay_proof = sanitize_daily_replay_proof(replay_proof)?;
+    if rules.no_skills && !entry.skill_usage.is_empty() {
+        return Err("a no-skills challenge needs a run without skills".into());
+    }
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let verification = verify_replay_proof(&replay_proof);
+    if !verification.ok {
+        return Err(format!(
+            "challenge replay did not verify: {}",
+            verification.reason.unwrap_or("unknown reason")
+        ));
+    }
+
+    let payload = ChallengeCodePayload {
+        seed: replay_proof.seed,
+        difficulty: replay_proof.difficulty as u8,
+        rules: encode_rules(rules),
+        target_score: u32::try_from(verification.actual.score)
+            .map_err(|_| "challenge score is out of range".to_string())?,
+        target_level: u16::try_from(verification.actual.level)
+            .map_err(|_| "challenge level is out of range".to_string())?,
+        challenger_id: friend_code_for_device(&device_uuid).replace('-', ""),
+        challenger_name: truncate_name_bytes(&entry.user),
+    };
+    Ok(CreatedChallengeCode {
+        code: encode_challenge_code(&payload),
+        seed: payload.seed,
+        difficulty: payload.difficulty as i64,
+        rules,
+        target_score: payload.target_score as i64,
+        target_level: payload.target_level as i64,
+        challenger_name: payload.challenger_name,
+    })
+}
+
+/// Decodes and validates a code, then opens a seeded run against it.
+#[tauri::command]
+pub async fn accept_challenge_code<R: Runtime>(
+    app: AppHandle<R>,
+    code: String,
+) -> Result<AcceptedChallenge, String> {
+    let payload = decode_challenge_code(&code)?;
+    let accepted = AcceptedChallenge {
+        challenge_id: Uuid::new_v4().to_string(),
+        seed: payload.seed,
+        difficulty: payload.difficulty as i64,
+        rules: decode_rules(payload.rules),
+        target_score: payload.target_score as i64,
+        target_level: payload.target_level as i64,
+        challenger_id: format_challenger_id(&payload.challenger_id),
+        challenger_name: payload.challenger_name,
+        accepted_at: current_utc_seconds(),
+    };
+
+    let mut ledger = read_ledger(&app)?;
+    ledger
+        .pending
+        .insert(accepted.challenge_id.clone(), accepted.clone());
+    write_ledger(&app, &ledger)?;
+    Ok(accepted)
+}
+
+/// Scores a finished run against the accepted challenge and updates the
+/// head-to-head record with that friend.
+#[tauri::command]
+pub async fn complete_challenge_run<R: Runtime>(
+    app: AppHandle<R>,
+    challenge_id: String,
+    entry: ScoreEntry,
+    replay_proof: DailyReplayProof,
+) -> Result<ChallengeRunOutcome, String> {
+    let mut ledger = read_ledger(&app)?;
+    let challenge = ledger
+        .pending
+        .remove(challenge_id.trim())
+        .ok_or_else(|| "friend challenge is unknown or already finished".to_string())?;
+    let entry = sanitize_entry(entry)?;
+    let replay_proof = sanitize_daily_replay_proof(replay_proof)?;
+    if replay_proof.seed != challenge.seed || replay_proof.difficulty != challenge.difficulty {
+        return Err("challenge run does not match the challenge seed".into());
+    }
+    if challenge.rules.no_skills && !entry.skill_usage.is_empty() {
+        return Err("skills are not allowed in this challenge".into());
+    }
+    let verification = verify_replay_proof(&replay_proof);
+    if !verification.ok {
+        return Err(format!(
+            "challenge replay did not verify: {}",
+            verification.reason.unwrap_or("unknown reason")
+        ));
+    }
+
+    let score = verification.actual.score;
+    let level = verification.actual.level;
+    let result = match (score, level).cmp(&(challenge.target_score, challenge.target_level)) {
+        std::cmp::Ordering::Greater => "win",
+        std::cmp::Ordering::Less => "loss",
+        std::cmp::Ordering::Equal => "tie",
+    };
+
+    let record = ledger
+        .head_to_head
+        .entry(challenge.challenger_id.clone())
+        .or_default();
+    record.friend_id = challenge.challenger_id.clone();
+    record.friend_name = challenge.challenger_name.clone();
+    match result {
+        "win" => record.wins += 1,
+        "loss" => record.losses += 1,
+        _ => record.ties += 1,
+    }
+    record.last_played_at = current_utc_seconds();
+    let record = record.clone();
+    write_ledger(&app, &ledger)?;
+
+    Ok(ChallengeRunOutcome {
+        result,
+        score,
+        level,
+        target_score: challenge.target_score,
+        target_level: challenge.target_level,
+        record,
+    })
+}
+
+#[tauri::command]
+pub async fn fetch_head_to_head<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<HeadToHeadRecord>, String> {
+    let ledger = read_ledger(&app)?;
+    let mut records = ledger.head_to_head.into_values().collect::<Vec<_>>();
+    records.sort_by_key(|record| std::cmp::Reverse(record.last_played_at));
+    Ok(records)
+}
+
+fn encode_rules(rules: ChallengeRules) -> u8 {
+    if rules.no_skills {
+        RULE_NO_SKILLS
+    } else {
+        0
+    }
+}
+
+fn decode_rules(bits: u8) -> ChallengeRules {
+    ChallengeRules {
+        no_skills: bits & RULE_NO_SKILLS != 0,
+    }
+}
+
+fn format_challenger_id(compact: &str) -> String {
+    format!("{}-{}", &compact[..5], &compact[5..])
+}
+
+fn truncate_name_bytes(name: &str) -> String {
+    let mut truncated = String::new();
+    for ch in name.chars() {
+        if truncated.len() + ch.len_utf8() > CHALLENGE_CODE_MAX_NAME_BYTES {
+            break;
+        }
+        truncated.push(ch);
+    }
+    truncated
+}
+
+/// Layout: version, difficulty, rules, seed (u32), target score (u32), target
+/// level (u16), challenger id (10 ASCII bytes), name length, name bytes, then
+/// a 4-byte FNV-1a checksum of all preceding bytes. The bytes are base32 encoded and grouped with dashes.
+fn encode_challenge_code(payload: &ChallengeCodePayload) -> String {
+    let mut bytes = vec![CHALLENGE_CODE_VERSION, payload.difficulty, payload.rules];
+    bytes.extend_from_slice(&payload.seed.to_be_bytes());
+    bytes.extend_from_slice(&payload.target_score.to_be_bytes());
+    bytes.extend_from_slice(&payload.target_level.to_be_bytes());
+    bytes.extend_from_slice(payload.challenger_id.as_bytes());
+    bytes.push(payload.challenger_name.len() as u8);
+    bytes.extend_from_slice(payload.challenger_name.as_bytes());
+    bytes.extend_from_slice(&checksum(&bytes).to_be_bytes());
+
+    let encoded = encode_base32(&bytes);
+    let groups = encoded
+        .as_bytes()
+        .chunks(CHALLENGE_CODE_GROUP_LEN)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect::<Vec<_>>();
+    format!("{CHALLENGE_CODE_PREFIX}-{}", groups.join("-"))
+}
+
+fn decode_challenge_code(raw: &str) -> Result<ChallengeCodePayload, String> {
+    let normalized = raw
+        .trim()
+        .to_ascii_uppercase()
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && *ch != '-')
+        .collect::<String>();
+    let body = normalized
+        .strip_prefix(CHALLENGE_CODE_PREFIX)
+        .ok_or_else(|| "challenge code must start with TORUS".to_string())?;
+    let bytes = decode_base32(body)?;
+    let header_len = 3 + 4 + 4 + 2 + CHALLENGER_ID_LEN + 1;
+    if bytes.len() < header_len + CHALLENGE_CODE_CHECKSUM_LEN {
+        return Err("challenge code is too short".into());
+    }
+
+    let (content, stored_checksum) = bytes.split_at(bytes.len() - CHALLENGE_CODE_CHECKSUM_LEN);
+    let stored_checksum = u32::from_be_bytes([
+        stored_checksum[0],
+        stored_checksum[1],
+        stored_checksum[2],
+        stored_checksum[3],
+    ]);
+    if checksum(content) != stored_checksum {
+        return Err("challenge code checksum does not match (typo?)".into());
+    }
+    if content[0] != CHALLENGE_CODE_VERSION {
+        return Err("challenge code version is not supported".into());
+    }
+
+    let difficulty = content[1];
+    if !(1..=3).contains(&difficulty) {
+        return Err("challenge code difficulty is invalid".into());
+    }
+    let rules = content[2];
+    if rules & !RULE_NO_SKILLS != 0 {
+        return Err("challenge code uses rules this version does not know".into());
+    }
+    let seed = u32::from_be_bytes([content[3], content[4], content[5], content[6]]);
+    let target_score = u32::from_be_bytes([content[7], content[8], content[9], content[10]]);
+    let target_level = u16::from_be_bytes([content[11], content[12]]);
+    let challenger_id = &content[13..13 + CHALLENGER_ID_LEN];
+    if !challenger_id
+        .iter()
+        .all(|byte| CODE_ALPHABET.contains(byte))
+    {
+        return Err("challenge code challenger id is malformed".into());
+    }
+    let challenger_id = String::from_utf8_lossy(challenger_id).into_owned();
+    let name_len = content[header_len - 1] as usize;
+    let name_bytes = &content[header_len..];
+    if name_bytes.len() != name_len || name_len > CHALLENGE_CODE_MAX_NAME_BYTES {
+        return Err("challenge code name is malformed".into());
+    }
+    let challenger_name = String::from_utf8(name_bytes.to_vec())
+        .map_err(|_| "challenge code name is not valid UTF-8".to_string())?;
+    if challenger_name.trim().is_empty() {
+        return Err("challenge code is missing the challenger name".into());
+    }
+
+    Ok(ChallengeCodePayload {
+        seed,
+        difficulty,
+        rules,
+        target_score,
+        target_level,
+        challenger_id,
+        challenger_name,
+    })
+}
+
+fn checksum(bytes: &[u8]) -> u32 {
+    let mut hash = 2_166_136_261_u32;
+    for byte in bytes {
+        hash ^= *byte as u32;
+        hash = hash.wrapping_mul(16_777_619);
+    }
+    hash
+}
+
+pub(crate) fn encode_base32(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len() * 8 / 5 + 1);
+    let mut buffer = 0_u32;
+    let mut bits = 0_u32;
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(CODE_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(CODE_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+fn decode_base32(raw: &str) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(raw.len() * 5 / 8);
+    let mut buffer = 0_u32;
+    let mut bits = 0_u32;
+    for ch in raw.chars() {
+        let value = match ch {
+            'O' => 0,
+            'I' | 'L' => 1,
+            _ => CODE_ALPHABET
+                .iter()
+                .position(|candidate| *candidate as char == ch)
+                .ok_or_else(|| format!("challenge code contains an invalid character '{ch}'"))?
+                as u32,
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+    Ok(output)
+}
+
+fn ledger_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(CHALLENGE_CODES_FILE_NAME);
+    Ok(dir)
+}
+
+fn read_ledger<R: Runtime>(app: &AppHandle<R>) -> Result<ChallengeLedger, String> {
+    let path = ledger_path(app)?;
+    if !path.exists() {
+        return Ok(ChallengeLedger::default());
+    }
+
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read friend challenges: {error}"))?;
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn write_ledger<R: Runtime>(app: &AppHandle<R>, ledger: &ChallengeLedger) -> Result<(), String> {
+    let path = ledger_path(app)?;
+    let body = serde_json::to_string(ledger)
+        .map_err(|error| format!("failed to serialize friend challenges: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write friend challenges: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(name: &str) -> ChallengeCodePayload {
+        ChallengeCodePayload {
+            seed: 0xdead_beef,
+            difficulty: 3,
+            rules: RULE_NO_SKILLS,
+            target_score: 48_250,
+            target_level: 12,
+            challenger_id: "7K3M9QXR2A".into(),
+            challenger_name: name.into(),
+        }
+    }
+
+    /// Replaces the character at `index` of the code body with another one
+    /// from the alphabet.
+    fn corrupt(code: &str, index: usize) -> String {
+        let mut chars = code.chars().collect::<Vec<_>>();
+        let position = chars
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| **ch != '-')
+            .map(|(position, _)| position)
+            .nth(CHALLENGE_CODE_PREFIX.len() + index)
+            .unwrap();
+        chars[position] = if chars[position] == 'A' { 'B' } else { 'A' };
+        chars.into_iter().collect()
+    }
+
+    #[test]
+    fn base32_round_trips_every_length() {
+        let bytes = (0..=255_u8).collect::<Vec<_>>();
+        for len in 0..40 {
+            let encoded = encode_base32(&bytes[..len]);
+            assert!(encoded.bytes().all(|byte| CODE_ALPHABET.contains(&byte)));
+            assert_eq!(decode_base32(&encoded).unwrap(), &bytes[..len]);
+        }
+        assert_eq!(encode_base32(&[0xff; 5]), "ZZZZZZZZ");
+    }
+
+    #[test]
+    fn base32_reads_confusable_letters_as_digits() {
+        assert_eq!(decode_base32("O0").unwrap(), decode_base32("00").unwrap());
+        assert_eq!(decode_base32("IL").unwrap(), decode_base32("11").unwrap());
+        assert!(decode_base32("UU").unwrap_err().contains("'U'"));
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        for name in ["Ann", "Zoë 🦊", &"x".repeat(CHALLENGE_CODE_MAX_NAME_BYTES)] {
+            let payload = payload(name);
+            let code = encode_challenge_code(&payload);
+            assert!(code.starts_with("TORUS-"));
+            assert!(code
+                .split('-')
+                .skip(1)
+                .all(|group| group.len() <= CHALLENGE_CODE_GROUP_LEN));
+            assert_eq!(decode_challenge_code(&code).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn codes_tolerate_case_spacing_and_missing_dashes() {
+        let payload = payload("Ann");
+        let code = encode_challenge_code(&payload);
+        let retyped = format!("  {} ", code.to_ascii_lowercase().replace('-', " "));
+        assert_eq!(decode_challenge_code(&retyped).unwrap(), payload);
+        let compact = code.replace('-', "");
+        assert_eq!(decode_challenge_code(&compact).unwrap(), payload);
+    }
+
+    #[test]
+    fn any_single_character_typo_fails_the_checksum() {
+        let code = encode_challenge_code(&payload("Ann"));
+        let body_len = code.replace('-', "").len() - CHALLENGE_CODE_PREFIX.len();
+        // The final character only carries padding bits.
+        for index in 0..body_len - 1 {
+            let error = decode_challenge_code(&corrupt(&code, index)).unwrap_err();
+            assert!(error.contains("checksum"), "typo at {index}: {error}");
+        }
+    }
+
+    #[test]
+    fn malformed_codes_are_refused() {
+        let code = encode_challenge_code(&payload("Ann"));
+        assert!(decode_challenge_code(&code.replacen("TORUS", "TORSO", 1))
+            .unwrap_err()
+            .contains("must start with TORUS"));
+        assert!(decode_challenge_code("TORUS-ABCDE")
+            .unwrap_err()
+            .contains("too short"));
+        assert!(decode_challenge_code(&format!("{code}U"))
+            .unwrap_err()
+            .contains("invalid character"));
+        let truncated = &code[..code.len() - 3];
+        assert!(decode_challenge_code(truncated).is_err());
+    }
+
+    /// Re-encodes raw content with a valid checksum, so only the field checks
+    /// behind the checksum can refuse it.
+    fn code_from_content(mut content: Vec<u8>) -> String {
+        content.extend_from_slice(&checksum(&content).to_be_bytes());
+        format!("{CHALLENGE_CODE_PREFIX}{}", encode_base32(&content))
+    }
+
+    #[test]
+    fn checksummed_codes_with_bad_fields_are_refused() {
+        let code = encode_challenge_code(&payload("Ann"));
+        let bytes = decode_base32(&code.replace('-', "")[CHALLENGE_CODE_PREFIX.len()..]).unwrap();
+        let content = bytes[..bytes.len() - CHALLENGE_CODE_CHECKSUM_LEN].to_vec();
+
+        let cases: [(usize, u8, &str); 5] = [
+            (0, 1, "version"),
+            (1, 4, "difficulty"),
+            (2, 0b10, "rules"),
+            (13, b'U', "challenger id"),
+            (23, 4, "name is malformed"),
+        ];
+        for (index, value, expected) in cases {
+            let mut changed = content.clone();
+            changed[index] = value;
+            let error = decode_challenge_code(&code_from_content(changed)).unwrap_err();
+            assert!(error.contains(expected), "byte {index}: {error}");
+        }
+
+        let mut blank = content[..content.len() - 3].to_vec();
+        blank.extend_from_slice(b"   ");
+        let error = decode_challenge_code(&code_from_content(blank)).unwrap_err();
+        assert!(error.contains("missing the challenger name"), "{error}");
+    }
+
+    #[test]
+    fn names_are_truncated_on_character_boundaries() {
+        let name = "é".repeat(CHALLENGE_CODE_MAX_NAME_BYTES);
+        let truncated = truncate_name_bytes(&name);
+        assert_eq!(truncated.len(), CHALLENGE_CODE_MAX_NAME_BYTES);
+        assert_eq!(truncated.chars().count(), CHALLENGE_CODE_MAX_NAME_BYTES / 2);
+        assert_eq!(truncate_name_bytes("Ann"), "Ann");
+    }
+
+    #[test]
+    fn challenger_ids_are_friend_codes() {
+        let compact = friend_code_for_device("device-a").replace('-', "");
+        assert_eq!(compact.len(), CHALLENGER_ID_LEN);
+        assert_eq!(
+            format_challenger_id(&compact),
+            friend_code_for_device("device-a")
+        );
+    }
+}
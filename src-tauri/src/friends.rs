@@ -0,0 +1,421 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+use torus_core::clock::current_utc_seconds;
+
+use crate::challenge_codes::encode_base32;
+use crate::scoreboard::{
+    app_data_dir, get_or_create_device_secret, get_or_create_device_uuid,
+    normalize_supabase_config, observe_server_date, SupabaseConfig,
+};
+
+const FRIENDS_FILE_NAME: &str = "friends-v1.json";
+const PUBLISH_FRIEND_CODE_RPC_NAME: &str = "publish_friend_code";
+const RESOLVE_FRIEND_CODES_RPC_NAME: &str = "resolve_friend_codes";
+const FRIEND_CODE_SALT: &str = "torus-friend-v1:";
+const FRIEND_CODE_LEN: usize = 10;
+const MAX_FRIENDS: usize = 200;
+const MAX_FRIEND_NICKNAME_LEN: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Friend {
+    #[serde(rename = "friendCode")]
+    friend_code: String,
+    nickname: String,
+    /// Device the code belongs to, once resolved through Supabase.
+    #[serde(rename = "clientUuid", default)]
+    client_uuid: Option<String>,
+    #[serde(default)]
+    resolved: bool,
+    #[serde(rename = "addedAt")]
+    added_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FriendCodeInfo {
+    #[serde(rename = "friendCode")]
+    friend_code: String,
+    published: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FriendsFile {
+    #[serde(default)]
+    friends: Vec<Friend>,
+    #[serde(rename = "publishedCode", default)]
+    published_code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct PublishFriendCodePayload<'a> {
+    p_client_uuid: &'a str,
+    p_device_secret: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct PublishedFriendCode {
+    #[serde(rename = "friendCode")]
+    friend_code: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ResolveFriendCodesPayload<'a> {
+    p_friend_codes: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct FriendCodeRow {
+    friend_code: String,
+    client_uuid: String,
+}
+
+/// Devices whose scoreboard rows count as friends, and whether a board should
+/// be narrowed down to them.
+pub(crate) struct FriendScope {
+    pub(crate) client_uuids: HashSet<String>,
+    pub(crate) only: bool,
+}
+
+impl FriendScope {
+    pub(crate) fn is_friend(&self, client_uuid: Option<&str>) -> bool {
+        client_uuid
+            .map(|value| self.client_uuids.contains(value))
+            .unwrap_or(false)
+    }
+}
+
+/// This device's public friend code. With Supabase configured the code is
+/// published once so friends can resolve it; the server derives the code from
+/// the device UUID itself, so a device can only publish its own.
+#[tauri::command]
+pub async fn get_friend_code<R: Runtime>(
+    app: AppHandle<R>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<FriendCodeInfo, String> {
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let friend_code = friend_code_for_device(&device_uuid);
+    let mut file = read_friends(&app)?;
+    if file.published_code.as_deref() != Some(friend_code.as_str()) {
+        if let Some(config) = normalize_supabase_config(&app, supabase_url, supabase_anon_key) {
+            let device_secret = get_or_create_device_secret(&app)?;
+            match publish_remote_friend_code(&config, &device_uuid, &device_secret).await {
+                Ok(published) if published == friend_code => {
+                    file.published_code = Some(friend_code.clone());
+                    write_friends(&app, &file)?;
+                }
+                Ok(published) => {
+                    eprintln!("Server derived friend code {published}, expected {friend_code}.");
+                }
+                Err(error) => {
+                    eprintln!("Failed to publish friend code. {error}");
+                }
+            }
+        }
+    }
+    Ok(FriendCodeInfo {
+        published: file.published_code.as_deref() == Some(friend_code.as_str()),
+        friend_code,
+    })
+}
+
+#[tauri::command]
+pub async fn list_friends<R: Runtime>(app: AppHandle<R>) -> Result<Vec<Friend>, String> {
+    Ok(read_friends(&app)?.friends)
+}
+
+#[tauri::command]
+pub async fn add_friend<R: Runtime>(
+    app: AppHandle<R>,
+    friend_code: String,
+    nickname: Option<String>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<Friend, String> {
+    let normalized_code = normalize_friend_code(&friend_code)?;
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    if normalized_code == friend_code_for_device(&device_uuid) {
+        return Err("that is your own friend code".into());
+    }
+
+    let mut file = read_friends(&app)?;
+    if file
+        .friends
+        .iter()
+        .all(|friend| friend.friend_code != normalized_code)
+        && file.friends.len() >= MAX_FRIENDS
+    {
+        return Err(format!("friends list is limited to {MAX_FRIENDS} entries"));
+    }
+    let nickname = nickname
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(MAX_FRIEND_NICKNAME_LEN)
+        .collect::<String>();
+    let mut friend = Friend {
+        nickname: if nickname.is_empty() {
+            normalized_code.clone()
+        } else {
+            nickname
+        },
+        friend_code: normalized_code,
+        client_uuid: None,
+        resolved: false,
+        added_at: current_utc_seconds(),
+    };
+    if let Some(config) = normalize_supabase_config(&app, supabase_url, supabase_anon_key) {
+        match resolve_remote_friend_codes(&config, std::slice::from_ref(&friend.friend_code)).await
+        {
+            Ok(rows) => {
+                friend.client_uuid = rows.into_iter().next().map(|row| row.client_uuid);
+                friend.resolved = friend.client_uuid.is_some();
+            }
+            Err(error) => {
+                eprintln!("Failed to resolve friend code. Friend saved unresolved. {error}");
+            }
+        }
+    }
+
+    file.friends
+        .retain(|existing| existing.friend_code != friend.friend_code);
+    file.friends.push(friend.clone());
+    write_friends(&app, &file)?;
+    Ok(friend)
+}
+
+#[tauri::command]
+pub async fn remove_friend<R: Runtime>(
+    app: AppHandle<R>,
+    friend_code: String,
+) -> Result<Vec<Friend>, String> {
+    let normalized_code = normalize_friend_code(&friend_code)?;
+    let mut file = read_friends(&app)?;
+    file.friends
+        .retain(|friend| friend.friend_code != normalized_code);
+    write_friends(&app, &file)?;
+    Ok(file.friends)
+}
+
+/// Loads the friend set used to mark and filter scoreboard rows, resolving any
+/// codes that could not be resolved when they were added.
+pub(crate) async fn load_friend_scope<R: Runtime>(
+    app: &AppHandle<R>,
+    config: Option<&SupabaseConfig>,
+    only: bool,
+) -> FriendScope {
+    let mut file = match read_friends(app) {
+        Ok(file) => file,
+        Err(error) => {
+            eprintln!("Failed to read friends list. {error}");
+            FriendsFile::default()
+        }
+    };
+
+    let unresolved = file
+        .friends
+        .iter()
+        .filter(|friend| !friend.resolved)
+        .map(|friend| friend.friend_code.clone())
+        .collect::<Vec<_>>();
+    if let (Some(config), false) = (config, unresolved.is_empty()) {
+        match resolve_remote_friend_codes(config, &unresolved).await {
+            Ok(rows) if !rows.is_empty() => {
+                for row in rows {
+                    if let Some(friend) = file
+                        .friends
+                        .iter_mut()
+                        .find(|friend| friend.friend_code == row.friend_code)
+                    {
+                        friend.client_uuid = Some(row.client_uuid);
+                        friend.resolved = true;
+                    }
+                }
+                if let Err(error) = write_friends(app, &file) {
+                    eprintln!("Failed to save resolved friends. {error}");
+                }
+            }
+            Ok(_) => {}
+            Err(error) => {
+                eprintln!("Failed to resolve friend codes. {error}");
+            }
+        }
+    }
+
+    FriendScope {
+        client_uuids: file
+            .friends
+            .into_iter()
+            .filter_map(|friend| friend.client_uuid)
+            .collect(),
+        only,
+    }
+}
+
+/// Public code for a device: the first 50 bits of a salted SHA-256 of the
+/// device UUID, matching `public.friend_code_for_device` in the schema.
+pub(crate) fn friend_code_for_device(device_uuid: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(FRIEND_CODE_SALT)
+        .chain_update(device_uuid)
+        .finalize();
+    let encoded = encode_base32(&digest[..8]);
+    format!("{}-{}", &encoded[..5], &encoded[5..FRIEND_CODE_LEN])
+}
+
+fn normalize_friend_code(raw: &str) -> Result<String, String> {
+    let compact = raw
+        .trim()
+        .to_ascii_uppercase()
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && *ch != '-')
+        .map(|ch| match ch {
+            'O' => '0',
+            'I' | 'L' => '1',
+            other => other,
+        })
+        .collect::<String>();
+    let is_valid = compact.len() == FRIEND_CODE_LEN
+        && compact
+            .bytes()
+            .all(|ch| ch.is_ascii_digit() || (ch.is_ascii_uppercase() && ch != b'U'));
+    if !is_valid {
+        return Err("friend code must be 10 characters, like ABCDE-12345".into());
+    }
+    Ok(format!("{}-{}", &compact[..5], &compact[5..]))
+}
+
+async fn publish_remote_friend_code(
+    config: &SupabaseConfig,
+    device_uuid: &str,
+    device_secret: &str,
+) -> Result<String, String> {
+    let endpoint = format!(
+        "{}/rest/v1/rpc/{}",
+        config.url.trim_end_matches('/'),
+        PUBLISH_FRIEND_CODE_RPC_NAME
+    );
+    let payload = PublishFriendCodePayload {
+        p_client_uuid: device_uuid,
+        p_device_secret: device_secret,
+    };
+
+    let client = &config.client;
+    let response = client
+        .post(endpoint)
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|error| format!("supabase friend code publish failed: {error}"))?;
+    observe_server_date(&response);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "supabase friend code publish failed with {status}: {body}\n\
+Ensure /supabase/schema.sql has been applied (including RPC {PUBLISH_FRIEND_CODE_RPC_NAME})."
+        ));
+    }
+
+    response
+        .json::<PublishedFriendCode>()
+        .await
+        .map(|published| published.friend_code)
+        .map_err(|error| format!("failed to decode friend code publish response: {error}"))
+}
+
+/// Looks up the devices behind codes the player already has. The mapping table
+/// itself is not readable, so codes cannot be enumerated.
+async fn resolve_remote_friend_codes(
+    config: &SupabaseConfig,
+    friend_codes: &[String],
+) -> Result<Vec<FriendCodeRow>, String> {
+    let endpoint = format!(
+        "{}/rest/v1/rpc/{}",
+        config.url.trim_end_matches('/'),
+        RESOLVE_FRIEND_CODES_RPC_NAME
+    );
+    let payload = ResolveFriendCodesPayload {
+        p_friend_codes: friend_codes,
+    };
+    let client = &config.client;
+    let response = client
+        .post(endpoint)
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|error| format!("supabase friend code lookup failed: {error}"))?;
+    observe_server_date(&response);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "supabase friend code lookup failed with {status}: {body}\n\
+Ensure /supabase/schema.sql has been applied (including RPC {RESOLVE_FRIEND_CODES_RPC_NAME})."
+        ));
+    }
+
+    response
+        .json::<Vec<FriendCodeRow>>()
+        .await
+        .map_err(|error| format!("failed to decode friend code response: {error}"))
+}
+
+fn friends_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(FRIENDS_FILE_NAME);
+    Ok(dir)
+}
+
+fn read_friends<R: Runtime>(app: &AppHandle<R>) -> Result<FriendsFile, String> {
+    let path = friends_path(app)?;
+    if !path.exists() {
+        return Ok(FriendsFile::default());
+    }
+
+    let raw =
+        fs::read_to_string(&path).map_err(|error| format!("failed to read friends: {error}"))?;
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn write_friends<R: Runtime>(app: &AppHandle<R>, file: &FriendsFile) -> Result<(), String> {
+    let path = friends_path(app)?;
+    let body = serde_json::to_string(file)
+        .map_err(|error| format!("failed to serialize friends: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write friends: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn friend_codes_match_the_schema_derivation() {
+        // From `select public.friend_code_for_device(...)` on a migrated database.
+        assert_eq!(friend_code_for_device("device-a"), "2ZTKV-8WFCT");
+        assert_eq!(
+            friend_code_for_device("3f2504e0-4f89-11d3-9a0c-0305e82c3301"),
+            "JPP3M-WW9GC"
+        );
+    }
+
+    #[test]
+    fn typed_codes_are_normalized() {
+        assert_eq!(
+            normalize_friend_code(" 2ztkv 8wfct ").unwrap(),
+            "2ZTKV-8WFCT"
+        );
+        assert_eq!(normalize_friend_code("OILOI-00000").unwrap(), "01101-00000");
+        assert!(normalize_friend_code("2ZTKV-8WFCU").is_err());
+        assert!(normalize_friend_code("2ZTKV").is_err());
+    }
+}
@@ -0,0 +1,577 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+use torus_core::challenge_period::ChallengePeriod;
+use uuid::Uuid;
+
+use crate::friends;
+use crate::name_policy;
+use crate::profile;
+use crate::scoreboard::{
+    app_data_dir, fetch_remote_challenge_scores, fetch_remote_scores, get_or_create_device_secret,
+    get_or_create_device_uuid, is_current_streak_alive, normalize_limit, normalize_supabase_config,
+    observe_server_date, quote_postgrest_text, ScoreEntry, SupabaseConfig,
+};
+
+const GROUPS_FILE_NAME: &str = "groups-v1.json";
+const CREATE_GROUP_RPC_NAME: &str = "create_group";
+const JOIN_GROUP_RPC_NAME: &str = "join_group";
+const LEAVE_GROUP_RPC_NAME: &str = "leave_group";
+const LIST_GROUP_MEMBERS_RPC_NAME: &str = "list_group_members";
+const LIST_MY_GROUPS_RPC_NAME: &str = "list_my_groups";
+const CLASSIC_BOARD: &str = "classic";
+const MAX_GROUP_NAME_LEN: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Group {
+    #[serde(rename = "groupId")]
+    group_id: String,
+    name: String,
+    /// Only `create_group` and `join_group` return the code; listings keep the
+    /// one remembered locally, so the server never hands it out again.
+    #[serde(rename = "inviteCode", default)]
+    invite_code: Option<String>,
+    #[serde(rename = "ownerClientUuid", default)]
+    owner_client_uuid: String,
+    #[serde(rename = "memberCount", default)]
+    member_count: i64,
+    #[serde(rename = "isOwner", default)]
+    is_owner: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupStreakMember {
+    #[serde(rename = "playerName")]
+    player_name: String,
+    #[serde(rename = "currentStreak")]
+    current_streak: i64,
+    #[serde(rename = "maxStreak")]
+    max_streak: i64,
+    #[serde(rename = "playedToday")]
+    played_today: bool,
+    #[serde(rename = "isMe")]
+    is_me: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupStreakSummary {
+    #[serde(rename = "groupId")]
+    group_id: String,
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    #[serde(rename = "memberCount")]
+    member_count: usize,
+    #[serde(rename = "playedTodayCount")]
+    played_today_count: usize,
+    members: Vec<GroupStreakMember>,
+}
+
+/// Groups this device belongs to, as last seen on the server.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GroupsFile {
+    #[serde(default)]
+    groups: Vec<Group>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateGroupPayload<'a> {
+    p_client_uuid: &'a str,
+    p_device_secret: &'a str,
+    p_name: &'a str,
+    p_player_name: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct JoinGroupPayload<'a> {
+    p_client_uuid: &'a str,
+    p_device_secret: &'a str,
+    p_invite_code: &'a str,
+    p_player_name: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct GroupMemberPayload<'a> {
+    p_client_uuid: &'a str,
+    p_device_secret: &'a str,
+    p_group_id: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct ListMyGroupsPayload<'a> {
+    p_client_uuid: &'a str,
+    p_device_secret: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupMemberRow {
+    client_uuid: String,
+    player_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupStreakRow {
+    client_uuid: String,
+    #[serde(default)]
+    current_streak: Option<i64>,
+    #[serde(default)]
+    max_streak: Option<i64>,
+    #[serde(default)]
+    last_submission_key: Option<String>,
+    #[serde(default)]
+    freeze_tokens: Option<i64>,
+}
+
+#[tauri::command]
+pub async fn create_group<R: Runtime>(
+    app: AppHandle<R>,
+    name: String,
+    player_name: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<Group, String> {
+    let config = require_groups_config(&app, supabase_url, supabase_anon_key)?;
+    let name = name
+        .trim()
+        .chars()
+        .take(MAX_GROUP_NAME_LEN)
+        .collect::<String>();
+    if name.is_empty() {
+        return Err("group name must not be empty".into());
+    }
+    let player_name = group_player_name(&app, &config, &player_name).await?;
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let device_secret = get_or_create_device_secret(&app)?;
+    let payload = CreateGroupPayload {
+        p_client_uuid: &device_uuid,
+        p_device_secret: &device_secret,
+        p_name: &name,
+        p_player_name: &player_name,
+    };
+    let mut group: Group = call_group_rpc(&config, CREATE_GROUP_RPC_NAME, &payload).await?;
+    group.is_owner = group.owner_client_uuid == device_uuid;
+    remember_group(&app, &group)?;
+    Ok(group)
+}
+
+#[tauri::command]
+pub async fn join_group<R: Runtime>(
+    app: AppHandle<R>,
+    invite_code: String,
+    player_name: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<Group, String> {
+    let config = require_groups_config(&app, supabase_url, supabase_anon_key)?;
+    let invite_code = normalize_invite_code(&invite_code)?;
+    let player_name = group_player_name(&app, &config, &player_name).await?;
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let device_secret = get_or_create_device_secret(&app)?;
+    let payload = JoinGroupPayload {
+        p_client_uuid: &device_uuid,
+        p_device_secret: &device_secret,
+        p_invite_code: &invite_code,
+        p_player_name: &player_name,
+    };
+    let mut group: Group = call_group_rpc(&config, JOIN_GROUP_RPC_NAME, &payload).await?;
+    group.is_owner = group.owner_client_uuid == device_uuid;
+    remember_group(&app, &group)?;
+    Ok(group)
+}
+
+/// Leaves a group. Ownership passes to the longest-standing member, and the
+/// group is deleted once its last member leaves.
+#[tauri::command]
+pub async fn leave_group<R: Runtime>(
+    app: AppHandle<R>,
+    group_id: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<Vec<Group>, String> {
+    let config = require_groups_config(&app, supabase_url, supabase_anon_key)?;
+    let group_id = normalize_group_id(&group_id)?;
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let device_secret = get_or_create_device_secret(&app)?;
+    let payload = GroupMemberPayload {
+        p_client_uuid: &device_uuid,
+        p_device_secret: &device_secret,
+        p_group_id: &group_id,
+    };
+    let _: serde_json::Value = call_group_rpc(&config, LEAVE_GROUP_RPC_NAME, &payload).await?;
+
+    let mut file = read_groups(&app)?;
+    file.groups.retain(|group| group.group_id != group_id);
+    write_groups(&app, &file)?;
+    Ok(file.groups)
+}
+
+/// Groups this device belongs to. Falls back to the local copy when Supabase
+/// is unavailable.
+#[tauri::command]
+pub async fn list_groups<R: Runtime>(
+    app: AppHandle<R>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<Vec<Group>, String> {
+    let mut file = read_groups(&app)?;
+    let Some(config) = normalize_supabase_config(&app, supabase_url, supabase_anon_key) else {
+        return Ok(file.groups);
+    };
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let device_secret = get_or_create_device_secret(&app)?;
+    let payload = ListMyGroupsPayload {
+        p_client_uuid: &device_uuid,
+        p_device_secret: &device_secret,
+    };
+    match call_group_rpc::<Vec<Group>>(&config, LIST_MY_GROUPS_RPC_NAME, &payload).await {
+        Ok(mut groups) => {
+            for group in &mut groups {
+                group.is_owner = group.owner_client_uuid == device_uuid;
+                group.invite_code = file
+                    .groups
+                    .iter()
+                    .find(|known| known.group_id == group.group_id)
+                    .and_then(|known| known.invite_code.clone());
+            }
+            file.groups = groups;
+            write_groups(&app, &file)?;
+        }
+        Err(error) => {
+            eprintln!("Failed to load groups from Supabase. Using local copy. {error}");
+        }
+    }
+    Ok(file.groups)
+}
+
+/// Group-scoped board: the classic board, or a challenge board
+/// (`daily`/`weekly`/`monthly`) for `challenge_key` (default: current period).
+#[tauri::command]
+pub async fn fetch_group_scores<R: Runtime>(
+    app: AppHandle<R>,
+    group_id: String,
+    mode: String,
+    challenge_key: Option<String>,
+    limit: Option<u32>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<Vec<ScoreEntry>, String> {
+    let config = require_groups_config(&app, supabase_url, supabase_anon_key)?;
+    let group_id = normalize_group_id(&group_id)?;
+    let top_limit = normalize_limit(limit);
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let device_secret = get_or_create_device_secret(&app)?;
+    let members =
+        fetch_remote_group_members(&config, &device_uuid, &device_secret, &group_id).await?;
+    let member_uuids = members
+        .into_iter()
+        .map(|member| member.client_uuid)
+        .collect::<Vec<_>>();
+    let friend_scope = friends::load_friend_scope(&app, Some(&config), false).await;
+
+    if mode.trim().eq_ignore_ascii_case(CLASSIC_BOARD) {
+        return fetch_remote_scores(
+            &config,
+            top_limit,
+            Some(&device_uuid),
+            &friend_scope,
+            Some(&member_uuids),
+        )
+        .await;
+    }
+
+    let period = ChallengePeriod::from_mode(&mode)
+        .map_err(|_| "group board must be classic, daily, weekly, or monthly".to_string())?;
+    let challenge_key = match challenge_key {
+        Some(raw) => period.normalize_archived_key(&raw)?,
+        None => period.current_key(),
+    };
+    fetch_remote_challenge_scores(
+        &config,
+        period,
+        &challenge_key,
+        top_limit,
+        Some(&device_uuid),
+        &friend_scope,
+        Some(&member_uuids),
+    )
+    .await
+}
+
+/// Daily streaks of every member and who has already played today's challenge.
+#[tauri::command]
+pub async fn fetch_group_streaks<R: Runtime>(
+    app: AppHandle<R>,
+    group_id: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<GroupStreakSummary, String> {
+    let config = require_groups_config(&app, supabase_url, supabase_anon_key)?;
+    let group_id = normalize_group_id(&group_id)?;
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let device_secret = get_or_create_device_secret(&app)?;
+    let members =
+        fetch_remote_group_members(&config, &device_uuid, &device_secret, &group_id).await?;
+    let client_uuids = members
+        .iter()
+        .map(|member| member.client_uuid.clone())
+        .collect::<Vec<_>>();
+    let streak_rows = fetch_remote_group_streaks(&config, &client_uuids).await?;
+    let challenge_key = ChallengePeriod::Daily.current_key();
+
+    let mut summary_members = members
+        .into_iter()
+        .map(|member| {
+            let row = streak_rows
+                .iter()
+                .find(|row| row.client_uuid == member.client_uuid);
+            let last_key = row.and_then(|row| row.last_submission_key.as_deref());
+            let freeze_tokens = row.and_then(|row| row.freeze_tokens).unwrap_or(0).max(0);
+            let alive = is_current_streak_alive(last_key, &challenge_key, freeze_tokens);
+            GroupStreakMember {
+                current_streak: if alive {
+                    row.and_then(|row| row.current_streak).unwrap_or(0).max(0)
+                } else {
+                    0
+                },
+                max_streak: row.and_then(|row| row.max_streak).unwrap_or(0).max(0),
+                played_today: last_key == Some(challenge_key.as_str()),
+                is_me: member.client_uuid == device_uuid,
+                player_name: member.player_name,
+            }
+        })
+        .collect::<Vec<_>>();
+    summary_members.sort_by(|a, b| {
+        b.played_today
+            .cmp(&a.played_today)
+            .then_with(|| b.current_streak.cmp(&a.current_streak))
+            .then_with(|| a.player_name.cmp(&b.player_name))
+    });
+
+    Ok(GroupStreakSummary {
+        group_id,
+        challenge_key,
+        member_count: summary_members.len(),
+        played_today_count: summary_members
+            .iter()
+            .filter(|member| member.played_today)
+            .count(),
+        members: summary_members,
+    })
+}
+
+fn require_groups_config<R: Runtime>(
+    app: &AppHandle<R>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<SupabaseConfig, String> {
+    normalize_supabase_config(app, supabase_url, supabase_anon_key)
+        .ok_or_else(|| "groups require Supabase configuration".to_string())
+}
+
+/// Name shown to the other members: the profile name once one is chosen,
+/// otherwise `raw` under the same rules as a display name. The server applies
+/// the same choice.
+async fn group_player_name<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &SupabaseConfig,
+    raw: &str,
+) -> Result<String, String> {
+    if let Some(name) = profile::display_name(app) {
+        return Ok(name);
+    }
+    name_policy::ensure_server_name_blocklist(config).await;
+    name_policy::validate_display_name(app, raw).map_err(|rejection| rejection.to_string())
+}
+
+fn normalize_invite_code(raw: &str) -> Result<String, String> {
+    let compact = raw
+        .trim()
+        .to_ascii_uppercase()
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && *ch != '-')
+        .collect::<String>();
+    if compact.len() != 8 || !compact.bytes().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err("invite code must be 8 characters, like 1A2B-3C4D".into());
+    }
+    Ok(format!("{}-{}", &compact[..4], &compact[4..]))
+}
+
+fn normalize_group_id(raw: &str) -> Result<String, String> {
+    Uuid::parse_str(raw.trim())
+        .map(|value| value.to_string())
+        .map_err(|_| "group id is invalid".to_string())
+}
+
+async fn call_group_rpc<T: DeserializeOwned>(
+    config: &SupabaseConfig,
+    rpc_name: &str,
+    payload: &impl Serialize,
+) -> Result<T, String> {
+    let endpoint = format!(
+        "{}/rest/v1/rpc/{}",
+        config.url.trim_end_matches('/'),
+        rpc_name
+    );
+    let client = &config.client;
+    let response = client
+        .post(endpoint)
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .json(payload)
+        .send()
+        .await
+        .map_err(|error| format!("supabase {rpc_name} failed: {error}"))?;
+    observe_server_date(&response);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if let Some(message) = group_error_message(&body) {
+            return Err(message.into());
+        }
+        return Err(format!(
+            "supabase {rpc_name} failed with {status}: {body}\n\
+Ensure /supabase/schema.sql has been applied (including RPC {rpc_name})."
+        ));
+    }
+
+    response
+        .json::<T>()
+        .await
+        .map_err(|error| format!("failed to decode {rpc_name} response: {error}"))
+}
+
+/// Readable text for the exceptions raised by the group RPCs.
+fn group_error_message(body: &str) -> Option<&'static str> {
+    [
+        ("GROUP_NOT_FOUND", "no group matches that invite code"),
+        ("GROUP_FULL", "that group is full"),
+        (
+            "GROUP_LIMIT_REACHED",
+            "this device is already in the maximum number of groups",
+        ),
+        ("NOT_A_MEMBER", "this device is not a member of that group"),
+        (
+            "DEVICE_AUTH_FAILED",
+            "this device's secret does not match the one registered for its id",
+        ),
+        (
+            "DEVICE_NOT_REGISTERED",
+            "this device has scores but no registered secret yet; submit a run first",
+        ),
+        (
+            "INVALID_GROUP_NAME",
+            "group name must be 1 to 32 characters",
+        ),
+        ("NAME_BLOCKED", "display name contains a blocked word"),
+        ("NAME_RESERVED", "display name is reserved"),
+        (
+            "NAME_TAKEN",
+            "another player already uses that display name",
+        ),
+        (
+            "INVALID_PLAYER_NAME",
+            "display name has no visible characters",
+        ),
+    ]
+    .into_iter()
+    .find(|(code, _)| body.contains(code))
+    .map(|(_, message)| message)
+}
+
+async fn fetch_remote_group_members(
+    config: &SupabaseConfig,
+    device_uuid: &str,
+    device_secret: &str,
+    group_id: &str,
+) -> Result<Vec<GroupMemberRow>, String> {
+    let payload = GroupMemberPayload {
+        p_client_uuid: device_uuid,
+        p_device_secret: device_secret,
+        p_group_id: group_id,
+    };
+    call_group_rpc(config, LIST_GROUP_MEMBERS_RPC_NAME, &payload).await
+}
+
+async fn fetch_remote_group_streaks(
+    config: &SupabaseConfig,
+    client_uuids: &[String],
+) -> Result<Vec<GroupStreakRow>, String> {
+    if client_uuids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let endpoint = format!(
+        "{}/rest/v1/daily_streak_states",
+        config.url.trim_end_matches('/')
+    );
+    let owner_filter = format!(
+        "in.({})",
+        client_uuids
+            .iter()
+            .map(|value| quote_postgrest_text(value))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let client = &config.client;
+    let response = client
+        .get(endpoint)
+        .query(&[
+            (
+                "select",
+                "client_uuid,current_streak,max_streak,last_submission_key,freeze_tokens",
+            ),
+            ("client_uuid", owner_filter.as_str()),
+        ])
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .send()
+        .await
+        .map_err(|error| format!("supabase group streak fetch failed: {error}"))?;
+    observe_server_date(&response);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "supabase group streak fetch failed with {status}: {body}"
+        ));
+    }
+
+    response
+        .json::<Vec<GroupStreakRow>>()
+        .await
+        .map_err(|error| format!("failed to decode group streak response: {error}"))
+}
+
+fn remember_group<R: Runtime>(app: &AppHandle<R>, group: &Group) -> Result<(), String> {
+    let mut file = read_groups(app)?;
+    file.groups
+        .retain(|existing| existing.group_id != group.group_id);
+    file.groups.push(group.clone());
+    write_groups(app, &file)
+}
+
+fn groups_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(GROUPS_FILE_NAME);
+    Ok(dir)
+}
+
+fn read_groups<R: Runtime>(app: &AppHandle<R>) -> Result<GroupsFile, String> {
+    let path = groups_path(app)?;
+    if !path.exists() {
+        return Ok(GroupsFile::default());
+    }
+
+    let raw =
+        fs::read_to_string(&path).map_err(|error| format!("failed to read groups: {error}"))?;
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn write_groups<R: Runtime>(app: &AppHandle<R>, file: &GroupsFile) -> Result<(), String> {
+    let path = groups_path(app)?;
+    let body = serde_json::to_string(file)
+        .map_err(|error| format!("failed to serialize groups: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write groups: {error}"))
+}
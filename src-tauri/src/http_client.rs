@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+const DEFAULT_TIMEOUT_SECONDS: u64 = 8;
+const DEFAULT_CONNECT_TIMEOUT_SECONDS: u64 = 5;
+const DEFAULT_POOL_IDLE_TIMEOUT_SECONDS: u64 = 90;
+const DEFAULT_TCP_KEEPALIVE_SECONDS: u64 = 60;
+
+/// How the shared client talks to Supabase. `run` uses the defaults; tests
+/// shorten the timeouts so fault injection does not stall the suite.
+#[derive(Debug, Clone)]
+pub struct HttpClientSettings {
+    /// Upper bound for a whole request, from connect to the last body byte.
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    /// How long an idle pooled connection is kept for reuse.
+    pub pool_idle_timeout: Duration,
+    pub tcp_keepalive: Option<Duration>,
+    /// Advertises `Accept-Encoding: gzip` and decodes compressed bodies.
+    pub gzip: bool,
+    pub user_agent: String,
+}
+
+impl Default for HttpClientSettings {
+    fn default() -> Self {
+        HttpClientSettings {
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECONDS),
+            connect_timeout: Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECONDS),
+            pool_idle_timeout: Duration::from_secs(DEFAULT_POOL_IDLE_TIMEOUT_SECONDS),
+            tcp_keepalive: Some(Duration::from_secs(DEFAULT_TCP_KEEPALIVE_SECONDS)),
+            gzip: true,
+            user_agent: format!("torus-app/{}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+}
+
+/// The one `reqwest::Client` of the app, held in Tauri managed state.
+/// Cloning the inner client is cheap and shares its connection pool, so every
+/// remote call reuses warm TLS connections instead of handshaking again, and
+/// concurrent requests multiplex over HTTP/2 where the server offers it.
+pub struct HttpClient {
+    client: reqwest::Client,
+}
+
+impl HttpClient {
+    pub fn new(settings: &HttpClientSettings) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(settings.timeout)
+            .connect_timeout(settings.connect_timeout)
+            .pool_idle_timeout(settings.pool_idle_timeout)
+            .tcp_keepalive(settings.tcp_keepalive)
+            .gzip(settings.gzip)
+            .user_agent(settings.user_agent.as_str())
+            .build()
+            .map_err(|error| format!("failed to build http client: {error}"))?;
+        Ok(HttpClient { client })
+    }
+
+    pub(crate) fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+}
@@ -1,22 +1,89 @@
+mod achievements;
+mod badges;
+mod challenge_codes;
+mod friends;
+mod groups;
+pub mod http_client;
+mod name_policy;
+pub mod plausibility;
+mod practice;
+mod profile;
+mod rejects;
+mod reports;
 mod scoreboard;
+mod submission_guard;
+mod verify_error;
+
+use http_client::{HttpClient, HttpClientSettings};
+use tauri::{Builder, Runtime};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_process::init())
-        .plugin(tauri_plugin_updater::Builder::new().build())
-        .plugin(tauri_plugin_opener::init())
+    with_commands(
+        Builder::default()
+            .plugin(tauri_plugin_process::init())
+            .plugin(tauri_plugin_updater::Builder::new().build())
+            .plugin(tauri_plugin_opener::init()),
+        HttpClientSettings::default(),
+    )
+    .run(tauri::generate_context!())
+    .expect("error while running tauri application");
+}
+
+/// Registers every command on `builder` along with the shared HTTP client
+/// built from `http`. Integration tests pass Tauri's mock builder here to
+/// drive the commands without a webview.
+pub fn with_commands<R: Runtime>(builder: Builder<R>, http: HttpClientSettings) -> Builder<R> {
+    let http_client = HttpClient::new(&http).expect("failed to build the shared http client");
+    builder
+        .manage(http_client)
         .invoke_handler(tauri::generate_handler![
             scoreboard::fetch_global_scores,
             scoreboard::submit_global_score,
             scoreboard::fetch_daily_scores,
+            scoreboard::get_daily_clock,
+            scoreboard::fetch_daily_history,
+            scoreboard::fetch_streak_calendar,
             scoreboard::fetch_daily_status,
             scoreboard::fetch_daily_badge_status,
             scoreboard::start_daily_attempt,
             scoreboard::forfeit_daily_attempt,
             scoreboard::rollback_daily_attempt,
-            scoreboard::submit_daily_score
+            scoreboard::submit_daily_score,
+            scoreboard::get_challenge_period,
+            scoreboard::fetch_challenge_scores,
+            scoreboard::fetch_challenge_status,
+            scoreboard::start_challenge_attempt,
+            scoreboard::forfeit_challenge_attempt,
+            scoreboard::rollback_challenge_attempt,
+            scoreboard::submit_challenge_score,
+            practice::start_daily_practice,
+            practice::submit_daily_practice,
+            practice::fetch_daily_practice_scores,
+            practice::fetch_daily_practice_replay,
+            challenge_codes::create_challenge_code,
+            challenge_codes::accept_challenge_code,
+            challenge_codes::complete_challenge_run,
+            challenge_codes::fetch_head_to_head,
+            friends::get_friend_code,
+            friends::list_friends,
+            friends::add_friend,
+            friends::remove_friend,
+            groups::create_group,
+            groups::join_group,
+            groups::leave_group,
+            groups::list_groups,
+            groups::fetch_group_scores,
+            groups::fetch_group_streaks,
+            profile::get_profile,
+            profile::update_profile,
+            name_policy::check_display_name,
+            reports::report_score,
+            reports::list_hidden_players,
+            reports::unhide_player,
+            rejects::list_rejected_submissions,
+            rejects::export_rejected_submissions,
+            plausibility::analyze_replay,
+            achievements::fetch_achievements
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
 }
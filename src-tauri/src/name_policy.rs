@@ -0,0 +1,529 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Runtime};
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::skeleton;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::scoreboard::{
+    cached_board_names, normalize_supabase_config, observe_server_date, SupabaseConfig,
+};
+
+const BUNDLED_NAME_BLOCKLIST: &str = include_str!("../resources/name-blocklist.json");
+const NAME_BLOCKLIST_TABLE_NAME: &str = "name_blocklist";
+/// Matches the `char_length` checks on `scores.player_name`.
+pub(crate) const MAX_DISPLAY_NAME_CHARS: usize = 20;
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+static NAME_BLOCKLIST: RwLock<Option<Arc<NameBlocklist>>> = RwLock::new(None);
+static SERVER_BLOCKLIST_CHECKED: AtomicBool = AtomicBool::new(false);
+
+/// Why a display name was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum NameRejection {
+    /// Nothing visible remains after normalization.
+    Empty,
+    /// Contains a blocked term.
+    Blocked,
+    /// Reserved for staff or the game itself.
+    Reserved,
+    /// Already used by another player on the board.
+    Taken,
+    /// Looks like, but is not, a name already on the board.
+    Confusable { existing: String },
+}
+
+impl NameRejection {
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            NameRejection::Empty => "empty",
+            NameRejection::Blocked => "blocked",
+            NameRejection::Reserved => "reserved",
+            NameRejection::Taken => "taken",
+            NameRejection::Confusable { .. } => "confusable",
+        }
+    }
+}
+
+impl fmt::Display for NameRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameRejection::Empty => write!(f, "display name has no visible characters"),
+            NameRejection::Blocked => write!(f, "display name contains a blocked word"),
+            NameRejection::Reserved => write!(f, "display name is reserved"),
+            NameRejection::Taken => write!(f, "another player already uses that display name"),
+            NameRejection::Confusable { existing } => {
+                write!(f, "display name looks too similar to {existing}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct NameCheckResult {
+    ok: bool,
+    /// The name as it would be stored, when accepted.
+    name: Option<String>,
+    reason: Option<&'static str>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NameBlocklistFile {
+    #[serde(default)]
+    terms: Vec<String>,
+    #[serde(default)]
+    words: Vec<String>,
+    #[serde(rename = "reservedNames", default)]
+    reserved_names: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NameBlocklistRow {
+    kind: String,
+    term: String,
+}
+
+/// Folded blocklist entries: `terms` match at the start or end of a word, so
+/// that ordinary names merely containing one still pass, `words` only as a
+/// whole word, and reserved names only as the entire name. Mirrors
+/// `check_display_name` in schema.sql.
+#[derive(Debug, Default)]
+struct NameBlocklist {
+    terms: HashSet<String>,
+    words: HashSet<String>,
+    reserved_names: HashSet<String>,
+}
+
+impl NameBlocklist {
+    fn extend(&mut self, file: NameBlocklistFile) {
+        let fold_all = |values: Vec<String>| {
+            values
+                .iter()
+                .map(|value| fold_for_blocklist(value))
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<_>>()
+        };
+        self.terms.extend(fold_all(file.terms));
+        self.words.extend(fold_all(file.words));
+        self.reserved_names.extend(fold_all(file.reserved_names));
+    }
+
+    fn check(&self, name: &str) -> Result<(), NameRejection> {
+        let folded = fold_for_blocklist(name);
+        if self.reserved_names.contains(&folded) {
+            return Err(NameRejection::Reserved);
+        }
+        let has_blocked_word = name
+            .split(|ch: char| !ch.is_alphanumeric() && !is_leet_symbol(ch))
+            .map(fold_for_blocklist)
+            .filter(|word| !word.is_empty())
+            .any(|word| {
+                self.words.contains(&word)
+                    || self.terms.iter().any(|term| {
+                        word.starts_with(term.as_str()) || word.ends_with(term.as_str())
+                    })
+            });
+        if has_blocked_word {
+            return Err(NameRejection::Blocked);
+        }
+        Ok(())
+    }
+}
+
+/// Checks a display name without saving or submitting anything, so the UI can
+/// explain a rejection before a run starts.
+#[tauri::command]
+pub async fn check_display_name<R: Runtime>(
+    app: AppHandle<R>,
+    display_name: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<NameCheckResult, String> {
+    if let Some(config) = normalize_supabase_config(&app, supabase_url, supabase_anon_key) {
+        ensure_server_name_blocklist(&config).await;
+    }
+    Ok(match validate_display_name(&app, &display_name) {
+        Ok(name) => NameCheckResult {
+            ok: true,
+            name: Some(name),
+            reason: None,
+            message: None,
+        },
+        Err(rejection) => NameCheckResult {
+            ok: false,
+            name: None,
+            reason: Some(rejection.code()),
+            message: Some(rejection.to_string()),
+        },
+    })
+}
+
+/// Canonical form of a display name, checked against the blocklist and the
+/// names on the cached boards. Used when a name is chosen, not when a run is
+/// submitted, so a refused name never costs a run.
+pub(crate) fn validate_display_name<R: Runtime>(
+    app: &AppHandle<R>,
+    raw: &str,
+) -> Result<String, NameRejection> {
+    let name = normalize_display_name(raw)?;
+    name_blocklist().check(&name)?;
+    ensure_distinct_from_board(app, &name)?;
+    Ok(name)
+}
+
+/// Canonical form of a display name: invisible and control characters are
+/// dropped, whitespace is collapsed, and the result is NFC-normalized and cut
+/// to the length limit on a grapheme boundary.
+pub(crate) fn normalize_display_name(raw: &str) -> Result<String, NameRejection> {
+    let mut visible = String::with_capacity(raw.len());
+    let mut previous: Option<char> = None;
+    let mut chars = raw.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == ZERO_WIDTH_JOINER {
+            // Kept only inside emoji sequences such as family or flag emoji.
+            let joins_emoji = previous.is_some_and(is_emoji_like)
+                && chars.peek().copied().is_some_and(is_emoji_like);
+            if joins_emoji {
+                visible.push(ch);
+            }
+            continue;
+        }
+        if is_invisible(ch) {
+            continue;
+        }
+        if ch.is_whitespace() {
+            if previous.is_some_and(|value| value != ' ') {
+                visible.push(' ');
+                previous = Some(' ');
+            }
+            continue;
+        }
+        visible.push(ch);
+        previous = Some(ch);
+    }
+
+    let normalized = visible.nfc().collect::<String>();
+    let mut name = String::new();
+    let mut char_count = 0;
+    for grapheme in normalized.trim().graphemes(true) {
+        let grapheme_chars = grapheme.chars().count();
+        if char_count + grapheme_chars > MAX_DISPLAY_NAME_CHARS {
+            break;
+        }
+        name.push_str(grapheme);
+        char_count += grapheme_chars;
+    }
+    let name = name.trim_end().to_string();
+    if name.is_empty() {
+        return Err(NameRejection::Empty);
+    }
+    Ok(name)
+}
+
+/// Rejects a name another player already shows on the cached boards, either
+/// exactly or through a confusable skeleton, e.g. `Bob` with a Cyrillic `о`
+/// or `B0b`. This device's own records are not on the list.
+fn ensure_distinct_from_board<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+) -> Result<(), NameRejection> {
+    check_distinct(name, cached_board_names(app))
+}
+
+fn check_distinct(
+    name: &str,
+    others: impl IntoIterator<Item = String>,
+) -> Result<(), NameRejection> {
+    let key = confusable_key(name);
+    for existing in others {
+        if existing.to_lowercase() == name.to_lowercase() {
+            return Err(NameRejection::Taken);
+        }
+        if confusable_key(&existing) == key {
+            return Err(NameRejection::Confusable { existing });
+        }
+    }
+    Ok(())
+}
+
+/// Adds the server's `name_blocklist` rows to the bundled list, once per
+/// process. A missing table keeps the bundled list; a failed fetch is retried
+/// on the next check.
+pub(crate) async fn ensure_server_name_blocklist(config: &SupabaseConfig) {
+    if SERVER_BLOCKLIST_CHECKED.load(Ordering::SeqCst) {
+        return;
+    }
+
+    match fetch_remote_name_blocklist(config).await {
+        Ok(rows) => {
+            SERVER_BLOCKLIST_CHECKED.store(true, Ordering::SeqCst);
+            if rows.is_empty() {
+                return;
+            }
+            let mut file = NameBlocklistFile::default();
+            for row in rows {
+                match row.kind.as_str() {
+                    "term" => file.terms.push(row.term),
+                    "word" => file.words.push(row.term),
+                    "reserved" => file.reserved_names.push(row.term),
+                    _ => {}
+                }
+            }
+            let mut blocklist = bundled_name_blocklist();
+            blocklist.extend(file);
+            if let Ok(mut guard) = NAME_BLOCKLIST.write() {
+                *guard = Some(Arc::new(blocklist));
+            }
+        }
+        Err(error) => {
+            eprintln!("Failed to load name blocklist from Supabase. Using bundled list. {error}");
+        }
+    }
+}
+
+fn name_blocklist() -> Arc<NameBlocklist> {
+    if let Ok(guard) = NAME_BLOCKLIST.read() {
+        if let Some(blocklist) = guard.as_ref() {
+            return blocklist.clone();
+        }
+    }
+
+    let blocklist = Arc::new(bundled_name_blocklist());
+    if let Ok(mut guard) = NAME_BLOCKLIST.write() {
+        *guard = Some(blocklist.clone());
+    }
+    blocklist
+}
+
+fn bundled_name_blocklist() -> NameBlocklist {
+    let file = serde_json::from_str::<NameBlocklistFile>(BUNDLED_NAME_BLOCKLIST)
+        .expect("bundled name-blocklist.json must be valid");
+    let mut blocklist = NameBlocklist::default();
+    blocklist.extend(file);
+    blocklist
+}
+
+/// Server blocklist rows, or none when the table does not exist (404).
+async fn fetch_remote_name_blocklist(
+    config: &SupabaseConfig,
+) -> Result<Vec<NameBlocklistRow>, String> {
+    let endpoint = format!(
+        "{}/rest/v1/{}",
+        config.url.trim_end_matches('/'),
+        NAME_BLOCKLIST_TABLE_NAME
+    );
+    let client = &config.client;
+    let response = client
+        .get(endpoint)
+        .query(&[("select", "kind,term")])
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .send()
+        .await
+        .map_err(|error| format!("supabase name blocklist fetch failed: {error}"))?;
+    observe_server_date(&response);
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "supabase name blocklist fetch failed with {status}: {body}"
+        ));
+    }
+
+    response
+        .json::<Vec<NameBlocklistRow>>()
+        .await
+        .map_err(|error| format!("failed to decode name blocklist response: {error}"))
+}
+
+/// Lowercased UTS #39 skeleton without whitespace; equal keys render alike.
+fn confusable_key(name: &str) -> String {
+    skeleton(&name.to_lowercase())
+        .flat_map(char::to_lowercase)
+        .filter(|ch| !ch.is_whitespace())
+        .collect()
+}
+
+/// Blocklist form: common digit/symbol substitutions undone, look-alikes
+/// folded, and everything but letters and digits removed.
+fn fold_for_blocklist(raw: &str) -> String {
+    let unleeted = raw
+        .to_lowercase()
+        .chars()
+        .map(|ch| match ch {
+            '0' => 'o',
+            '1' | '!' | '|' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' => 't',
+            other => other,
+        })
+        .collect::<String>();
+    skeleton(&unleeted)
+        .flat_map(char::to_lowercase)
+        .filter(|ch| ch.is_alphanumeric())
+        .collect()
+}
+
+/// Symbols `fold_for_blocklist` reads as letters, so they do not split words.
+fn is_leet_symbol(ch: char) -> bool {
+    matches!(ch, '!' | '|' | '@' | '$')
+}
+
+fn is_emoji_like(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{2600}'..='\u{27BF}' | '\u{FE0F}' | '\u{1F000}'..='\u{1FAFF}'
+    )
+}
+
+/// Control, format and blank-rendering characters, including bidi overrides,
+/// zero-width spaces and Hangul fillers.
+fn is_invisible(ch: char) -> bool {
+    ch.is_control()
+        || matches!(
+            ch,
+            '\u{00AD}'
+                | '\u{034F}'
+                | '\u{061C}'
+                | '\u{115F}'
+                | '\u{1160}'
+                | '\u{17B4}'
+                | '\u{17B5}'
+                | '\u{180B}'..='\u{180F}'
+                | '\u{200B}'..='\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2060}'..='\u{206F}'
+                | '\u{2800}'
+                | '\u{3164}'
+                | '\u{FE00}'..='\u{FE0E}'
+                | '\u{FEFF}'
+                | '\u{FFA0}'
+                | '\u{FFF9}'..='\u{FFFB}'
+                | '\u{1D173}'..='\u{1D17A}'
+                | '\u{E0000}'..='\u{E007F}'
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str) -> Result<(), NameRejection> {
+        bundled_name_blocklist().check(&normalize_display_name(name)?)
+    }
+
+    #[test]
+    fn normalizes_to_nfc() {
+        let name = normalize_display_name("Rene\u{0301}e").unwrap();
+        assert_eq!(name, "Ren\u{00E9}e");
+        assert_eq!(name.chars().count(), 5);
+    }
+
+    #[test]
+    fn keeps_emoji_joiners_and_drops_stray_ones() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(normalize_display_name(family).unwrap(), family);
+        assert_eq!(normalize_display_name("Bo\u{200D}b").unwrap(), "Bob");
+        assert_eq!(
+            normalize_display_name("Bob\u{200D}\u{1F600}").unwrap(),
+            "Bob\u{1F600}"
+        );
+    }
+
+    #[test]
+    fn strips_bidi_controls_and_zero_width_characters() {
+        assert_eq!(
+            normalize_display_name("\u{202E}Bo\u{200B}b\u{2066}\u{FEFF}").unwrap(),
+            "Bob"
+        );
+        assert_eq!(
+            normalize_display_name("  Ace \t\n  Pilot ").unwrap(),
+            "Ace Pilot"
+        );
+        assert_eq!(
+            normalize_display_name("\u{3164}\u{200B}\u{202E}"),
+            Err(NameRejection::Empty)
+        );
+    }
+
+    #[test]
+    fn truncates_on_grapheme_boundaries() {
+        let prefix = "a".repeat(MAX_DISPLAY_NAME_CHARS - 1);
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(
+            normalize_display_name(&format!("{prefix}{family}")).unwrap(),
+            prefix
+        );
+
+        // Conjoining jamo compose to one syllable under NFC and fit.
+        let composed =
+            normalize_display_name(&format!("{prefix}\u{1112}\u{1161}\u{11AB}")).unwrap();
+        assert_eq!(composed, format!("{prefix}\u{D55C}"));
+
+        // An archaic final jamo does not compose, so the two-char syllable is
+        // dropped whole rather than split.
+        let archaic = normalize_display_name(&format!("{prefix}\u{1100}\u{1161}\u{11F0}")).unwrap();
+        assert_eq!(archaic, prefix);
+
+        let hangul = "\u{D55C}".repeat(MAX_DISPLAY_NAME_CHARS + 5);
+        assert_eq!(
+            normalize_display_name(&hangul).unwrap().chars().count(),
+            MAX_DISPLAY_NAME_CHARS
+        );
+    }
+
+    #[test]
+    fn blocklist_folds_leet_and_look_alikes() {
+        assert_eq!(check("$h1t"), Err(NameRejection::Blocked));
+        assert_eq!(check("B!tch"), Err(NameRejection::Blocked));
+        assert_eq!(check("sh|t happens"), Err(NameRejection::Blocked));
+        assert_eq!(check("4DM1N"), Err(NameRejection::Reserved));
+        // Cyrillic а and о.
+        assert_eq!(check("\u{0430}dmin"), Err(NameRejection::Reserved));
+        assert_eq!(check("m\u{043E}d"), Err(NameRejection::Reserved));
+        assert_eq!(check("Dick"), Err(NameRejection::Blocked));
+        assert_eq!(check("d1ck"), Err(NameRejection::Blocked));
+    }
+
+    #[test]
+    fn terms_match_only_at_word_edges() {
+        assert_eq!(check("bullshit"), Err(NameRejection::Blocked));
+        assert_eq!(check("shithead"), Err(NameRejection::Blocked));
+        assert_eq!(check("xX_Hitler_Xx"), Err(NameRejection::Blocked));
+        assert_eq!(check("Yoshitaka"), Ok(()));
+        assert_eq!(check("Matsushita"), Ok(()));
+        assert_eq!(check("Dickens"), Ok(()));
+        assert_eq!(check("Moderna Fan"), Ok(()));
+    }
+
+    #[test]
+    fn refuses_exact_and_confusable_board_names() {
+        let board = || vec!["Bob".to_string(), "Keunsong".to_string()];
+        assert_eq!(check_distinct("Bob", board()), Err(NameRejection::Taken));
+        assert_eq!(check_distinct("BOB", board()), Err(NameRejection::Taken));
+        assert_eq!(
+            check_distinct("B\u{043E}b", board()),
+            Err(NameRejection::Confusable {
+                existing: "Bob".into()
+            })
+        );
+        assert_eq!(
+            check_distinct("B0b", board()),
+            Err(NameRejection::Confusable {
+                existing: "Bob".into()
+            })
+        );
+        assert_eq!(check_distinct("Bobby", board()), Ok(()));
+        assert_eq!(check_distinct("Bob", Vec::new()), Ok(()));
+    }
+}
@@ -0,0 +1,285 @@
+use serde::Serialize;
+use torus_core::replay::{DailyReplayProof, ReplayInputEvent};
+
+/// Replay time advances one tick per `gameSpeedMs` (100 ms) in the frontend.
+const TICKS_PER_SECOND: i64 = 10;
+/// Keyboard auto-repeat tops out near 30 Hz, i.e. about 3 moves per tick.
+const HUMAN_MAX_MOVES_PER_TICK: usize = 4;
+const HARD_MAX_MOVES_PER_TICK: usize = 10;
+const HUMAN_MAX_MOVES_PER_SECOND: usize = 40;
+/// Only deliberate presses (this many ticks apart or more) are tested for
+/// machine-like regularity; shorter gaps are auto-repeat or Skill macros,
+/// which emit one move per `DEFAULT_SKILL_STEP_DELAY_MS` (110 ms).
+const MIN_DELIBERATE_INTERVAL_TICKS: i64 = 3;
+const MIN_REGULARITY_SAMPLES: usize = 30;
+const MAX_ROBOTIC_INTERVAL_CV: f64 = 0.05;
+const FLAG_SUSPICION: f64 = 0.5;
+const REFUSE_SUSPICION: f64 = 0.9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlausibilityVerdict {
+    Ok,
+    Flagged,
+    Refused,
+}
+
+/// Timing statistics of a replay's inputs and how suspicious they look.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlausibilityReport {
+    #[serde(rename = "totalMoves")]
+    pub total_moves: usize,
+    #[serde(rename = "durationTicks")]
+    pub duration_ticks: i64,
+    #[serde(rename = "maxMovesPerTick")]
+    pub max_moves_per_tick: usize,
+    /// Most moves inside any one-second window.
+    #[serde(rename = "maxMovesPerSecond")]
+    pub max_moves_per_second: usize,
+    #[serde(rename = "meanMovesPerSecond")]
+    pub mean_moves_per_second: f64,
+    /// Mean and standard deviation, in ticks, of the deliberate-press gaps.
+    #[serde(rename = "intervalMean")]
+    pub interval_mean: f64,
+    #[serde(rename = "intervalStdDev")]
+    pub interval_std_dev: f64,
+    #[serde(rename = "intervalSamples")]
+    pub interval_samples: usize,
+    /// 0.0 (looks human) to 1.0 (certainly not).
+    pub suspicion: f64,
+    pub flags: Vec<&'static str>,
+    pub verdict: PlausibilityVerdict,
+}
+
+#[tauri::command]
+pub async fn analyze_replay(replay_proof: DailyReplayProof) -> Result<PlausibilityReport, String> {
+    Ok(analyze_replay_inputs(&replay_proof.inputs))
+}
+
+/// Parses a replay proof JSON document and analyzes its inputs; used by the
+/// `replay-audit` binary.
+pub fn analyze_replay_json(raw: &str) -> Result<PlausibilityReport, String> {
+    let proof = serde_json::from_str::<DailyReplayProof>(raw)
+        .map_err(|error| format!("failed to parse replay proof: {error}"))?;
+    Ok(analyze_replay_inputs(&proof.inputs))
+}
+
+pub fn analyze_replay_inputs(inputs: &[ReplayInputEvent]) -> PlausibilityReport {
+    let times = inputs.iter().map(|event| event.time).collect::<Vec<_>>();
+    let duration_ticks = match (times.first(), times.last()) {
+        (Some(first), Some(last)) => last - first,
+        _ => 0,
+    };
+
+    let mut max_moves_per_tick = 0;
+    let mut run_start = 0;
+    for index in 1..=times.len() {
+        if index == times.len() || times[index] != times[run_start] {
+            max_moves_per_tick = max_moves_per_tick.max(index - run_start);
+            run_start = index;
+        }
+    }
+
+    let mut max_moves_per_second = 0;
+    let mut window_start = 0;
+    for (index, time) in times.iter().enumerate() {
+        while time - times[window_start] >= TICKS_PER_SECOND {
+            window_start += 1;
+        }
+        max_moves_per_second = max_moves_per_second.max(index - window_start + 1);
+    }
+
+    let mean_moves_per_second = if duration_ticks > 0 {
+        times.len() as f64 * TICKS_PER_SECOND as f64 / duration_ticks as f64
+    } else {
+        0.0
+    };
+
+    let intervals = times
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect::<Vec<_>>();
+    let deliberate = intervals
+        .iter()
+        .filter(|interval| **interval >= MIN_DELIBERATE_INTERVAL_TICKS)
+        .map(|interval| *interval as f64)
+        .collect::<Vec<_>>();
+    let (interval_mean, interval_std_dev) = mean_and_std_dev(&deliberate);
+
+    let mut suspicion = 0.0;
+    let mut flags = Vec::new();
+    if max_moves_per_tick > HUMAN_MAX_MOVES_PER_TICK {
+        flags.push("too many moves in one tick");
+        suspicion += 0.5 * (max_moves_per_tick - HUMAN_MAX_MOVES_PER_TICK) as f64
+            / (HARD_MAX_MOVES_PER_TICK - HUMAN_MAX_MOVES_PER_TICK) as f64;
+    }
+    if max_moves_per_second > HUMAN_MAX_MOVES_PER_SECOND {
+        flags.push("burst faster than keyboard auto-repeat");
+        suspicion += 0.3 * (max_moves_per_second - HUMAN_MAX_MOVES_PER_SECOND) as f64
+            / HUMAN_MAX_MOVES_PER_SECOND as f64;
+    }
+    if deliberate.len() >= MIN_REGULARITY_SAMPLES
+        && interval_mean > 0.0
+        && interval_std_dev / interval_mean < MAX_ROBOTIC_INTERVAL_CV
+    {
+        flags.push("inhumanly regular timing");
+        suspicion += 0.6;
+    }
+    if max_moves_per_tick >= HARD_MAX_MOVES_PER_TICK {
+        suspicion = 1.0;
+    }
+    let suspicion = suspicion.clamp(0.0, 1.0);
+    let verdict = if suspicion >= REFUSE_SUSPICION {
+        PlausibilityVerdict::Refused
+    } else if suspicion >= FLAG_SUSPICION {
+        PlausibilityVerdict::Flagged
+    } else {
+        PlausibilityVerdict::Ok
+    };
+
+    PlausibilityReport {
+        total_moves: times.len(),
+        duration_ticks,
+        max_moves_per_tick,
+        max_moves_per_second,
+        mean_moves_per_second,
+        interval_mean,
+        interval_std_dev,
+        interval_samples: deliberate.len(),
+        suspicion,
+        flags,
+        verdict,
+    }
+}
+
+fn mean_and_std_dev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(times: impl IntoIterator<Item = i64>) -> Vec<ReplayInputEvent> {
+        times
+            .into_iter()
+            .map(|time| ReplayInputEvent {
+                time,
+                move_dir: "right".into(),
+            })
+            .collect()
+    }
+
+    /// `per_tick` moves in each of `ticks` consecutive ticks.
+    fn burst(ticks: i64, per_tick: usize) -> Vec<ReplayInputEvent> {
+        inputs((0..ticks).flat_map(|tick| std::iter::repeat_n(tick, per_tick)))
+    }
+
+    /// Deliberate presses with gaps cycling through 3..=9 ticks.
+    fn human_presses(count: usize) -> Vec<i64> {
+        (0..count as i64)
+            .scan(0, |time, index| {
+                *time += 3 + (index * 5) % 7;
+                Some(*time)
+            })
+            .collect()
+    }
+
+    /// Presses the way a Skill macro emits them: one every 110 ms, quantized
+    /// to 100 ms ticks.
+    fn macro_run(start: i64, moves: i64) -> impl Iterator<Item = i64> {
+        (0..moves).map(move |step| start + step * 11 / 10)
+    }
+
+    #[test]
+    fn empty_replay_is_ok() {
+        let report = analyze_replay_inputs(&[]);
+        assert_eq!(report.total_moves, 0);
+        assert_eq!(report.duration_ticks, 0);
+        assert_eq!(report.suspicion, 0.0);
+        assert_eq!(report.verdict, PlausibilityVerdict::Ok);
+    }
+
+    #[test]
+    fn moves_per_tick_thresholds() {
+        let report = analyze_replay_inputs(&burst(1, HUMAN_MAX_MOVES_PER_TICK));
+        assert!(report.flags.is_empty());
+        assert_eq!(report.verdict, PlausibilityVerdict::Ok);
+
+        let report = analyze_replay_inputs(&burst(1, HUMAN_MAX_MOVES_PER_TICK + 1));
+        assert_eq!(report.max_moves_per_tick, HUMAN_MAX_MOVES_PER_TICK + 1);
+        assert_eq!(report.flags, vec!["too many moves in one tick"]);
+        assert_eq!(report.verdict, PlausibilityVerdict::Ok);
+
+        let report = analyze_replay_inputs(&burst(1, HARD_MAX_MOVES_PER_TICK));
+        assert_eq!(report.suspicion, 1.0);
+        assert_eq!(report.verdict, PlausibilityVerdict::Refused);
+    }
+
+    #[test]
+    fn sustained_bursts_are_flagged() {
+        let report = analyze_replay_inputs(&burst(TICKS_PER_SECOND, HUMAN_MAX_MOVES_PER_TICK));
+        assert_eq!(report.max_moves_per_second, HUMAN_MAX_MOVES_PER_SECOND);
+        assert!(report.flags.is_empty());
+
+        let report = analyze_replay_inputs(&burst(TICKS_PER_SECOND, 8));
+        assert_eq!(report.max_moves_per_second, 80);
+        assert!(report
+            .flags
+            .contains(&"burst faster than keyboard auto-repeat"));
+        assert!((report.suspicion - (0.5 * 4.0 / 6.0 + 0.3)).abs() < 1e-9);
+        assert_eq!(report.verdict, PlausibilityVerdict::Flagged);
+    }
+
+    #[test]
+    fn regular_deliberate_presses_are_flagged() {
+        let robotic = (1..=MIN_REGULARITY_SAMPLES as i64 + 1).map(|index| index * 5);
+        let report = analyze_replay_inputs(&inputs(robotic));
+        assert_eq!(report.interval_samples, MIN_REGULARITY_SAMPLES);
+        assert_eq!(report.interval_std_dev, 0.0);
+        assert_eq!(report.flags, vec!["inhumanly regular timing"]);
+        assert_eq!(report.verdict, PlausibilityVerdict::Flagged);
+
+        let too_few = (1..=MIN_REGULARITY_SAMPLES as i64).map(|index| index * 5);
+        assert_eq!(
+            analyze_replay_inputs(&inputs(too_few)).verdict,
+            PlausibilityVerdict::Ok
+        );
+
+        let report = analyze_replay_inputs(&inputs(human_presses(200)));
+        assert!(report.flags.is_empty());
+        assert_eq!(report.verdict, PlausibilityVerdict::Ok);
+    }
+
+    #[test]
+    fn skill_macro_pacing_is_not_robotic() {
+        // One long macro: perfectly regular, but every gap is 1 or 2 ticks.
+        let report = analyze_replay_inputs(&inputs(macro_run(0, 200)));
+        assert_eq!(report.interval_samples, 0);
+        assert!(report.max_moves_per_tick <= 1);
+        assert!(report.flags.is_empty());
+        assert_eq!(report.verdict, PlausibilityVerdict::Ok);
+
+        // Macros fired between ordinary presses only add 1-2 tick gaps.
+        let mut times = Vec::new();
+        for (index, press) in human_presses(60).into_iter().enumerate() {
+            let start = press * 4;
+            times.push(start);
+            if index % 3 == 0 {
+                times.extend(macro_run(start + 1, 8));
+            }
+        }
+        let report = analyze_replay_inputs(&inputs(times));
+        assert!(report.flags.is_empty());
+        assert_eq!(report.verdict, PlausibilityVerdict::Ok);
+    }
+}
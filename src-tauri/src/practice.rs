@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+use torus_core::challenge_period::ChallengePeriod;
+use torus_core::clock::current_utc_seconds;
+use torus_core::replay::{replay_fingerprint, verify_replay_proof, DailyReplayProof};
+use uuid::Uuid;
+
+use crate::scoreboard::{
+    app_data_dir, get_or_create_device_uuid, load_challenge_status, normalize_limit,
+    require_challenge_config, sanitize_daily_replay_proof, sanitize_entry, ScoreEntry,
+};
+
+const PRACTICE_FILE_NAME: &str = "daily-practice-v1.json";
+const PRACTICE_REPLAYS_DIR_NAME: &str = "daily-practice-replays";
+const PRACTICE_TOKEN_PREFIX: &str = "practice-";
+/// Mirrors `DAILY_CHALLENGE_DIFFICULTY` in the frontend.
+const DAILY_CHALLENGE_DIFFICULTY: i64 = 1;
+const PRACTICE_BOARD_MAX_ENTRIES: usize = 20;
+const PRACTICE_BOARD_MAX_DAYS: usize = 14;
+
+#[derive(Debug, Serialize)]
+pub struct DailyPracticeStart {
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    seed: u32,
+    difficulty: i64,
+    #[serde(rename = "practiceToken")]
+    practice_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PracticeScoreEntry {
+    #[serde(flatten)]
+    entry: ScoreEntry,
+    #[serde(rename = "replayId")]
+    replay_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyPracticeResult {
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    #[serde(rename = "replayId")]
+    replay_id: String,
+    /// 1-based position on the local practice board, if the run made the cut.
+    rank: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PracticeSession {
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    seed: u32,
+    difficulty: i64,
+    #[serde(rename = "startedAt")]
+    started_at: i64,
+}
+
+/// Open practice sessions and the local practice boards, keyed by challenge.
+/// Practice never touches Supabase.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PracticeLedger {
+    #[serde(default)]
+    sessions: BTreeMap<String, PracticeSession>,
+    #[serde(default)]
+    boards: BTreeMap<String, Vec<PracticeScoreEntry>>,
+}
+
+/// Hands out today's seed for an unranked run once every daily attempt has
+/// been used. No server attempt is consumed.
+#[tauri::command]
+pub async fn start_daily_practice<R: Runtime>(
+    app: AppHandle<R>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<DailyPracticeStart, String> {
+    let period = ChallengePeriod::Daily;
+    let challenge_key = period.current_key();
+    let config = require_challenge_config(&app, period, supabase_url, supabase_anon_key)?;
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let status = load_challenge_status(&config, period, &challenge_key, &device_uuid).await?;
+    if status.can_submit || status.has_active_attempt {
+        return Err("daily practice unlocks after all daily attempts are used".into());
+    }
+
+    let mut ledger = read_ledger(&app)?;
+    ledger
+        .sessions
+        .retain(|_, session| session.challenge_key == challenge_key);
+    let practice_token = format!("{PRACTICE_TOKEN_PREFIX}{}", Uuid::new_v4());
+    let session = PracticeSession {
+        challenge_key: challenge_key.clone(),
+        seed: period.seed(&challenge_key),
+        difficulty: DAILY_CHALLENGE_DIFFICULTY,
+        started_at: current_utc_seconds(),
+    };
+    ledger
+        .sessions
+        .insert(practice_token.clone(), session.clone());
+    write_ledger(&app, &ledger)?;
+
+    Ok(DailyPracticeStart {
+        challenge_key,
+        seed: session.seed,
+        difficulty: session.difficulty,
+        practice_token,
+    })
+}
+
+/// Verifies a finished practice run locally, saves its replay and ranks it on
+/// the practice board for that day.
+#[tauri::command]
+pub async fn submit_daily_practice<R: Runtime>(
+    app: AppHandle<R>,
+    practice_token: String,
+    entry: ScoreEntry,
+    replay_proof: DailyReplayProof,
+) -> Result<DailyPracticeResult, String> {
+    let mut ledger = read_ledger(&app)?;
+    let session = ledger
+        .sessions
+        .remove(practice_token.trim())
+        .ok_or_else(|| "daily practice session is unknown or already finished".to_string())?;
+    let mut entry = sanitize_entry(entry)?;
+    let replay_proof = sanitize_daily_replay_proof(replay_proof)?;
+    if replay_proof.seed != session.seed || replay_proof.difficulty != session.difficulty {
+        return Err("practice replay does not match the practice seed".into());
+    }
+    if entry.score != replay_proof.final_score || entry.level != replay_proof.final_level {
+        return Err("practice entry does not match its replay proof".into());
+    }
+    let verification = verify_replay_proof(&replay_proof);
+    if !verification.ok {
+        return Err(format!(
+            "practice replay did not verify: {}",
+            verification.reason.unwrap_or("unknown reason")
+        ));
+    }
+
+    let replay_id = replay_fingerprint(&replay_proof);
+    write_practice_replay(&app, &session.challenge_key, &replay_id, &replay_proof)?;
+    entry.is_me = true;
+    let board = ledger
+        .boards
+        .entry(session.challenge_key.clone())
+        .or_default();
+    board.retain(|existing| existing.replay_id != replay_id);
+    board.push(PracticeScoreEntry {
+        entry,
+        replay_id: replay_id.clone(),
+    });
+    sort_practice_board(board);
+    let dropped = board.split_off(board.len().min(PRACTICE_BOARD_MAX_ENTRIES));
+    let rank = board
+        .iter()
+        .position(|existing| existing.replay_id == replay_id)
+        .map(|index| index + 1);
+    for stale in dropped {
+        remove_practice_replay(&app, &session.challenge_key, &stale.replay_id);
+    }
+    prune_old_boards(&app, &mut ledger);
+    write_ledger(&app, &ledger)?;
+
+    Ok(DailyPracticeResult {
+        challenge_key: session.challenge_key,
+        replay_id,
+        rank,
+    })
+}
+
+#[tauri::command]
+pub async fn fetch_daily_practice_scores<R: Runtime>(
+    app: AppHandle<R>,
+    challenge_key: String,
+    limit: Option<u32>,
+) -> Result<Vec<PracticeScoreEntry>, String> {
+    let normalized_challenge_key = ChallengePeriod::Daily.normalize_archived_key(&challenge_key)?;
+    let ledger = read_ledger(&app)?;
+    Ok(ledger
+        .boards
+        .get(&normalized_challenge_key)
+        .map(|board| board.iter().take(normalize_limit(limit)).cloned().collect())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn fetch_daily_practice_replay<R: Runtime>(
+    app: AppHandle<R>,
+    challenge_key: String,
+    replay_id: String,
+) -> Result<DailyReplayProof, String> {
+    let normalized_challenge_key = ChallengePeriod::Daily.normalize_archived_key(&challenge_key)?;
+    let path = practice_replay_path(&app, &normalized_challenge_key, replay_id.trim())?;
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read practice replay: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse practice replay: {error}"))
+}
+
+/// Refuses to send a practice run to a ranked board, whether it arrives with a
+/// practice token or as a replay already saved on a practice board.
+pub(crate) fn ensure_not_practice_run<R: Runtime>(
+    app: &AppHandle<R>,
+    attempt_token: &str,
+    replay_proof: &DailyReplayProof,
+) -> Result<(), String> {
+    if attempt_token.starts_with(PRACTICE_TOKEN_PREFIX) {
+        return Err("practice runs cannot be submitted to ranked challenges".into());
+    }
+    let replay_id = replay_fingerprint(replay_proof);
+    let ledger = read_ledger(app)?;
+    let is_practice_replay = ledger
+        .boards
+        .values()
+        .flatten()
+        .any(|existing| existing.replay_id == replay_id);
+    if is_practice_replay {
+        return Err("practice runs cannot be submitted to ranked challenges".into());
+    }
+    Ok(())
+}
+
+fn sort_practice_board(board: &mut [PracticeScoreEntry]) {
+    board.sort_by(|a, b| {
+        b.entry
+            .score
+            .cmp(&a.entry.score)
+            .then_with(|| b.entry.level.cmp(&a.entry.level))
+            .then_with(|| a.entry.date.cmp(&b.entry.date))
+    });
+}
+
+fn prune_old_boards<R: Runtime>(app: &AppHandle<R>, ledger: &mut PracticeLedger) {
+    while ledger.boards.len() > PRACTICE_BOARD_MAX_DAYS {
+        let Some((challenge_key, board)) = ledger.boards.pop_first() else {
+            break;
+        };
+        for stale in board {
+            remove_practice_replay(app, &challenge_key, &stale.replay_id);
+        }
+    }
+}
+
+fn ledger_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(PRACTICE_FILE_NAME);
+    Ok(dir)
+}
+
+fn practice_replay_path<R: Runtime>(
+    app: &AppHandle<R>,
+    challenge_key: &str,
+    replay_id: &str,
+) -> Result<PathBuf, String> {
+    if replay_id.is_empty() || !replay_id.bytes().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err("practice replay id is invalid".into());
+    }
+    let mut dir = app_data_dir(app)?;
+    dir.push(PRACTICE_REPLAYS_DIR_NAME);
+    dir.push(format!("{challenge_key}-{replay_id}.json"));
+    Ok(dir)
+}
+
+fn write_practice_replay<R: Runtime>(
+    app: &AppHandle<R>,
+    challenge_key: &str,
+    replay_id: &str,
+    replay_proof: &DailyReplayProof,
+) -> Result<(), String> {
+    let path = practice_replay_path(app, challenge_key, replay_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("failed to create practice replay dir: {error}"))?;
+    }
+    let body = serde_json::to_string(replay_proof)
+        .map_err(|error| format!("failed to serialize practice replay: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write practice replay: {error}"))
+}
+
+fn remove_practice_replay<R: Runtime>(app: &AppHandle<R>, challenge_key: &str, replay_id: &str) {
+    if let Ok(path) = practice_replay_path(app, challenge_key, replay_id) {
+        if let Err(error) = fs::remove_file(&path) {
+            eprintln!(
+                "Failed to remove practice replay {}. {error}",
+                path.display()
+            );
+        }
+    }
+}
+
+fn read_ledger<R: Runtime>(app: &AppHandle<R>) -> Result<PracticeLedger, String> {
+    let path = ledger_path(app)?;
+    if !path.exists() {
+        return Ok(PracticeLedger::default());
+    }
+
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read daily practice: {error}"))?;
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn write_ledger<R: Runtime>(app: &AppHandle<R>, ledger: &PracticeLedger) -> Result<(), String> {
+    let path = ledger_path(app)?;
+    let body = serde_json::to_string(ledger)
+        .map_err(|error| format!("failed to serialize daily practice: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write daily practice: {error}"))
+}
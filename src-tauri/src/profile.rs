@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+use torus_core::clock::current_utc_seconds;
+
+use crate::name_policy::{ensure_server_name_blocklist, validate_display_name};
+use crate::scoreboard::{
+    app_data_dir, get_or_create_device_secret, get_or_create_device_uuid,
+    normalize_supabase_config, observe_server_date, rename_own_cached_entries, ScoreEntry,
+    SupabaseConfig,
+};
+
+const PROFILE_FILE_NAME: &str = "profile-v1.json";
+const UPSERT_PROFILE_RPC_NAME: &str = "upsert_profile";
+
+/// The device's display name, used for every record it submits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    #[serde(rename = "displayName")]
+    display_name: String,
+    /// ISO 3166-1 alpha-2 code shown as a flag.
+    #[serde(rename = "countryCode", default)]
+    country_code: Option<String>,
+    #[serde(rename = "createdAt")]
+    created_at: i64,
+    /// Whether Supabase has the current name and country.
+    #[serde(default)]
+    synced: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct UpsertProfilePayload<'a> {
+    p_client_uuid: &'a str,
+    p_device_secret: &'a str,
+    p_display_name: &'a str,
+    p_country_code: Option<&'a str>,
+}
+
+/// Why Supabase did not take a profile.
+enum ProfileSyncError {
+    /// The server refused the name or the device; saving it again will not help.
+    Refused(&'static str),
+    Failed(String),
+}
+
+/// The saved profile, if a display name has been chosen. A profile that
+/// could not be pushed earlier is pushed again here.
+#[tauri::command]
+pub async fn get_profile<R: Runtime>(
+    app: AppHandle<R>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<Option<PlayerProfile>, String> {
+    let Some(mut profile) = read_profile(&app)? else {
+        return Ok(None);
+    };
+    if !profile.synced {
+        if let Some(config) = normalize_supabase_config(&app, supabase_url, supabase_anon_key) {
+            sync_profile(&app, &config, &mut profile).await;
+        }
+    }
+    Ok(Some(profile))
+}
+
+/// Sets the display name and country. Renaming rewrites the name on every
+/// record this device owns, both in Supabase and in the local score cache.
+/// A name Supabase refuses is not saved; one that could not be sent is saved
+/// locally and pushed again later.
+#[tauri::command]
+pub async fn update_profile<R: Runtime>(
+    app: AppHandle<R>,
+    display_name: String,
+    country_code: Option<String>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<PlayerProfile, String> {
+    let config = normalize_supabase_config(&app, supabase_url, supabase_anon_key);
+    if let Some(config) = &config {
+        ensure_server_name_blocklist(config).await;
+    }
+    let display_name =
+        validate_display_name(&app, &display_name).map_err(|rejection| rejection.to_string())?;
+    let country_code = country_code
+        .map(|raw| normalize_country_code(&raw))
+        .transpose()?
+        .flatten();
+    let created_at = read_profile(&app)?
+        .map(|existing| existing.created_at)
+        .unwrap_or_else(current_utc_seconds);
+    let mut profile = PlayerProfile {
+        display_name,
+        country_code,
+        created_at,
+        synced: false,
+    };
+    if let Some(config) = &config {
+        match push_profile(&app, config, &profile).await {
+            Ok(()) => profile.synced = true,
+            Err(ProfileSyncError::Refused(message)) => return Err(message.into()),
+            Err(ProfileSyncError::Failed(error)) => {
+                eprintln!("Failed to save profile to Supabase. Profile kept locally. {error}");
+            }
+        }
+    }
+    write_profile(&app, &profile)?;
+    rename_own_cached_entries(&app, &profile.display_name)?;
+    Ok(profile)
+}
+
+/// Name to submit for this device: the profile name once one is chosen.
+pub(crate) fn display_name<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    match read_profile(app) {
+        Ok(profile) => profile.map(|profile| profile.display_name),
+        Err(error) => {
+            eprintln!("Failed to read profile. {error}");
+            None
+        }
+    }
+}
+
+/// Replaces the free-text name on a submitted entry with the profile name.
+pub(crate) fn apply_display_name<R: Runtime>(
+    app: &AppHandle<R>,
+    mut entry: ScoreEntry,
+) -> ScoreEntry {
+    if let Some(name) = display_name(app) {
+        entry.user = name;
+    }
+    entry
+}
+
+async fn sync_profile<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &SupabaseConfig,
+    profile: &mut PlayerProfile,
+) {
+    match push_profile(app, config, profile).await {
+        Ok(()) => {
+            profile.synced = true;
+            if let Err(error) = write_profile(app, profile) {
+                eprintln!("Failed to save synced profile. {error}");
+            }
+        }
+        Err(ProfileSyncError::Refused(message)) => {
+            eprintln!("Supabase refused the saved profile. Choose another name. {message}");
+        }
+        Err(ProfileSyncError::Failed(error)) => {
+            eprintln!("Failed to save profile to Supabase. Profile kept locally. {error}");
+        }
+    }
+}
+
+async fn push_profile<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &SupabaseConfig,
+    profile: &PlayerProfile,
+) -> Result<(), ProfileSyncError> {
+    let device_uuid = get_or_create_device_uuid(app).map_err(ProfileSyncError::Failed)?;
+    let device_secret = get_or_create_device_secret(app).map_err(ProfileSyncError::Failed)?;
+    upsert_remote_profile(config, &device_uuid, &device_secret, profile).await
+}
+
+fn normalize_country_code(raw: &str) -> Result<Option<String>, String> {
+    let code = raw.trim().to_ascii_uppercase();
+    if code.is_empty() {
+        return Ok(None);
+    }
+    if code.len() != 2 || !code.bytes().all(|ch| ch.is_ascii_uppercase()) {
+        return Err("country code must be two letters, like KR".into());
+    }
+    Ok(Some(code))
+}
+
+async fn upsert_remote_profile(
+    config: &SupabaseConfig,
+    device_uuid: &str,
+    device_secret: &str,
+    profile: &PlayerProfile,
+) -> Result<(), ProfileSyncError> {
+    let endpoint = format!(
+        "{}/rest/v1/rpc/{}",
+        config.url.trim_end_matches('/'),
+        UPSERT_PROFILE_RPC_NAME
+    );
+    let payload = UpsertProfilePayload {
+        p_client_uuid: device_uuid,
+        p_device_secret: device_secret,
+        p_display_name: &profile.display_name,
+        p_country_code: profile.country_code.as_deref(),
+    };
+
+    let client = &config.client;
+    let response = client
+        .post(endpoint)
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|error| {
+            ProfileSyncError::Failed(format!("supabase profile update failed: {error}"))
+        })?;
+    observe_server_date(&response);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if let Some(message) = profile_error_message(&body) {
+            return Err(ProfileSyncError::Refused(message));
+        }
+        return Err(ProfileSyncError::Failed(format!(
+            "supabase profile update failed with {status}: {body}\n\
+Ensure /supabase/schema.sql has been applied (including RPC {UPSERT_PROFILE_RPC_NAME})."
+        )));
+    }
+    Ok(())
+}
+
+/// Readable text for the exceptions raised by `upsert_profile`.
+fn profile_error_message(body: &str) -> Option<&'static str> {
+    [
+        ("NAME_BLOCKED", "display name contains a blocked word"),
+        ("NAME_RESERVED", "display name is reserved"),
+        (
+            "NAME_TAKEN",
+            "another player already uses that display name",
+        ),
+        (
+            "INVALID_PLAYER_NAME",
+            "display name has no visible characters",
+        ),
+        (
+            "DEVICE_AUTH_FAILED",
+            "this device's secret does not match the one registered for its id",
+        ),
+        (
+            "DEVICE_NOT_REGISTERED",
+            "this device has scores but no registered secret yet; submit a run first",
+        ),
+    ]
+    .into_iter()
+    .find(|(code, _)| body.contains(code))
+    .map(|(_, message)| message)
+}
+
+fn profile_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(PROFILE_FILE_NAME);
+    Ok(dir)
+}
+
+fn read_profile<R: Runtime>(app: &AppHandle<R>) -> Result<Option<PlayerProfile>, String> {
+    let path = profile_path(app)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw =
+        fs::read_to_string(&path).map_err(|error| format!("failed to read profile: {error}"))?;
+    Ok(serde_json::from_str(&raw).ok())
+}
+
+fn write_profile<R: Runtime>(app: &AppHandle<R>, profile: &PlayerProfile) -> Result<(), String> {
+    let path = profile_path(app)?;
+    let body = serde_json::to_string(profile)
+        .map_err(|error| format!("failed to serialize profile: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write profile: {error}"))
+}
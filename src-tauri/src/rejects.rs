@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+use torus_core::clock::current_utc_seconds;
+use torus_core::replay::{replay_fingerprint, DailyReplayProof};
+
+use crate::scoreboard::{app_data_dir, ScoreEntry};
+
+const REJECTS_DIR_NAME: &str = "rejected-submissions";
+const REJECTS_MAX_FILES: usize = 50;
+const BUG_REPORT_FORMAT: &str = "torus-rejects-v1";
+
+/// A submission `verify-score` refused, kept with everything needed to
+/// reproduce the verdict: the raw response and the exact entry and proof sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedSubmission {
+    id: String,
+    mode: String,
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    #[serde(rename = "rejectedAt")]
+    rejected_at: i64,
+    status: u16,
+    /// Response body exactly as received.
+    body: String,
+    /// The body parsed as JSON, including any expected/actual values.
+    #[serde(default)]
+    response: Option<serde_json::Value>,
+    entry: ScoreEntry,
+    #[serde(rename = "replayProof")]
+    replay_proof: DailyReplayProof,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RejectedSubmissionSummary {
+    id: String,
+    mode: String,
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    #[serde(rename = "rejectedAt")]
+    rejected_at: i64,
+    status: u16,
+    /// The `error` field of the response, when it has one.
+    error: Option<String>,
+    score: i64,
+    level: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct BugReportBundle<'a> {
+    format: &'static str,
+    #[serde(rename = "appVersion")]
+    app_version: String,
+    os: &'static str,
+    arch: &'static str,
+    #[serde(rename = "exportedAt")]
+    exported_at: i64,
+    rejects: &'a [RejectedSubmission],
+}
+
+/// Details of one refused submission, passed to `archive_rejection`.
+pub(crate) struct Rejection<'a> {
+    pub mode: &'a str,
+    pub challenge_key: &'a str,
+    pub status: u16,
+    pub body: &'a str,
+    pub entry: &'a ScoreEntry,
+    pub replay_proof: &'a DailyReplayProof,
+}
+
+/// Archived rejections, newest first.
+#[tauri::command]
+pub async fn list_rejected_submissions<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<RejectedSubmissionSummary>, String> {
+    Ok(read_rejects(&app)?
+        .into_iter()
+        .map(|reject| RejectedSubmissionSummary {
+            error: reject
+                .response
+                .as_ref()
+                .and_then(|value| value.get("error"))
+                .and_then(|value| value.as_str())
+                .map(str::to_string),
+            id: reject.id,
+            mode: reject.mode,
+            challenge_key: reject.challenge_key,
+            rejected_at: reject.rejected_at,
+            status: reject.status,
+            score: reject.entry.score,
+            level: reject.entry.level,
+        })
+        .collect())
+}
+
+/// Bundles the given rejections (all of them when `ids` is omitted) with the
+/// app version and platform into one JSON document to attach to a bug report.
+#[tauri::command]
+pub async fn export_rejected_submissions<R: Runtime>(
+    app: AppHandle<R>,
+    ids: Option<Vec<String>>,
+) -> Result<String, String> {
+    let mut rejects = read_rejects(&app)?;
+    if let Some(ids) = ids {
+        rejects.retain(|reject| ids.contains(&reject.id));
+        if rejects.is_empty() {
+            return Err("no rejected submissions match the given ids".into());
+        }
+    }
+    let bundle = BugReportBundle {
+        format: BUG_REPORT_FORMAT,
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        exported_at: current_utc_seconds(),
+        rejects: &rejects,
+    };
+    serde_json::to_string_pretty(&bundle)
+        .map_err(|error| format!("failed to serialize rejects bundle: {error}"))
+}
+
+/// Saves a rejected submission; only the newest `REJECTS_MAX_FILES` are kept.
+pub(crate) fn archive_rejection<R: Runtime>(app: &AppHandle<R>, rejection: Rejection<'_>) {
+    if let Err(error) = write_reject(app, rejection) {
+        eprintln!("Failed to archive rejected submission. {error}");
+    }
+}
+
+fn write_reject<R: Runtime>(app: &AppHandle<R>, rejection: Rejection<'_>) -> Result<(), String> {
+    let dir = rejects_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|error| format!("failed to create rejects dir: {error}"))?;
+
+    let rejected_at = current_utc_seconds();
+    let id = format!(
+        "{rejected_at}-{}-{}",
+        rejection.mode,
+        replay_fingerprint(rejection.replay_proof)
+    );
+    let reject = RejectedSubmission {
+        id: id.clone(),
+        mode: rejection.mode.to_string(),
+        challenge_key: rejection.challenge_key.to_string(),
+        rejected_at,
+        status: rejection.status,
+        body: rejection.body.to_string(),
+        response: serde_json::from_str(rejection.body).ok(),
+        entry: rejection.entry.clone(),
+        replay_proof: rejection.replay_proof.clone(),
+    };
+    let body = serde_json::to_string(&reject)
+        .map_err(|error| format!("failed to serialize rejected submission: {error}"))?;
+    fs::write(dir.join(format!("{id}.json")), body)
+        .map_err(|error| format!("failed to write rejected submission: {error}"))?;
+
+    let mut paths = reject_paths(app)?;
+    while paths.len() > REJECTS_MAX_FILES {
+        let oldest = paths.remove(0);
+        if let Err(error) = fs::remove_file(&oldest) {
+            eprintln!(
+                "Failed to remove old rejected submission {}. {error}",
+                oldest.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn rejects_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(REJECTS_DIR_NAME);
+    Ok(dir)
+}
+
+/// Archive files, oldest first; ids start with the rejection time.
+fn reject_paths<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<PathBuf>, String> {
+    let dir = rejects_dir(app)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = fs::read_dir(&dir)
+        .map_err(|error| format!("failed to read rejects dir: {error}"))?
+        .filter_map(|item| item.ok().map(|item| item.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort_by_key(|path| {
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("");
+        let rejected_at = stem
+            .split('-')
+            .next()
+            .and_then(|value| value.parse::<i64>().ok())
+            .unwrap_or(0);
+        (rejected_at, stem.to_string())
+    });
+    Ok(paths)
+}
+
+fn read_rejects<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<RejectedSubmission>, String> {
+    let mut rejects = Vec::new();
+    for path in reject_paths(app)?.into_iter().rev() {
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(error) => {
+                eprintln!(
+                    "Failed to read rejected submission {}. {error}",
+                    path.display()
+                );
+                continue;
+            }
+        };
+        match serde_json::from_str::<RejectedSubmission>(&raw) {
+            Ok(reject) => rejects.push(reject),
+            Err(error) => {
+                eprintln!(
+                    "Skipping unreadable rejected submission {}. {error}",
+                    path.display()
+                );
+            }
+        }
+    }
+    Ok(rejects)
+}
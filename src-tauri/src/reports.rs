@@ -0,0 +1,323 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+use torus_core::challenge_period::ChallengePeriod;
+use torus_core::clock::current_utc_seconds;
+
+use crate::friends::friend_code_for_device;
+use crate::scoreboard::{
+    app_data_dir, get_or_create_device_secret, get_or_create_device_uuid,
+    normalize_supabase_config, observe_server_date, ScoreEntry, SupabaseConfig,
+};
+
+const HIDDEN_PLAYERS_FILE_NAME: &str = "hidden-players-v1.json";
+const REPORT_SCORE_RPC_NAME: &str = "report_score";
+const CLASSIC_MODE: &str = "classic";
+const MAX_HIDDEN_PLAYERS: usize = 500;
+const MAX_REPORT_DETAIL_LEN: usize = 280;
+const REPORT_REASONS: [&str; 3] = ["cheating", "offensive_name", "other"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HiddenPlayer {
+    /// Public player id of a Supabase row; absent for local-only rows.
+    #[serde(rename = "playerId", default)]
+    player_id: Option<String>,
+    /// Name as shown when hidden; also matches local-only rows.
+    name: String,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(rename = "hiddenAt")]
+    hidden_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportResult {
+    reported: bool,
+    hidden: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HiddenPlayersFile {
+    #[serde(default)]
+    players: Vec<HiddenPlayer>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReportScorePayload<'a> {
+    p_reporter_client_uuid: &'a str,
+    p_device_secret: &'a str,
+    p_reported_player_id: &'a str,
+    p_mode: &'a str,
+    p_challenge_key: &'a str,
+    p_player_name: &'a str,
+    p_score: i64,
+    p_level: i64,
+    p_reason: &'a str,
+    p_detail: &'a str,
+}
+
+/// Players this device chose not to see on its boards.
+pub(crate) struct HiddenPlayers {
+    players: Vec<HiddenPlayer>,
+}
+
+impl HiddenPlayers {
+    pub(crate) fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Own rows are never hidden. Rows with a player id match by id; local-only
+    /// rows fall back to the name.
+    pub(crate) fn is_hidden(&self, entry: &ScoreEntry) -> bool {
+        if entry.is_me {
+            return false;
+        }
+        self.players
+            .iter()
+            .any(|hidden| match (&entry.player_id, &hidden.player_id) {
+                (Some(entry_id), Some(hidden_id)) => entry_id == hidden_id,
+                (None, _) => entry.user == hidden.name,
+                (Some(_), None) => false,
+            })
+    }
+
+    /// Drops hidden rows, then keeps the top `limit` of what is left.
+    pub(crate) fn filter_top(&self, entries: Vec<ScoreEntry>, limit: usize) -> Vec<ScoreEntry> {
+        entries
+            .into_iter()
+            .filter(|entry| !self.is_hidden(entry))
+            .take(limit)
+            .collect()
+    }
+}
+
+/// Reports a leaderboard row to Supabase and, unless `hide` is false, hides
+/// the player from this device's boards.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn report_score<R: Runtime>(
+    app: AppHandle<R>,
+    entry: ScoreEntry,
+    mode: String,
+    challenge_key: Option<String>,
+    reason: String,
+    detail: Option<String>,
+    hide: Option<bool>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<ReportResult, String> {
+    let config = normalize_supabase_config(&app, supabase_url, supabase_anon_key)
+        .ok_or_else(|| "reporting requires Supabase configuration".to_string())?;
+    if entry.is_me {
+        return Err("you cannot report your own score".into());
+    }
+    let reason = reason.trim().to_ascii_lowercase();
+    if !REPORT_REASONS.contains(&reason.as_str()) {
+        return Err(format!(
+            "report reason must be one of: {}",
+            REPORT_REASONS.join(", ")
+        ));
+    }
+    let (mode, challenge_key) = if mode.trim().eq_ignore_ascii_case(CLASSIC_MODE) {
+        (CLASSIC_MODE, CLASSIC_MODE.to_string())
+    } else {
+        let period = ChallengePeriod::from_mode(&mode)?;
+        let raw_key = challenge_key
+            .ok_or_else(|| format!("{} reports need a challenge key", period.mode()))?;
+        (period.mode(), period.normalize_archived_key(&raw_key)?)
+    };
+    let player_id = entry
+        .player_id
+        .as_deref()
+        .ok_or_else(|| "only online scores can be reported".to_string())?;
+    let detail = detail
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(MAX_REPORT_DETAIL_LEN)
+        .collect::<String>();
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let device_secret = get_or_create_device_secret(&app)?;
+
+    let payload = ReportScorePayload {
+        p_reporter_client_uuid: &device_uuid,
+        p_device_secret: &device_secret,
+        p_reported_player_id: player_id,
+        p_mode: mode,
+        p_challenge_key: &challenge_key,
+        p_player_name: &entry.user,
+        p_score: entry.score,
+        p_level: entry.level,
+        p_reason: &reason,
+        p_detail: &detail,
+    };
+    submit_remote_report(&config, &payload).await?;
+
+    let hidden = hide.unwrap_or(true);
+    if hidden {
+        hide_player(
+            &app,
+            HiddenPlayer {
+                player_id: Some(player_id.to_string()),
+                name: entry.user.clone(),
+                reason: Some(reason),
+                hidden_at: current_utc_seconds(),
+            },
+        )?;
+    }
+    Ok(ReportResult {
+        reported: true,
+        hidden,
+    })
+}
+
+#[tauri::command]
+pub async fn list_hidden_players<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<HiddenPlayer>, String> {
+    Ok(read_hidden_players(&app)?.players)
+}
+
+/// Removes a hidden player, matched by player id or, for local-only rows, by
+/// name.
+#[tauri::command]
+pub async fn unhide_player<R: Runtime>(
+    app: AppHandle<R>,
+    player_id: Option<String>,
+    name: Option<String>,
+) -> Result<Vec<HiddenPlayer>, String> {
+    let player_id = player_id
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let name = name
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    if player_id.is_none() && name.is_none() {
+        return Err("unhide needs a player id or name".into());
+    }
+
+    let mut file = read_hidden_players(&app)?;
+    file.players.retain(|hidden| match &player_id {
+        Some(player_id) => hidden.player_id.as_deref() != Some(player_id.as_str()),
+        None => Some(&hidden.name) != name.as_ref(),
+    });
+    write_hidden_players(&app, &file)?;
+    Ok(file.players)
+}
+
+pub(crate) fn load_hidden_players<R: Runtime>(app: &AppHandle<R>) -> HiddenPlayers {
+    let players = match read_hidden_players(app) {
+        Ok(file) => file.players,
+        Err(error) => {
+            eprintln!("Failed to read hidden players. {error}");
+            Vec::new()
+        }
+    };
+    HiddenPlayers { players }
+}
+
+/// Public id for the device behind a Supabase row: its friend code, which
+/// `report_score` resolves back to the device on the server.
+pub(crate) fn player_id_for(client_uuid: &str) -> String {
+    friend_code_for_device(client_uuid)
+}
+
+fn hide_player<R: Runtime>(app: &AppHandle<R>, player: HiddenPlayer) -> Result<(), String> {
+    let mut file = read_hidden_players(app)?;
+    file.players.retain(|existing| match &player.player_id {
+        Some(player_id) => existing.player_id.as_ref() != Some(player_id),
+        None => existing.name != player.name,
+    });
+    if file.players.len() >= MAX_HIDDEN_PLAYERS {
+        return Err(format!(
+            "hidden players list is limited to {MAX_HIDDEN_PLAYERS} entries"
+        ));
+    }
+    file.players.push(player);
+    write_hidden_players(app, &file)
+}
+
+async fn submit_remote_report(
+    config: &SupabaseConfig,
+    payload: &ReportScorePayload<'_>,
+) -> Result<(), String> {
+    let endpoint = format!(
+        "{}/rest/v1/rpc/{}",
+        config.url.trim_end_matches('/'),
+        REPORT_SCORE_RPC_NAME
+    );
+    let client = &config.client;
+    let response = client
+        .post(endpoint)
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .json(payload)
+        .send()
+        .await
+        .map_err(|error| format!("supabase score report failed: {error}"))?;
+    observe_server_date(&response);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if let Some(message) = report_error_message(&body) {
+            return Err(message.into());
+        }
+        return Err(format!(
+            "supabase score report failed with {status}: {body}\n\
+Ensure /supabase/schema.sql has been applied (including RPC {REPORT_SCORE_RPC_NAME})."
+        ));
+    }
+    Ok(())
+}
+
+/// Readable text for the exceptions raised by `report_score`.
+fn report_error_message(body: &str) -> Option<&'static str> {
+    [
+        (
+            "REPORT_LIMIT_REACHED",
+            "too many reports today; try again tomorrow",
+        ),
+        ("SCORE_NOT_FOUND", "that score is no longer on the board"),
+        ("CANNOT_REPORT_SELF", "you cannot report your own score"),
+        (
+            "DEVICE_AUTH_FAILED",
+            "this device's secret does not match the one registered for its id",
+        ),
+        (
+            "DEVICE_NOT_REGISTERED",
+            "this device has scores but no registered secret yet; submit a run first",
+        ),
+    ]
+    .into_iter()
+    .find(|(code, _)| body.contains(code))
+    .map(|(_, message)| message)
+}
+
+fn hidden_players_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(HIDDEN_PLAYERS_FILE_NAME);
+    Ok(dir)
+}
+
+fn read_hidden_players<R: Runtime>(app: &AppHandle<R>) -> Result<HiddenPlayersFile, String> {
+    let path = hidden_players_path(app)?;
+    if !path.exists() {
+        return Ok(HiddenPlayersFile::default());
+    }
+
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read hidden players: {error}"))?;
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn write_hidden_players<R: Runtime>(
+    app: &AppHandle<R>,
+    file: &HiddenPlayersFile,
+) -> Result<(), String> {
+    let path = hidden_players_path(app)?;
+    let body = serde_json::to_string(file)
+        .map_err(|error| format!("failed to serialize hidden players: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write hidden players: {error}"))
+}
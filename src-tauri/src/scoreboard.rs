@@ -2,16 +2,37 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Runtime};
+use torus_core::calendar::{
+    challenge_key_to_day_number, day_number_to_challenge_key, days_from_civil, days_in_month,
+    parse_i32_digits, SECONDS_PER_DAY,
+};
+use torus_core::challenge_period::{ChallengePeriod, MAX_CHALLENGE_ATTEMPTS};
+use torus_core::clock::{self, current_utc_seconds, server_clock_offset};
+use torus_core::replay::{DailyReplayProof, ReplayInputEvent};
 use uuid::Uuid;
 
+use crate::achievements::{self, RunRecord};
+use crate::badges::{badge_tiers, ensure_server_badge_tiers};
+use crate::friends::{self, FriendScope};
+use crate::http_client::HttpClient;
+use crate::name_policy;
+use crate::plausibility::{analyze_replay_inputs, PlausibilityVerdict};
+use crate::practice;
+use crate::profile;
+use crate::rejects::{self, Rejection};
+use crate::reports;
+use crate::submission_guard::{self, Admission, Refusal};
+use crate::verify_error::VerifyErrorResponse;
+
 const CACHE_FILE_NAME: &str = "scoreboard-global-cache-v1.json";
 const DEVICE_UUID_FILE_NAME: &str = "device-uuid-v1.txt";
+const DEVICE_SECRET_FILE_NAME: &str = "device-secret-v1.txt";
+const DEVICE_SECRET_LEN: usize = 64;
+const STREAK_STATE_CACHE_FILE_NAME: &str = "daily-streak-state-cache-v1.json";
 const CACHE_MAX_ENTRIES: usize = 100;
 const DEFAULT_TOP_LIMIT: usize = 10;
 const MAX_TOP_LIMIT: usize = 100;
-const HTTP_TIMEOUT_SECONDS: u64 = 8;
 const MAX_SKILL_USAGE_ITEMS: usize = 20;
 const MAX_SKILL_NAME_LEN: usize = 20;
 const MAX_SKILL_HOTKEY_LEN: usize = 16;
@@ -19,14 +40,21 @@ const MAX_SKILL_COMMAND_LEN: usize = 120;
 const CLASSIC_MODE: &str = "classic";
 const DAILY_MODE: &str = "daily";
 const CLASSIC_CHALLENGE_KEY: &str = "classic";
-const DAILY_MAX_ATTEMPTS: i64 = 3;
-const DAILY_BADGE_MAX_POWER: i64 = 9;
+const DAILY_STREAK_MAX_FREEZE_TOKENS: i64 = 2;
 const DAILY_START_RPC_NAME: &str = "start_daily_attempt";
 const DAILY_FORFEIT_RPC_NAME: &str = "forfeit_daily_attempt";
 const DAILY_ROLLBACK_RPC_NAME: &str = "rollback_daily_attempt";
+const DAILY_HISTORY_RANKS_RPC_NAME: &str = "daily_history_ranks";
+const CHALLENGE_SETTINGS_TABLE_NAME: &str = "challenge_settings";
 const VERIFY_SCORE_FUNCTION_NAME: &str = "verify-score";
 const MAX_DAILY_REPLAY_EVENTS: usize = 20_000;
 const MAX_DAILY_REPLAY_FINAL_TIME: i64 = 2_000_000;
+const DAILY_CHALLENGE_GRACE_DAYS: i64 = 1;
+const DAILY_HISTORY_MAX_DAYS: i64 = 31;
+const STREAK_CALENDAR_DEFAULT_DAYS: u32 = 365;
+const STREAK_CALENDAR_MAX_DAYS: u32 = 730;
+const STREAK_CALENDAR_MAX_TIER: i64 = 4;
+const DAILY_SUBMISSION_HISTORY_LIMIT: usize = 4096;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillUsage {
@@ -42,7 +70,11 @@ pub struct ScoreEntry {
     pub score: i64,
     pub level: i64,
     pub date: String,
-    #[serde(rename = "badgePower", default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "badgePower",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub badge_power: Option<i64>,
     #[serde(
         rename = "badgeMaxStreak",
@@ -54,6 +86,26 @@ pub struct ScoreEntry {
     pub skill_usage: Vec<SkillUsage>,
     #[serde(rename = "isMe", default)]
     pub is_me: bool,
+    #[serde(rename = "isFriend", default)]
+    pub is_friend: bool,
+    /// Public id of the device behind a Supabase row, used to report or hide it.
+    #[serde(rename = "playerId", default, skip_serializing_if = "Option::is_none")]
+    pub player_id: Option<String>,
+    /// Whether an own cached entry reached Supabase. Rows fetched from Supabase
+    /// are `synced`; entries cached before this field existed have none.
+    #[serde(rename = "syncState", default, skip_serializing_if = "Option::is_none")]
+    pub sync_state: Option<SyncState>,
+    /// Server's rejection reason when `sync_state` is `rejected`.
+    #[serde(rename = "syncError", default, skip_serializing_if = "Option::is_none")]
+    pub sync_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncState {
+    Pending,
+    Synced,
+    Rejected,
 }
 
 #[derive(Debug, Deserialize)]
@@ -77,6 +129,28 @@ struct DailyAttemptsRow {
 }
 
 #[derive(Debug, Deserialize)]
+struct ChallengeSettingsRow {
+    max_attempts: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct DailyHistoryRow {
+    challenge_key: String,
+    score: i64,
+    level: i64,
+    #[serde(default)]
+    attempts_used: Option<i64>,
+    #[serde(default)]
+    daily_has_submission: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DailyHistoryRankRow {
+    challenge_key: String,
+    rank: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct DailyStreakStateRow {
     #[serde(default)]
     current_streak: Option<i64>,
@@ -84,6 +158,19 @@ struct DailyStreakStateRow {
     max_streak: Option<i64>,
     #[serde(default)]
     last_submission_key: Option<String>,
+    #[serde(default)]
+    freeze_tokens: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedDailyStreakState {
+    #[serde(rename = "clientUuid")]
+    client_uuid: String,
+    #[serde(rename = "fetchedKey")]
+    fetched_key: String,
+    #[serde(rename = "fetchedAt")]
+    fetched_at: i64,
+    state: Option<DailyStreakStateRow>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -94,9 +181,11 @@ struct DailyStreakLookupRow {
 }
 
 #[derive(Debug, Clone)]
-struct SupabaseConfig {
-    url: String,
-    anon_key: String,
+pub(crate) struct SupabaseConfig {
+    pub(crate) url: String,
+    pub(crate) anon_key: String,
+    /// Handle to the shared pooled client from managed state.
+    pub(crate) client: reqwest::Client,
 }
 
 #[derive(Debug, Serialize)]
@@ -110,9 +199,76 @@ pub struct DailyStatus {
     #[serde(rename = "maxAttempts")]
     max_attempts: i64,
     #[serde(rename = "canSubmit")]
-    can_submit: bool,
+    pub(crate) can_submit: bool,
     #[serde(rename = "hasActiveAttempt")]
-    has_active_attempt: bool,
+    pub(crate) has_active_attempt: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyClock {
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    #[serde(rename = "graceChallengeKey")]
+    grace_challenge_key: String,
+    #[serde(rename = "secondsUntilRollover")]
+    seconds_until_rollover: i64,
+    #[serde(rename = "serverOffsetSeconds")]
+    server_offset_seconds: i64,
+    #[serde(rename = "serverSynced")]
+    server_synced: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChallengePeriodInfo {
+    mode: String,
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    #[serde(rename = "graceChallengeKey")]
+    grace_challenge_key: String,
+    seed: u32,
+    #[serde(rename = "maxAttempts")]
+    max_attempts: i64,
+    #[serde(rename = "secondsUntilRollover")]
+    seconds_until_rollover: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyHistoryRecord {
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    submitted: bool,
+    #[serde(rename = "bestScore")]
+    best_score: Option<i64>,
+    level: Option<i64>,
+    #[serde(rename = "attemptsUsed")]
+    attempts_used: i64,
+    rank: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreakCalendar {
+    days: Vec<StreakCalendarDay>,
+    runs: Vec<StreakRun>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreakCalendarDay {
+    #[serde(rename = "challengeKey")]
+    challenge_key: String,
+    submitted: bool,
+    #[serde(rename = "attemptsUsed")]
+    attempts_used: i64,
+    #[serde(rename = "scoreTier")]
+    score_tier: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StreakRun {
+    #[serde(rename = "startKey")]
+    start_key: String,
+    #[serde(rename = "endKey")]
+    end_key: String,
+    length: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,12 +281,26 @@ pub struct DailyBadgeStatus {
     pub highest_badge_power: Option<i64>,
     #[serde(rename = "highestBadgeDays")]
     pub highest_badge_days: Option<i64>,
+    #[serde(rename = "highestBadgeName", default)]
+    pub highest_badge_name: Option<String>,
+    #[serde(rename = "highestBadgeIconId", default)]
+    pub highest_badge_icon_id: Option<String>,
     #[serde(rename = "nextBadgePower")]
     pub next_badge_power: Option<i64>,
     #[serde(rename = "nextBadgeDays")]
     pub next_badge_days: Option<i64>,
+    #[serde(rename = "nextBadgeName", default)]
+    pub next_badge_name: Option<String>,
     #[serde(rename = "daysToNextBadge")]
     pub days_to_next_badge: Option<i64>,
+    #[serde(rename = "freezeTokens", default)]
+    pub freeze_tokens: i64,
+    #[serde(rename = "freezePending", default)]
+    pub freeze_pending: bool,
+    #[serde(rename = "projectedStreakAlive", default)]
+    pub projected_streak_alive: bool,
+    #[serde(default)]
+    pub offline: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -146,7 +316,7 @@ pub struct DailyAttemptStartResult {
     pub attempts_used: i64,
     #[serde(rename = "attemptsLeft")]
     pub attempts_left: i64,
-    #[serde(rename = "maxAttempts")]
+    #[serde(rename = "maxAttempts", default)]
     pub max_attempts: i64,
     #[serde(rename = "canSubmit")]
     pub can_submit: bool,
@@ -165,12 +335,54 @@ pub struct DailySubmitResult {
     pub attempts_used: i64,
     #[serde(rename = "attemptsLeft")]
     pub attempts_left: i64,
-    #[serde(rename = "maxAttempts")]
+    #[serde(rename = "maxAttempts", default)]
     pub max_attempts: i64,
     #[serde(rename = "canSubmit")]
     pub can_submit: bool,
     #[serde(rename = "hasActiveAttempt", default)]
     pub has_active_attempt: bool,
+    /// Set when the run was kept from the server; the attempt counts are then
+    /// the current status, and the attempt stays open.
+    #[serde(rename = "localOnly", default)]
+    pub local_only: bool,
+    /// `duplicate` or `rate_limited`, when `local_only` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Seconds until a `rate_limited` run may be sent.
+    #[serde(
+        rename = "retryAfter",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub retry_after: Option<i64>,
+}
+
+/// Outcome of a classic submission. Without a server answer the score is only
+/// cached locally, and `improved`/`previousBest` compare against that cache.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GlobalSubmitResult {
+    pub accepted: bool,
+    #[serde(default)]
+    pub improved: bool,
+    #[serde(rename = "previousBest", default)]
+    pub previous_best: Option<i64>,
+    #[serde(default)]
+    pub rank: Option<i64>,
+    #[serde(rename = "totalPlayers", default)]
+    pub total_players: Option<i64>,
+    #[serde(rename = "localOnly", default)]
+    pub local_only: bool,
+    /// Why a run stayed local without being sent: `duplicate` or
+    /// `rate_limited`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Seconds until a `rate_limited` run may be sent.
+    #[serde(
+        rename = "retryAfter",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub retry_after: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -182,7 +394,7 @@ pub struct DailyForfeitResult {
     pub attempts_used: i64,
     #[serde(rename = "attemptsLeft")]
     pub attempts_left: i64,
-    #[serde(rename = "maxAttempts")]
+    #[serde(rename = "maxAttempts", default)]
     pub max_attempts: i64,
     #[serde(rename = "canSubmit")]
     pub can_submit: bool,
@@ -190,32 +402,18 @@ pub struct DailyForfeitResult {
     pub has_active_attempt: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ReplayInputEvent {
-    pub time: i64,
-    #[serde(rename = "move")]
-    pub move_dir: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DailyReplayProof {
-    pub version: i64,
-    pub difficulty: i64,
-    pub seed: u32,
-    #[serde(rename = "finalTime")]
-    pub final_time: i64,
-    #[serde(rename = "finalScore")]
-    pub final_score: i64,
-    #[serde(rename = "finalLevel")]
-    pub final_level: i64,
-    pub inputs: Vec<ReplayInputEvent>,
-}
-
 #[derive(Debug, Serialize)]
 struct DailyStartPayload<'a> {
     p_client_uuid: &'a str,
     p_challenge_key: &'a str,
     p_player_name: &'a str,
+    p_mode: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct DailyHistoryRanksPayload<'a> {
+    p_client_uuid: &'a str,
+    p_challenge_keys: &'a [String],
 }
 
 #[derive(Debug, Serialize)]
@@ -223,6 +421,7 @@ struct DailyForfeitPayload<'a> {
     p_client_uuid: &'a str,
     p_challenge_key: &'a str,
     p_attempt_token: &'a str,
+    p_mode: &'a str,
 }
 
 #[derive(Debug, Serialize)]
@@ -234,15 +433,20 @@ struct VerifyScorePayload<'a> {
     attempt_token: Option<&'a str>,
     #[serde(rename = "clientUuid")]
     client_uuid: &'a str,
+    /// Binds the device's secret on its first verified run; see
+    /// `public.authenticate_submission`.
+    #[serde(rename = "deviceSecret")]
+    device_secret: &'a str,
     entry: &'a ScoreEntry,
     #[serde(rename = "replayProof")]
     replay_proof: &'a DailyReplayProof,
 }
 
 #[tauri::command]
-pub async fn fetch_global_scores(
-    app: AppHandle,
+pub async fn fetch_global_scores<R: Runtime>(
+    app: AppHandle<R>,
     limit: Option<u32>,
+    friends_only: Option<bool>,
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
 ) -> Result<Vec<ScoreEntry>, String> {
@@ -255,16 +459,45 @@ pub async fn fetch_global_scores(
             None
         }
     };
-    let config = normalize_supabase_config(supabase_url, supabase_anon_key);
+    let config = normalize_supabase_config(&app, supabase_url, supabase_anon_key);
+    let friends_only = friends_only.unwrap_or(false);
+    let friend_scope = friends::load_friend_scope(&app, config.as_ref(), friends_only).await;
+    // Over-fetch by the number of hidden players so the board stays full.
+    let hidden_players = reports::load_hidden_players(&app);
+    let fetch_limit = top_limit + hidden_players.len();
+
+    if friends_only {
+        // The local cache has no device identities, so friends boards are remote only.
+        let config = config
+            .ok_or_else(|| "friends leaderboard requires Supabase configuration".to_string())?;
+        let remote_entries = fetch_remote_scores(
+            &config,
+            fetch_limit,
+            device_uuid.as_deref(),
+            &friend_scope,
+            None,
+        )
+        .await?;
+        return Ok(hidden_players.filter_top(remote_entries, top_limit));
+    }
 
     if let Some(config) = config {
-        match fetch_remote_scores(&config, top_limit, device_uuid.as_deref()).await {
+        match fetch_remote_scores(
+            &config,
+            fetch_limit,
+            device_uuid.as_deref(),
+            &friend_scope,
+            None,
+        )
+        .await
+        {
             Ok(remote_entries) => {
+                reconcile_sync_states(&mut cache, &remote_entries);
                 cache.extend(remote_entries.clone());
                 sort_and_dedupe(&mut cache);
                 truncate_cache(&mut cache);
                 write_cache(&app, &cache)?;
-                return Ok(remote_entries);
+                return Ok(hidden_players.filter_top(remote_entries, top_limit));
             }
             Err(error) => {
                 eprintln!("Failed to load scores from Supabase. Using local cache. {error}");
@@ -273,55 +506,146 @@ pub async fn fetch_global_scores(
     }
 
     sort_and_dedupe(&mut cache);
-    Ok(cache.into_iter().take(top_limit).collect())
+    Ok(hidden_players.filter_top(cache, top_limit))
 }
 
 #[tauri::command]
-pub async fn submit_global_score(
-    app: AppHandle,
+pub async fn submit_global_score<R: Runtime>(
+    app: AppHandle<R>,
     entry: ScoreEntry,
     replay_proof: DailyReplayProof,
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
-) -> Result<(), String> {
+) -> Result<GlobalSubmitResult, String> {
     let mut cache = read_cache(&app)?;
-    let mut entry = sanitize_entry(entry)?;
+    let mut entry = profile::apply_display_name(&app, sanitize_entry(entry)?);
     let replay_proof = sanitize_daily_replay_proof(replay_proof)?;
     let device_uuid = get_or_create_device_uuid(&app)?;
+    let device_secret = get_or_create_device_secret(&app)?;
     entry.is_me = true;
-    cache.push(entry.clone());
-    sort_and_dedupe(&mut cache);
-    truncate_cache(&mut cache);
-    write_cache(&app, &cache)?;
-
-    if let Some(config) = normalize_supabase_config(supabase_url, supabase_anon_key) {
-        if let Err(error) = submit_remote_global_score(
-            &config,
-            &entry,
+    entry.sync_state = Some(SyncState::Pending);
+    let local_best = cache
+        .iter()
+        .filter(|cached| cached.is_me)
+        .map(|cached| (cached.score, cached.level))
+        .max();
+    let mut result = GlobalSubmitResult {
+        accepted: false,
+        improved: local_best.is_none_or(|best| (entry.score, entry.level) > best),
+        previous_best: local_best.map(|(score, _)| score),
+        rank: None,
+        total_players: None,
+        local_only: true,
+        reason: None,
+        retry_after: None,
+    };
+    // The guard runs before caching: a duplicate is already cached with the
+    // server's verdict, which a new pending copy must not replace.
+    let config = normalize_supabase_config(&app, supabase_url, supabase_anon_key);
+    let admission = match &config {
+        Some(_) => Some(submission_guard::admit_submission(
+            &app,
+            CLASSIC_MODE,
             &replay_proof,
-            &device_uuid,
-        )
-        .await
-        {
-            eprintln!("Failed to save score to Supabase. Score kept locally. {error}");
+        )?),
+        None => None,
+    };
+    if !matches!(admission, Some(Admission::Refused(Refusal::Duplicate))) {
+        cache.push(entry.clone());
+        sort_and_dedupe(&mut cache);
+        truncate_cache(&mut cache);
+        write_cache(&app, &cache)?;
+    }
+
+    match (config, admission) {
+        (_, Some(Admission::Refused(refusal))) => {
+            eprintln!("Kept classic run from Supabase ({}).", refusal.code());
+            result.reason = Some(refusal.code().to_string());
+            result.retry_after = refusal.retry_after();
         }
+        (Some(config), Some(Admission::Admitted(ticket))) => {
+            let remote = submit_remote_global_score(
+                &config,
+                &entry,
+                &replay_proof,
+                &device_uuid,
+                &device_secret,
+            )
+            .await;
+            record_submit_outcome(
+                &app,
+                &ticket,
+                CLASSIC_MODE,
+                CLASSIC_CHALLENGE_KEY,
+                &entry,
+                &replay_proof,
+                &remote,
+            );
+            match remote {
+                Ok(remote_result) => {
+                    mark_cached_sync_state(&app, &entry, SyncState::Synced, None);
+                    result = remote_result;
+                }
+                Err(error) => {
+                    if let RemoteSubmitError::Rejected { reason, .. } = &error {
+                        mark_cached_sync_state(&app, &entry, SyncState::Rejected, Some(reason));
+                    }
+                    eprintln!("Failed to save score to Supabase. Score kept locally. {error}");
+                }
+            }
+        }
+        _ => {}
     }
 
-    Ok(())
+    achievements::record_run(
+        &app,
+        RunRecord {
+            mode: CLASSIC_MODE,
+            challenge_key: None,
+            entry: &entry,
+            replay_proof: &replay_proof,
+            daily_rank: None,
+        },
+    )
+    .await;
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn fetch_daily_scores<R: Runtime>(
+    app: AppHandle<R>,
+    challenge_key: String,
+    limit: Option<u32>,
+    friends_only: Option<bool>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<Vec<ScoreEntry>, String> {
+    fetch_challenge_scores(
+        app,
+        DAILY_MODE.to_string(),
+        challenge_key,
+        limit,
+        friends_only,
+        supabase_url,
+        supabase_anon_key,
+    )
+    .await
 }
 
 #[tauri::command]
-pub async fn fetch_daily_scores(
-    app: AppHandle,
+pub async fn fetch_challenge_scores<R: Runtime>(
+    app: AppHandle<R>,
+    mode: String,
     challenge_key: String,
     limit: Option<u32>,
+    friends_only: Option<bool>,
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
 ) -> Result<Vec<ScoreEntry>, String> {
+    let period = ChallengePeriod::from_mode(&mode)?;
     let top_limit = normalize_limit(limit);
-    let normalized_challenge_key = normalize_daily_challenge_key(&challenge_key)?;
-    let config = normalize_supabase_config(supabase_url, supabase_anon_key)
-        .ok_or_else(|| "daily challenge sync requires Supabase configuration".to_string())?;
+    let normalized_challenge_key = period.normalize_archived_key(&challenge_key)?;
+    let config = require_challenge_config(&app, period, supabase_url, supabase_anon_key)?;
     let device_uuid = match get_or_create_device_uuid(&app) {
         Ok(value) => Some(value),
         Err(error) => {
@@ -329,81 +653,295 @@ pub async fn fetch_daily_scores(
             None
         }
     };
-    fetch_remote_daily_scores(
+    let friend_scope =
+        friends::load_friend_scope(&app, Some(&config), friends_only.unwrap_or(false)).await;
+    let hidden_players = reports::load_hidden_players(&app);
+    let remote_entries = fetch_remote_challenge_scores(
         &config,
+        period,
         &normalized_challenge_key,
-        top_limit,
+        top_limit + hidden_players.len(),
         device_uuid.as_deref(),
+        &friend_scope,
+        None,
+    )
+    .await?;
+    Ok(hidden_players.filter_top(remote_entries, top_limit))
+}
+
+#[tauri::command]
+pub async fn get_daily_clock<R: Runtime>(
+    app: AppHandle<R>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<DailyClock, String> {
+    if server_clock_offset().is_none() {
+        if let Some(config) = normalize_supabase_config(&app, supabase_url, supabase_anon_key) {
+            if let Err(error) = sync_server_clock(&config).await {
+                eprintln!("Failed to sync server clock. Using local UTC clock. {error}");
+            }
+        }
+    }
+    Ok(build_daily_clock(current_utc_seconds()))
+}
+
+#[tauri::command]
+pub async fn get_challenge_period<R: Runtime>(
+    app: AppHandle<R>,
+    mode: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<ChallengePeriodInfo, String> {
+    let period = ChallengePeriod::from_mode(&mode)?;
+    let config = normalize_supabase_config(&app, supabase_url, supabase_anon_key);
+    if server_clock_offset().is_none() {
+        if let Some(config) = config.as_ref() {
+            if let Err(error) = sync_server_clock(config).await {
+                eprintln!("Failed to sync server clock. Using local UTC clock. {error}");
+            }
+        }
+    }
+    let challenge_key = period.current_key();
+    let max_attempts = match config.as_ref() {
+        Some(config) => resolve_challenge_max_attempts(config, period, &challenge_key).await,
+        None => period.default_max_attempts(),
+    };
+    Ok(ChallengePeriodInfo {
+        mode: period.mode().to_string(),
+        seed: period.seed(&challenge_key),
+        grace_challenge_key: period.grace_key(),
+        max_attempts,
+        seconds_until_rollover: period.seconds_until_rollover(),
+        challenge_key,
+    })
+}
+
+#[tauri::command]
+pub async fn fetch_daily_history<R: Runtime>(
+    app: AppHandle<R>,
+    from: String,
+    to: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<Vec<DailyHistoryRecord>, String> {
+    let from_key = ChallengePeriod::Daily.normalize_archived_key(&from)?;
+    let to_key = ChallengePeriod::Daily.normalize_archived_key(&to)?;
+    let challenge_keys = daily_challenge_key_range(&from_key, &to_key)?;
+    let config = normalize_supabase_config(&app, supabase_url, supabase_anon_key)
+        .ok_or_else(|| "daily challenge sync requires Supabase configuration".to_string())?;
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let rows =
+        fetch_remote_daily_history_rows(&config, Some(&challenge_keys), &device_uuid).await?;
+    let submitted_keys = rows
+        .iter()
+        .filter(|row| row.daily_has_submission.unwrap_or(false))
+        .map(|row| row.challenge_key.clone())
+        .collect::<Vec<_>>();
+    let ranks = if submitted_keys.is_empty() {
+        HashMap::new()
+    } else {
+        match fetch_remote_daily_ranks(&config, &device_uuid, &submitted_keys).await {
+            Ok(ranks) => ranks,
+            Err(error) => {
+                eprintln!("Failed to resolve daily ranks. {error}");
+                HashMap::new()
+            }
+        }
+    };
+    let mut rows_by_key = rows
+        .into_iter()
+        .map(|row| (row.challenge_key.clone(), row))
+        .collect::<HashMap<_, _>>();
+
+    let mut records = Vec::with_capacity(challenge_keys.len());
+    for challenge_key in challenge_keys {
+        let Some(row) = rows_by_key.remove(&challenge_key) else {
+            records.push(DailyHistoryRecord {
+                challenge_key,
+                submitted: false,
+                best_score: None,
+                level: None,
+                attempts_used: 0,
+                rank: None,
+            });
+            continue;
+        };
+
+        let attempts_used = row
+            .attempts_used
+            .unwrap_or(0)
+            .clamp(0, MAX_CHALLENGE_ATTEMPTS);
+        if !row.daily_has_submission.unwrap_or(false) {
+            records.push(DailyHistoryRecord {
+                challenge_key,
+                submitted: false,
+                best_score: None,
+                level: None,
+                attempts_used,
+                rank: None,
+            });
+            continue;
+        }
+
+        records.push(DailyHistoryRecord {
+            rank: ranks.get(&challenge_key).copied(),
+            challenge_key,
+            submitted: true,
+            best_score: Some(row.score),
+            level: Some(row.level),
+            attempts_used,
+        });
+    }
+    Ok(records)
+}
+
+#[tauri::command]
+pub async fn fetch_streak_calendar<R: Runtime>(
+    app: AppHandle<R>,
+    days: Option<u32>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<StreakCalendar, String> {
+    let span = days
+        .unwrap_or(STREAK_CALENDAR_DEFAULT_DAYS)
+        .clamp(1, STREAK_CALENDAR_MAX_DAYS) as i64;
+    let config = normalize_supabase_config(&app, supabase_url, supabase_anon_key)
+        .ok_or_else(|| "daily challenge sync requires Supabase configuration".to_string())?;
+    let device_uuid = get_or_create_device_uuid(&app)?;
+    let rows = fetch_remote_daily_history_rows(&config, None, &device_uuid).await?;
+    let today = current_utc_seconds().div_euclid(SECONDS_PER_DAY);
+    Ok(build_streak_calendar(&rows, today - span + 1, today))
+}
+
+#[tauri::command]
+pub async fn fetch_daily_status<R: Runtime>(
+    app: AppHandle<R>,
+    challenge_key: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<DailyStatus, String> {
+    fetch_challenge_status(
+        app,
+        DAILY_MODE.to_string(),
+        challenge_key,
+        supabase_url,
+        supabase_anon_key,
     )
     .await
 }
 
 #[tauri::command]
-pub async fn fetch_daily_status(
-    app: AppHandle,
+pub async fn fetch_challenge_status<R: Runtime>(
+    app: AppHandle<R>,
+    mode: String,
     challenge_key: String,
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
 ) -> Result<DailyStatus, String> {
-    let normalized_challenge_key = normalize_daily_challenge_key(&challenge_key)?;
-    let config = normalize_supabase_config(supabase_url, supabase_anon_key)
-        .ok_or_else(|| "daily challenge sync requires Supabase configuration".to_string())?;
+    let period = ChallengePeriod::from_mode(&mode)?;
+    let normalized_challenge_key = period.normalize_active_key(&challenge_key)?;
+    let config = require_challenge_config(&app, period, supabase_url, supabase_anon_key)?;
     let device_uuid = get_or_create_device_uuid(&app)?;
-    let remote_status =
-        fetch_remote_daily_attempts(&config, &normalized_challenge_key, &device_uuid).await?;
-    Ok(build_daily_status(
-        &normalized_challenge_key,
-        remote_status.attempts_used,
-        remote_status.has_active_attempt,
-    ))
+    load_challenge_status(&config, period, &normalized_challenge_key, &device_uuid).await
 }
 
 #[tauri::command]
-pub async fn fetch_daily_badge_status(
-    app: AppHandle,
+pub async fn fetch_daily_badge_status<R: Runtime>(
+    app: AppHandle<R>,
     challenge_key: String,
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
 ) -> Result<DailyBadgeStatus, String> {
-    let normalized_challenge_key = normalize_daily_challenge_key(&challenge_key)?;
-    let config = normalize_supabase_config(supabase_url, supabase_anon_key)
+    let normalized_challenge_key = ChallengePeriod::Daily.normalize_active_key(&challenge_key)?;
+    let config = normalize_supabase_config(&app, supabase_url, supabase_anon_key)
         .ok_or_else(|| "daily challenge sync requires Supabase configuration".to_string())?;
     let device_uuid = get_or_create_device_uuid(&app)?;
-    let state = fetch_remote_daily_streak_state(&config, &device_uuid).await?;
-    let (current_streak, max_streak) = match state {
-        Some(value) => {
-            let stored_current = value.current_streak.unwrap_or(0).max(0);
-            let stored_max = value.max_streak.unwrap_or(0).max(stored_current).max(0);
-            let effective_current =
-                if is_current_streak_alive(value.last_submission_key.as_deref(), &normalized_challenge_key) {
-                    stored_current
-                } else {
-                    0
-                };
-            (effective_current, stored_max)
+    ensure_server_badge_tiers(&config).await;
+    let (state, offline) = match fetch_remote_daily_streak_state(&config, &device_uuid).await {
+        Ok(state) => {
+            if let Err(error) =
+                write_streak_state_cache(&app, &device_uuid, &normalized_challenge_key, &state)
+            {
+                eprintln!("Failed to cache daily streak state. {error}");
+            }
+            (state, false)
+        }
+        Err(error) => {
+            let Some(cached) = read_streak_state_cache(&app, &device_uuid) else {
+                return Err(error);
+            };
+            eprintln!(
+                "Failed to load daily streak state. Using cache from {}. {error}",
+                cached.fetched_key
+            );
+            (cached.state, true)
         }
-        None => (0, 0),
     };
-    Ok(badge_status_from_streaks(current_streak, max_streak))
+    let mut status = badge_status_from_streak_state(state.as_ref(), &normalized_challenge_key);
+    status.offline = offline;
+    Ok(status)
 }
 
 #[tauri::command]
-pub async fn start_daily_attempt(
-    app: AppHandle,
+pub async fn start_daily_attempt<R: Runtime>(
+    app: AppHandle<R>,
     challenge_key: String,
+    display_name: Option<String>,
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
 ) -> Result<DailyAttemptStartResult, String> {
-    let normalized_challenge_key = normalize_daily_challenge_key(&challenge_key)?;
-    let config = normalize_supabase_config(supabase_url, supabase_anon_key)
-        .ok_or_else(|| "daily challenge sync requires Supabase configuration".to_string())?;
+    start_challenge_attempt(
+        app,
+        DAILY_MODE.to_string(),
+        challenge_key,
+        display_name,
+        supabase_url,
+        supabase_anon_key,
+    )
+    .await
+}
+
+/// Charges an attempt. Without a profile, `display_name` is the name the run
+/// will be submitted under; it is checked here, before the attempt is used.
+#[tauri::command]
+pub async fn start_challenge_attempt<R: Runtime>(
+    app: AppHandle<R>,
+    mode: String,
+    challenge_key: String,
+    display_name: Option<String>,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<DailyAttemptStartResult, String> {
+    let period = ChallengePeriod::from_mode(&mode)?;
+    let normalized_challenge_key = period.normalize_active_key(&challenge_key)?;
+    let config = require_challenge_config(&app, period, supabase_url, supabase_anon_key)?;
     let device_uuid = get_or_create_device_uuid(&app)?;
-    start_remote_daily_attempt(&config, &normalized_challenge_key, &device_uuid).await
+    let player_name = match profile::display_name(&app) {
+        Some(name) => Some(name),
+        None => match display_name {
+            Some(raw) => {
+                name_policy::ensure_server_name_blocklist(&config).await;
+                Some(
+                    name_policy::validate_display_name(&app, &raw)
+                        .map_err(|rejection| rejection.to_string())?,
+                )
+            }
+            None => None,
+        },
+    };
+    start_remote_challenge_attempt(
+        &config,
+        period,
+        &normalized_challenge_key,
+        &device_uuid,
+        player_name.as_deref(),
+    )
+    .await
 }
 
 #[tauri::command]
-pub async fn submit_daily_score(
-    app: AppHandle,
+pub async fn submit_daily_score<R: Runtime>(
+    app: AppHandle<R>,
     challenge_key: String,
     attempt_token: String,
     entry: ScoreEntry,
@@ -411,45 +949,156 @@ pub async fn submit_daily_score(
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
 ) -> Result<DailySubmitResult, String> {
-    let normalized_challenge_key = normalize_daily_challenge_key(&challenge_key)?;
-    let config = normalize_supabase_config(supabase_url, supabase_anon_key)
-        .ok_or_else(|| "daily challenge sync requires Supabase configuration".to_string())?;
-    let entry = sanitize_entry(entry)?;
+    submit_challenge_score(
+        app,
+        DAILY_MODE.to_string(),
+        challenge_key,
+        attempt_token,
+        entry,
+        replay_proof,
+        supabase_url,
+        supabase_anon_key,
+    )
+    .await
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn submit_challenge_score<R: Runtime>(
+    app: AppHandle<R>,
+    mode: String,
+    challenge_key: String,
+    attempt_token: String,
+    entry: ScoreEntry,
+    replay_proof: DailyReplayProof,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<DailySubmitResult, String> {
+    let period = ChallengePeriod::from_mode(&mode)?;
+    let normalized_challenge_key = period.normalize_active_key(&challenge_key)?;
+    let config = require_challenge_config(&app, period, supabase_url, supabase_anon_key)?;
+    let entry = profile::apply_display_name(&app, sanitize_entry(entry)?);
     let replay_proof = sanitize_daily_replay_proof(replay_proof)?;
     let device_uuid = get_or_create_device_uuid(&app)?;
-    let normalized_attempt_token = attempt_token.trim().to_string();
-    if normalized_attempt_token.is_empty() {
-        return Err("daily challenge attempt token is required".into());
-    }
-    submit_remote_daily_score(
+    let device_secret = get_or_create_device_secret(&app)?;
+    let normalized_attempt_token = normalize_attempt_token(period, &attempt_token)?;
+    practice::ensure_not_practice_run(&app, &normalized_attempt_token, &replay_proof)?;
+    let ticket = match submission_guard::admit_submission(&app, period.mode(), &replay_proof)? {
+        Admission::Admitted(ticket) => ticket,
+        Admission::Refused(refusal) => {
+            eprintln!(
+                "Kept {} run from Supabase ({}).",
+                period.mode(),
+                refusal.code()
+            );
+            let status =
+                load_challenge_status(&config, period, &normalized_challenge_key, &device_uuid)
+                    .await?;
+            return Ok(DailySubmitResult {
+                accepted: false,
+                improved: false,
+                challenge_key: status.challenge_key,
+                attempts_used: status.attempts_used,
+                attempts_left: status.attempts_left,
+                max_attempts: status.max_attempts,
+                can_submit: status.can_submit,
+                has_active_attempt: status.has_active_attempt,
+                local_only: true,
+                reason: Some(refusal.code().to_string()),
+                retry_after: refusal.retry_after(),
+            });
+        }
+    };
+    let result = submit_remote_challenge_score(
         &config,
+        period,
         &normalized_challenge_key,
         &normalized_attempt_token,
         &entry,
         &replay_proof,
         &device_uuid,
+        &device_secret,
+    )
+    .await;
+    record_submit_outcome(
+        &app,
+        &ticket,
+        period.mode(),
+        &normalized_challenge_key,
+        &entry,
+        &replay_proof,
+        &result,
+    );
+    let result = result?;
+    if result.accepted {
+        // Only a skill-free run that became the day's best can win the day, so
+        // the rank lookup is skipped for every other run.
+        let daily_rank = if period == ChallengePeriod::Daily
+            && result.improved
+            && entry.skill_usage.is_empty()
+        {
+            let keys = std::slice::from_ref(&normalized_challenge_key);
+            match fetch_remote_daily_ranks(&config, &device_uuid, keys).await {
+                Ok(ranks) => ranks.get(&normalized_challenge_key).copied(),
+                Err(error) => {
+                    eprintln!("Failed to load daily rank for achievements. {error}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        achievements::record_run(
+            &app,
+            RunRecord {
+                mode: period.mode(),
+                challenge_key: Some(&normalized_challenge_key),
+                entry: &entry,
+                replay_proof: &replay_proof,
+                daily_rank,
+            },
+        )
+        .await;
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn forfeit_daily_attempt<R: Runtime>(
+    app: AppHandle<R>,
+    challenge_key: String,
+    attempt_token: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<DailyForfeitResult, String> {
+    forfeit_challenge_attempt(
+        app,
+        DAILY_MODE.to_string(),
+        challenge_key,
+        attempt_token,
+        supabase_url,
+        supabase_anon_key,
     )
     .await
 }
 
 #[tauri::command]
-pub async fn forfeit_daily_attempt(
-    app: AppHandle,
+pub async fn forfeit_challenge_attempt<R: Runtime>(
+    app: AppHandle<R>,
+    mode: String,
     challenge_key: String,
     attempt_token: String,
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
 ) -> Result<DailyForfeitResult, String> {
-    let normalized_challenge_key = normalize_daily_challenge_key(&challenge_key)?;
-    let config = normalize_supabase_config(supabase_url, supabase_anon_key)
-        .ok_or_else(|| "daily challenge sync requires Supabase configuration".to_string())?;
-    let normalized_attempt_token = attempt_token.trim().to_string();
-    if normalized_attempt_token.is_empty() {
-        return Err("daily challenge attempt token is required".into());
-    }
+    let period = ChallengePeriod::from_mode(&mode)?;
+    let normalized_challenge_key = period.normalize_active_key(&challenge_key)?;
+    let config = require_challenge_config(&app, period, supabase_url, supabase_anon_key)?;
+    let normalized_attempt_token = normalize_attempt_token(period, &attempt_token)?;
     let device_uuid = get_or_create_device_uuid(&app)?;
-    forfeit_remote_daily_attempt(
+    forfeit_remote_challenge_attempt(
         &config,
+        period,
         &normalized_challenge_key,
         &normalized_attempt_token,
         &device_uuid,
@@ -458,23 +1107,41 @@ pub async fn forfeit_daily_attempt(
 }
 
 #[tauri::command]
-pub async fn rollback_daily_attempt(
-    app: AppHandle,
+pub async fn rollback_daily_attempt<R: Runtime>(
+    app: AppHandle<R>,
     challenge_key: String,
     attempt_token: String,
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
 ) -> Result<DailyForfeitResult, String> {
-    let normalized_challenge_key = normalize_daily_challenge_key(&challenge_key)?;
-    let config = normalize_supabase_config(supabase_url, supabase_anon_key)
-        .ok_or_else(|| "daily challenge sync requires Supabase configuration".to_string())?;
-    let normalized_attempt_token = attempt_token.trim().to_string();
-    if normalized_attempt_token.is_empty() {
-        return Err("daily challenge attempt token is required".into());
-    }
+    rollback_challenge_attempt(
+        app,
+        DAILY_MODE.to_string(),
+        challenge_key,
+        attempt_token,
+        supabase_url,
+        supabase_anon_key,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn rollback_challenge_attempt<R: Runtime>(
+    app: AppHandle<R>,
+    mode: String,
+    challenge_key: String,
+    attempt_token: String,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<DailyForfeitResult, String> {
+    let period = ChallengePeriod::from_mode(&mode)?;
+    let normalized_challenge_key = period.normalize_active_key(&challenge_key)?;
+    let config = require_challenge_config(&app, period, supabase_url, supabase_anon_key)?;
+    let normalized_attempt_token = normalize_attempt_token(period, &attempt_token)?;
     let device_uuid = get_or_create_device_uuid(&app)?;
-    rollback_remote_daily_attempt(
+    rollback_remote_challenge_attempt(
         &config,
+        period,
         &normalized_challenge_key,
         &normalized_attempt_token,
         &device_uuid,
@@ -482,12 +1149,13 @@ pub async fn rollback_daily_attempt(
     .await
 }
 
-fn normalize_limit(limit: Option<u32>) -> usize {
+pub(crate) fn normalize_limit(limit: Option<u32>) -> usize {
     let raw = limit.unwrap_or(DEFAULT_TOP_LIMIT as u32);
     raw.clamp(1, MAX_TOP_LIMIT as u32) as usize
 }
 
-fn normalize_supabase_config(
+pub(crate) fn normalize_supabase_config<R: Runtime>(
+    app: &AppHandle<R>,
     supabase_url: Option<String>,
     supabase_anon_key: Option<String>,
 ) -> Option<SupabaseConfig> {
@@ -498,28 +1166,221 @@ fn normalize_supabase_config(
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty())?;
 
-    Some(SupabaseConfig { url, anon_key })
+    let client = app.state::<HttpClient>().client().clone();
+    Some(SupabaseConfig {
+        url,
+        anon_key,
+        client,
+    })
+}
+
+pub(crate) fn require_challenge_config<R: Runtime>(
+    app: &AppHandle<R>,
+    period: ChallengePeriod,
+    supabase_url: Option<String>,
+    supabase_anon_key: Option<String>,
+) -> Result<SupabaseConfig, String> {
+    normalize_supabase_config(app, supabase_url, supabase_anon_key).ok_or_else(|| {
+        format!(
+            "{} challenge sync requires Supabase configuration",
+            period.mode()
+        )
+    })
 }
 
-fn normalize_daily_challenge_key(raw: &str) -> Result<String, String> {
-    let trimmed = raw.trim();
-    if trimmed.len() != 10 {
-        return Err("daily challenge key must be in YYYY-MM-DD format".into());
+fn normalize_attempt_token(period: ChallengePeriod, raw: &str) -> Result<String, String> {
+    let normalized = raw.trim().to_string();
+    if normalized.is_empty() {
+        return Err(format!(
+            "{} challenge attempt token is required",
+            period.mode()
+        ));
     }
-    let bytes = trimmed.as_bytes();
-    for (index, ch) in bytes.iter().enumerate() {
-        let is_dash = index == 4 || index == 7;
-        if is_dash {
-            if *ch != b'-' {
-                return Err("daily challenge key must be in YYYY-MM-DD format".into());
-            }
-            continue;
-        }
-        if !(*ch >= b'0' && *ch <= b'9') {
-            return Err("daily challenge key must be in YYYY-MM-DD format".into());
+    Ok(normalized)
+}
+
+fn daily_challenge_key_range(from_key: &str, to_key: &str) -> Result<Vec<String>, String> {
+    let from_day = challenge_key_to_day_number(from_key)
+        .ok_or_else(|| "daily history start key is invalid".to_string())?;
+    let to_day = challenge_key_to_day_number(to_key)
+        .ok_or_else(|| "daily history end key is invalid".to_string())?;
+    if from_day > to_day {
+        return Err("daily history start key must not be after end key".into());
+    }
+    if to_day - from_day >= DAILY_HISTORY_MAX_DAYS {
+        return Err(format!(
+            "daily history range cannot exceed {DAILY_HISTORY_MAX_DAYS} days"
+        ));
+    }
+    Ok((from_day..=to_day)
+        .map(day_number_to_challenge_key)
+        .collect())
+}
+
+fn build_daily_clock(now_seconds: i64) -> DailyClock {
+    let today = now_seconds.div_euclid(SECONDS_PER_DAY);
+    let next_rollover = (today + 1) * SECONDS_PER_DAY;
+    let offset = server_clock_offset();
+    DailyClock {
+        challenge_key: day_number_to_challenge_key(today),
+        grace_challenge_key: day_number_to_challenge_key(today - DAILY_CHALLENGE_GRACE_DAYS),
+        seconds_until_rollover: (next_rollover - now_seconds).max(0),
+        server_offset_seconds: offset.unwrap_or(0),
+        server_synced: offset.is_some(),
+    }
+}
+
+pub(crate) fn observe_server_date(response: &reqwest::Response) {
+    let Some(server_seconds) = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_http_date)
+    else {
+        return;
+    };
+    clock::record_server_time(server_seconds);
+}
+
+async fn sync_server_clock(config: &SupabaseConfig) -> Result<(), String> {
+    let endpoint = format!(
+        "{}/rest/v1/daily_streak_states",
+        config.url.trim_end_matches('/')
+    );
+    let client = &config.client;
+    let response = client
+        .head(endpoint)
+        .query(&[("select", "client_uuid"), ("limit", "1")])
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .send()
+        .await
+        .map_err(|error| format!("supabase clock sync failed: {error}"))?;
+    observe_server_date(&response);
+    if server_clock_offset().is_none() {
+        return Err("supabase response did not include a Date header".into());
+    }
+    Ok(())
+}
+
+/// Parses an IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`) into Unix seconds.
+/// The weekday must match the date, as RFC 7231 requires.
+fn parse_http_date(raw: &str) -> Option<i64> {
+    let parts = raw.split_whitespace().collect::<Vec<_>>();
+    if parts.len() != 6 || parts[5] != "GMT" {
+        return None;
+    }
+    let day = parse_i32_digits(parts[1])?;
+    let month = match parts[2] {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year = parse_i32_digits(parts[3])?;
+    if day < 1 || day as u32 > days_in_month(year, month) {
+        return None;
+    }
+
+    let clock = parts[4].split(':').collect::<Vec<_>>();
+    if clock.len() != 3 {
+        return None;
+    }
+    let hour = parse_i32_digits(clock[0])? as i64;
+    let minute = parse_i32_digits(clock[1])? as i64;
+    let second = parse_i32_digits(clock[2])? as i64;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day as u32);
+    // Unix day 0 was a Thursday.
+    let weekday =
+        ["Mon,", "Tue,", "Wed,", "Thu,", "Fri,", "Sat,", "Sun,"][(days + 3).rem_euclid(7) as usize];
+    if parts[0] != weekday {
+        return None;
+    }
+    Some(days * SECONDS_PER_DAY + hour * 3_600 + minute * 60 + second)
+}
+
+/// Whether two timestamps name the same millisecond. Postgres echoes the
+/// client's `2026-01-01T00:00:00.000Z` back as `2026-01-01T00:00:00+00:00`;
+/// unparseable values only match verbatim.
+fn same_instant(left: &str, right: &str) -> bool {
+    match (parse_timestamp_millis(left), parse_timestamp_millis(right)) {
+        (Some(left), Some(right)) => left == right,
+        _ => left.trim() == right.trim(),
+    }
+}
+
+/// Parses an RFC 3339 timestamp (`T` or space separated, optional fraction,
+/// `Z` or a `±HH[:MM]` offset) into Unix milliseconds.
+fn parse_timestamp_millis(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    if raw.len() < 19 || !raw.is_ascii() {
+        return None;
+    }
+    let (date, rest) = raw.split_at(10);
+    let day_number = challenge_key_to_day_number(date)?;
+    let separator = rest.as_bytes()[0];
+    if separator != b'T' && separator != b't' && separator != b' ' {
+        return None;
+    }
+    let clock = &rest[1..9];
+    let bytes = clock.as_bytes();
+    if bytes[2] != b':' || bytes[5] != b':' {
+        return None;
+    }
+    let hour = parse_i32_digits(&clock[0..2])? as i64;
+    let minute = parse_i32_digits(&clock[3..5])? as i64;
+    let second = parse_i32_digits(&clock[6..8])? as i64;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &rest[9..];
+    let mut millis = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
         }
+        let padded = format!("{:0<3}", &fraction[..digits.min(3)]);
+        millis = parse_i32_digits(&padded)? as i64;
+        rest = &fraction[digits..];
     }
-    Ok(trimmed.to_string())
+    let offset_seconds = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let offset = rest[1..].replace(':', "");
+            if offset.len() != 2 && offset.len() != 4 {
+                return None;
+            }
+            let hours = parse_i32_digits(&offset[0..2])? as i64;
+            let minutes = match offset.get(2..4) {
+                Some(value) => parse_i32_digits(value)? as i64,
+                None => 0,
+            };
+            sign * (hours * 3_600 + minutes * 60)
+        }
+    };
+
+    let seconds = day_number * SECONDS_PER_DAY + hour * 3_600 + minute * 60 + second;
+    Some((seconds - offset_seconds) * 1_000 + millis)
 }
 
 struct RemoteDailyAttemptStatus {
@@ -527,28 +1388,44 @@ struct RemoteDailyAttemptStatus {
     has_active_attempt: bool,
 }
 
-fn build_daily_status(
+pub(crate) async fn load_challenge_status(
+    config: &SupabaseConfig,
+    period: ChallengePeriod,
+    challenge_key: &str,
+    owner_key: &str,
+) -> Result<DailyStatus, String> {
+    let remote_status =
+        fetch_remote_challenge_attempts(config, period, challenge_key, owner_key).await?;
+    let max_attempts = resolve_challenge_max_attempts(config, period, challenge_key).await;
+    Ok(build_challenge_status(
+        challenge_key,
+        max_attempts,
+        remote_status.attempts_used,
+        remote_status.has_active_attempt,
+    ))
+}
+
+fn build_challenge_status(
     challenge_key: &str,
+    max_attempts: i64,
     attempts_used: i64,
     has_active_attempt: bool,
 ) -> DailyStatus {
-    let used = attempts_used.clamp(0, DAILY_MAX_ATTEMPTS);
-    let left = DAILY_MAX_ATTEMPTS - used;
+    let used = attempts_used.clamp(0, max_attempts);
+    let left = max_attempts - used;
     DailyStatus {
         challenge_key: challenge_key.to_string(),
         attempts_used: used,
         attempts_left: left,
-        max_attempts: DAILY_MAX_ATTEMPTS,
+        max_attempts,
         can_submit: left > 0,
         has_active_attempt,
     }
 }
 
-fn sanitize_entry(entry: ScoreEntry) -> Result<ScoreEntry, String> {
-    let user = entry.user.trim().chars().take(20).collect::<String>();
-    if user.is_empty() {
-        return Err("score entry user is empty".into());
-    }
+pub(crate) fn sanitize_entry(entry: ScoreEntry) -> Result<ScoreEntry, String> {
+    let user = name_policy::normalize_display_name(&entry.user)
+        .map_err(|rejection| rejection.to_string())?;
 
     let mut seen = HashSet::<String>::new();
     let mut skill_usage: Vec<SkillUsage> = Vec::new();
@@ -565,7 +1442,13 @@ fn sanitize_entry(entry: ScoreEntry) -> Result<ScoreEntry, String> {
 
         let hotkey = usage
             .hotkey
-            .map(|value| value.trim().chars().take(MAX_SKILL_HOTKEY_LEN).collect::<String>())
+            .map(|value| {
+                value
+                    .trim()
+                    .chars()
+                    .take(MAX_SKILL_HOTKEY_LEN)
+                    .collect::<String>()
+            })
             .and_then(|value| if value.is_empty() { None } else { Some(value) });
 
         let command = usage
@@ -605,10 +1488,16 @@ fn sanitize_entry(entry: ScoreEntry) -> Result<ScoreEntry, String> {
         badge_max_streak: None,
         skill_usage,
         is_me: false,
+        is_friend: false,
+        player_id: None,
+        sync_state: None,
+        sync_error: None,
     })
 }
 
-fn sanitize_daily_replay_proof(proof: DailyReplayProof) -> Result<DailyReplayProof, String> {
+pub(crate) fn sanitize_daily_replay_proof(
+    proof: DailyReplayProof,
+) -> Result<DailyReplayProof, String> {
     if proof.version != 1 {
         return Err("daily replay proof version is not supported".into());
     }
@@ -654,6 +1543,25 @@ fn sanitize_daily_replay_proof(proof: DailyReplayProof) -> Result<DailyReplayPro
         return Err("daily replay input exceeds final time".into());
     }
 
+    let plausibility = analyze_replay_inputs(&sanitized_inputs);
+    match plausibility.verdict {
+        PlausibilityVerdict::Refused => {
+            return Err(format!(
+                "daily replay input timing is implausible ({}; suspicion {:.2})",
+                plausibility.flags.join(", "),
+                plausibility.suspicion
+            ));
+        }
+        PlausibilityVerdict::Flagged => {
+            eprintln!(
+                "Replay input timing looks suspicious ({}; suspicion {:.2}).",
+                plausibility.flags.join(", "),
+                plausibility.suspicion
+            );
+        }
+        PlausibilityVerdict::Ok => {}
+    }
+
     Ok(DailyReplayProof {
         version: 1,
         difficulty: proof.difficulty,
@@ -665,7 +1573,7 @@ fn sanitize_daily_replay_proof(proof: DailyReplayProof) -> Result<DailyReplayPro
     })
 }
 
-fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn app_data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
     let dir = app
         .path()
         .app_data_dir()
@@ -675,18 +1583,61 @@ fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(dir)
 }
 
-fn score_cache_path(app: &AppHandle) -> Result<PathBuf, String> {
+fn score_cache_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
     let mut dir = app_data_dir(app)?;
     dir.push(CACHE_FILE_NAME);
     Ok(dir)
 }
 
-fn device_uuid_path(app: &AppHandle) -> Result<PathBuf, String> {
+fn device_uuid_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
     let mut dir = app_data_dir(app)?;
     dir.push(DEVICE_UUID_FILE_NAME);
     Ok(dir)
 }
 
+fn device_secret_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(DEVICE_SECRET_FILE_NAME);
+    Ok(dir)
+}
+
+fn streak_state_cache_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(STREAK_STATE_CACHE_FILE_NAME);
+    Ok(dir)
+}
+
+fn read_streak_state_cache<R: Runtime>(
+    app: &AppHandle<R>,
+    owner_key: &str,
+) -> Option<CachedDailyStreakState> {
+    let path = streak_state_cache_path(app).ok()?;
+    let raw = fs::read_to_string(path).ok()?;
+    let cached = serde_json::from_str::<CachedDailyStreakState>(&raw).ok()?;
+    if cached.client_uuid != owner_key {
+        return None;
+    }
+    Some(cached)
+}
+
+fn write_streak_state_cache<R: Runtime>(
+    app: &AppHandle<R>,
+    owner_key: &str,
+    challenge_key: &str,
+    state: &Option<DailyStreakStateRow>,
+) -> Result<(), String> {
+    let path = streak_state_cache_path(app)?;
+    let cached = CachedDailyStreakState {
+        client_uuid: owner_key.to_string(),
+        fetched_key: challenge_key.to_string(),
+        fetched_at: current_utc_seconds(),
+        state: state.clone(),
+    };
+    let body = serde_json::to_string(&cached)
+        .map_err(|error| format!("failed to serialize streak state cache: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write streak state cache: {error}"))
+}
+
 fn read_device_uuid(path: &Path) -> Option<String> {
     if !path.exists() {
         return None;
@@ -697,7 +1648,7 @@ fn read_device_uuid(path: &Path) -> Option<String> {
     Some(normalized.to_string())
 }
 
-fn get_or_create_device_uuid(app: &AppHandle) -> Result<String, String> {
+pub(crate) fn get_or_create_device_uuid<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
     let path = device_uuid_path(app)?;
     if let Some(existing) = read_device_uuid(&path) {
         return Ok(existing);
@@ -708,7 +1659,34 @@ fn get_or_create_device_uuid(app: &AppHandle) -> Result<String, String> {
     Ok(created)
 }
 
-fn read_cache(app: &AppHandle) -> Result<Vec<ScoreEntry>, String> {
+fn read_device_secret(path: &Path) -> Option<String> {
+    let raw = fs::read_to_string(path).ok()?;
+    let secret = raw.trim();
+    let is_valid = secret.len() == DEVICE_SECRET_LEN
+        && secret
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte));
+    is_valid.then(|| secret.to_string())
+}
+
+/// Random secret that proves this install owns its device UUID. The UUID is
+/// public on every board, so RPCs that change what a device owns (profile,
+/// friend code, groups) also take this secret; the server keeps only its hash.
+pub(crate) fn get_or_create_device_secret<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<String, String> {
+    let path = device_secret_path(app)?;
+    if let Some(existing) = read_device_secret(&path) {
+        return Ok(existing);
+    }
+
+    let created = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    fs::write(&path, &created)
+        .map_err(|error| format!("failed to write device secret: {error}"))?;
+    Ok(created)
+}
+
+fn read_cache<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<ScoreEntry>, String> {
     let path = score_cache_path(app)?;
     if !path.exists() {
         return Ok(Vec::new());
@@ -722,7 +1700,7 @@ fn read_cache(app: &AppHandle) -> Result<Vec<ScoreEntry>, String> {
     Ok(entries)
 }
 
-fn write_cache(app: &AppHandle, entries: &[ScoreEntry]) -> Result<(), String> {
+fn write_cache<R: Runtime>(app: &AppHandle<R>, entries: &[ScoreEntry]) -> Result<(), String> {
     let path = score_cache_path(app)?;
     let body = serde_json::to_string(entries)
         .map_err(|error| format!("failed to serialize cache: {error}"))?;
@@ -747,6 +1725,11 @@ fn sort_and_dedupe(entries: &mut Vec<ScoreEntry>) {
         );
         if let Some(existing) = deduped.get_mut(&key) {
             existing.is_me = existing.is_me || entry.is_me;
+            // A copy seen on the server settles the entry.
+            if existing.sync_state.is_none() || entry.sync_state == Some(SyncState::Synced) {
+                existing.sync_state = entry.sync_state;
+                existing.sync_error = entry.sync_error;
+            }
             continue;
         }
         deduped.insert(key, entry);
@@ -762,124 +1745,170 @@ fn sort_and_dedupe(entries: &mut Vec<ScoreEntry>) {
     *entries = values;
 }
 
-fn truncate_cache(entries: &mut Vec<ScoreEntry>) {
-    if entries.len() > CACHE_MAX_ENTRIES {
-        entries.truncate(CACHE_MAX_ENTRIES);
+/// Names of other players on the cached global board.
+pub(crate) fn cached_board_names<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    match read_cache(app) {
+        Ok(cache) => cache
+            .into_iter()
+            .filter(|entry| !entry.is_me)
+            .map(|entry| entry.user)
+            .collect(),
+        Err(error) => {
+            eprintln!("Failed to read score cache for name checks. {error}");
+            Vec::new()
+        }
     }
 }
 
-fn create_http_client() -> Result<reqwest::Client, String> {
-    reqwest::Client::builder()
-        .timeout(Duration::from_secs(HTTP_TIMEOUT_SECONDS))
-        .build()
-        .map_err(|error| format!("failed to build http client: {error}"))
+/// Rewrites the name on this device's cached records after a profile rename.
+pub(crate) fn rename_own_cached_entries<R: Runtime>(
+    app: &AppHandle<R>,
+    display_name: &str,
+) -> Result<(), String> {
+    let mut cache = read_cache(app)?;
+    for entry in cache.iter_mut().filter(|entry| entry.is_me) {
+        entry.user = display_name.to_string();
+    }
+    sort_and_dedupe(&mut cache);
+    write_cache(app, &cache)
 }
 
-async fn fetch_remote_scores(
-    config: &SupabaseConfig,
-    limit: usize,
-    owner_key: Option<&str>,
-) -> Result<Vec<ScoreEntry>, String> {
-    let endpoint = format!("{}/rest/v1/scores", config.url.trim_end_matches('/'));
-    let client = create_http_client()?;
-    let request = client
-        .get(endpoint)
-        .query(&[
-            (
-                "select",
-                "player_name,score,level,created_at,skill_usage,client_uuid",
-            ),
-            ("mode", "eq.classic"),
-            ("challenge_key", "eq.classic"),
-            ("order", "score.desc,level.desc,created_at.desc"),
-            ("limit", &limit.to_string()),
-        ])
-        .header("apikey", &config.anon_key)
-        .header("Authorization", format!("Bearer {}", config.anon_key));
-
-    let response = request
-        .send()
-        .await
-        .map_err(|error| format!("supabase request failed: {error}"))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("supabase request failed with {status}: {body}"));
+/// Settles own cached entries that the server's board already shows, matched
+/// by score, level and date like `mark_cached_sync_state`, but not by name
+/// since a rename may have changed it.
+fn reconcile_sync_states(cache: &mut [ScoreEntry], remote_entries: &[ScoreEntry]) {
+    for cached in cache
+        .iter_mut()
+        .filter(|cached| cached.is_me && cached.sync_state != Some(SyncState::Synced))
+    {
+        let on_server = remote_entries.iter().any(|remote| {
+            remote.is_me
+                && remote.score == cached.score
+                && remote.level == cached.level
+                && same_instant(&remote.date, &cached.date)
+        });
+        if on_server {
+            cached.sync_state = Some(SyncState::Synced);
+            cached.sync_error = None;
+        }
     }
+}
 
-    let rows = response
-        .json::<Vec<ScoreRow>>()
-        .await
-        .map_err(|error| format!("failed to decode supabase response: {error}"))?;
-
-    let owners = rows
-        .iter()
-        .filter_map(|row| row.client_uuid.clone())
-        .collect::<Vec<_>>();
-    let streak_map = match fetch_remote_streak_max_map(config, &owners).await {
-        Ok(map) => map,
-        Err(error) => {
-            eprintln!("Failed to load streak states for scoreboard rows. {error}");
-            HashMap::new()
+fn mark_cached_sync_state<R: Runtime>(
+    app: &AppHandle<R>,
+    entry: &ScoreEntry,
+    sync_state: SyncState,
+    sync_error: Option<&str>,
+) {
+    let result = read_cache(app).and_then(|mut cache| {
+        for cached in cache.iter_mut().filter(|cached| {
+            cached.is_me
+                && cached.score == entry.score
+                && cached.level == entry.level
+                && cached.date == entry.date
+        }) {
+            cached.sync_state = Some(sync_state);
+            cached.sync_error = sync_error.map(str::to_string);
         }
-    };
+        write_cache(app, &cache)
+    });
+    if let Err(error) = result {
+        eprintln!("Failed to update cached sync state. {error}");
+    }
+}
+
+fn truncate_cache(entries: &mut Vec<ScoreEntry>) {
+    if entries.len() > CACHE_MAX_ENTRIES {
+        entries.truncate(CACHE_MAX_ENTRIES);
+    }
+}
 
-    Ok(rows
-        .into_iter()
-        .map(|row| ScoreEntry {
-            badge_max_streak: row
-                .client_uuid
-                .as_ref()
-                .and_then(|value| streak_map.get(value).copied()),
-            badge_power: row
-                .client_uuid
-                .as_ref()
-                .and_then(|value| streak_map.get(value).copied())
-                .and_then(resolve_badge_power),
-            user: row.player_name,
-            score: row.score,
-            level: row.level,
-            date: row.created_at,
-            skill_usage: row.skill_usage.unwrap_or_default(),
-            is_me: is_owned_by_owner(row.client_uuid.as_deref(), owner_key),
-        })
-        .collect())
+pub(crate) async fn fetch_remote_scores(
+    config: &SupabaseConfig,
+    limit: usize,
+    owner_key: Option<&str>,
+    friend_scope: &FriendScope,
+    member_uuids: Option<&[String]>,
+) -> Result<Vec<ScoreEntry>, String> {
+    let filters = vec![
+        ("mode", "eq.classic".to_string()),
+        ("challenge_key", "eq.classic".to_string()),
+    ];
+    let board = RemoteBoard {
+        owner_key,
+        friend_scope,
+        member_uuids,
+    };
+    fetch_remote_board(config, filters, limit, board, "request").await
 }
 
-async fn fetch_remote_daily_scores(
+pub(crate) async fn fetch_remote_challenge_scores(
     config: &SupabaseConfig,
+    period: ChallengePeriod,
     challenge_key: &str,
     limit: usize,
     owner_key: Option<&str>,
+    friend_scope: &FriendScope,
+    member_uuids: Option<&[String]>,
+) -> Result<Vec<ScoreEntry>, String> {
+    let mode = period.mode();
+    let filters = vec![
+        ("mode", format!("eq.{mode}")),
+        ("challenge_key", format!("eq.{challenge_key}")),
+        ("daily_has_submission", "eq.true".to_string()),
+    ];
+    let board = RemoteBoard {
+        owner_key,
+        friend_scope,
+        member_uuids,
+    };
+    fetch_remote_board(config, filters, limit, board, &format!("{mode} fetch")).await
+}
+
+/// Whose rows a board fetch keeps and how they are tagged.
+struct RemoteBoard<'a> {
+    owner_key: Option<&'a str>,
+    friend_scope: &'a FriendScope,
+    member_uuids: Option<&'a [String]>,
+}
+
+/// Reads one board from `scores` with the given column filters, then tags
+/// each row with its owner's badge, friendship and ownership.
+async fn fetch_remote_board(
+    config: &SupabaseConfig,
+    filters: Vec<(&str, String)>,
+    limit: usize,
+    board: RemoteBoard<'_>,
+    action: &str,
 ) -> Result<Vec<ScoreEntry>, String> {
     let endpoint = format!("{}/rest/v1/scores", config.url.trim_end_matches('/'));
-    let mode_filter = format!("eq.{DAILY_MODE}");
-    let challenge_filter = format!("eq.{challenge_key}");
-    let client = create_http_client()?;
-    let response = client
+    let mut query = vec![(
+        "select",
+        "player_name,score,level,created_at,skill_usage,client_uuid".to_string(),
+    )];
+    query.extend(filters);
+    query.push(("order", "score.desc,level.desc,created_at.desc".to_string()));
+    query.push(("limit", limit.to_string()));
+    if let Some(filter) =
+        board_client_filter(board.friend_scope, board.owner_key, board.member_uuids)
+    {
+        query.push(("client_uuid", filter));
+    }
+    let response = config
+        .client
         .get(endpoint)
-        .query(&[
-            (
-                "select",
-                "player_name,score,level,created_at,skill_usage,client_uuid",
-            ),
-            ("mode", mode_filter.as_str()),
-            ("challenge_key", challenge_filter.as_str()),
-            ("daily_has_submission", "eq.true"),
-            ("order", "score.desc,level.desc,created_at.desc"),
-            ("limit", &limit.to_string()),
-        ])
+        .query(&query)
         .header("apikey", &config.anon_key)
         .header("Authorization", format!("Bearer {}", config.anon_key))
         .send()
         .await
-        .map_err(|error| format!("supabase daily fetch failed: {error}"))?;
+        .map_err(|error| format!("supabase {action} failed: {error}"))?;
+    observe_server_date(&response);
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        return Err(format!("supabase daily fetch failed with {status}: {body}"));
+        return Err(format!("supabase {action} failed with {status}: {body}"));
     }
 
     let rows = response
@@ -891,32 +1920,36 @@ async fn fetch_remote_daily_scores(
         .iter()
         .filter_map(|row| row.client_uuid.clone())
         .collect::<Vec<_>>();
+    ensure_server_badge_tiers(config).await;
     let streak_map = match fetch_remote_streak_max_map(config, &owners).await {
         Ok(map) => map,
         Err(error) => {
-            eprintln!("Failed to load streak states for daily rows. {error}");
+            eprintln!("Failed to load streak states for scoreboard rows. {error}");
             HashMap::new()
         }
     };
 
     Ok(rows
         .into_iter()
-        .map(|row| ScoreEntry {
-            badge_max_streak: row
-                .client_uuid
-                .as_ref()
-                .and_then(|value| streak_map.get(value).copied()),
-            badge_power: row
+        .map(|row| {
+            let max_streak = row
                 .client_uuid
                 .as_ref()
-                .and_then(|value| streak_map.get(value).copied())
-                .and_then(resolve_badge_power),
-            user: row.player_name,
-            score: row.score,
-            level: row.level,
-            date: row.created_at,
-            skill_usage: row.skill_usage.unwrap_or_default(),
-            is_me: is_owned_by_owner(row.client_uuid.as_deref(), owner_key),
+                .and_then(|value| streak_map.get(value).copied());
+            ScoreEntry {
+                badge_max_streak: max_streak,
+                badge_power: max_streak.and_then(resolve_badge_power),
+                user: row.player_name,
+                score: row.score,
+                level: row.level,
+                date: row.created_at,
+                skill_usage: row.skill_usage.unwrap_or_default(),
+                is_me: is_owned_by_owner(row.client_uuid.as_deref(), board.owner_key),
+                is_friend: board.friend_scope.is_friend(row.client_uuid.as_deref()),
+                player_id: row.client_uuid.as_deref().map(reports::player_id_for),
+                sync_state: Some(SyncState::Synced),
+                sync_error: None,
+            }
         })
         .collect())
 }
@@ -947,8 +1980,15 @@ async fn fetch_remote_streak_max_map(
         "{}/rest/v1/daily_streak_states",
         config.url.trim_end_matches('/')
     );
-    let owner_filter = format!("in.({})", normalized.iter().map(|value| quote_postgrest_text(value)).collect::<Vec<_>>().join(","));
-    let client = create_http_client()?;
+    let owner_filter = format!(
+        "in.({})",
+        normalized
+            .iter()
+            .map(|value| quote_postgrest_text(value))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let client = &config.client;
     let response = client
         .get(endpoint)
         .query(&[
@@ -961,6 +2001,7 @@ async fn fetch_remote_streak_max_map(
         .send()
         .await
         .map_err(|error| format!("supabase streak state fetch failed: {error}"))?;
+    observe_server_date(&response);
 
     if !response.status().is_success() {
         let status = response.status();
@@ -984,21 +2025,55 @@ async fn fetch_remote_streak_max_map(
     Ok(map)
 }
 
-fn quote_postgrest_text(raw: &str) -> String {
+/// `client_uuid=in.(...)` filter for narrowed boards: the given group
+/// members, or friends plus this device for friends-only boards.
+fn board_client_filter(
+    friend_scope: &FriendScope,
+    owner_key: Option<&str>,
+    member_uuids: Option<&[String]>,
+) -> Option<String> {
+    let mut client_uuids = match member_uuids {
+        Some(members) => members.to_vec(),
+        None if friend_scope.only => {
+            let mut client_uuids = friend_scope
+                .client_uuids
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>();
+            client_uuids.extend(owner_key.map(str::to_string));
+            client_uuids
+        }
+        None => return None,
+    };
+    client_uuids.sort();
+    client_uuids.dedup();
+    Some(format!(
+        "in.({})",
+        client_uuids
+            .iter()
+            .map(|value| quote_postgrest_text(value))
+            .collect::<Vec<_>>()
+            .join(",")
+    ))
+}
+
+pub(crate) fn quote_postgrest_text(raw: &str) -> String {
     let escaped = raw.replace('\\', "\\\\").replace('"', "\\\"");
     format!("\"{escaped}\"")
 }
 
-async fn fetch_remote_daily_attempts(
+async fn fetch_remote_challenge_attempts(
     config: &SupabaseConfig,
+    period: ChallengePeriod,
     challenge_key: &str,
     owner_key: &str,
 ) -> Result<RemoteDailyAttemptStatus, String> {
+    let mode = period.mode();
     let endpoint = format!("{}/rest/v1/scores", config.url.trim_end_matches('/'));
-    let mode_filter = format!("eq.{DAILY_MODE}");
+    let mode_filter = format!("eq.{mode}");
     let challenge_filter = format!("eq.{challenge_key}");
     let uuid_filter = format!("eq.{owner_key}");
-    let client = create_http_client()?;
+    let client = &config.client;
     let response = client
         .get(endpoint)
         .query(&[
@@ -1012,13 +2087,14 @@ async fn fetch_remote_daily_attempts(
         .header("Authorization", format!("Bearer {}", config.anon_key))
         .send()
         .await
-        .map_err(|error| format!("supabase daily status fetch failed: {error}"))?;
+        .map_err(|error| format!("supabase {mode} status fetch failed: {error}"))?;
+    observe_server_date(&response);
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
         return Err(format!(
-            "supabase daily status fetch failed with {status}: {body}"
+            "supabase {mode} status fetch failed with {status}: {body}"
         ));
     }
 
@@ -1026,15 +2102,15 @@ async fn fetch_remote_daily_attempts(
         .json::<Vec<DailyAttemptsRow>>()
         .await
         .map_err(|error| format!("failed to decode supabase response: {error}"))?;
-    let attempts = rows
-        .into_iter()
-        .next()
-        .unwrap_or(DailyAttemptsRow {
-            attempts_used: Some(0),
-            active_attempt_token: None,
-        });
+    let attempts = rows.into_iter().next().unwrap_or(DailyAttemptsRow {
+        attempts_used: Some(0),
+        active_attempt_token: None,
+    });
     Ok(RemoteDailyAttemptStatus {
-        attempts_used: attempts.attempts_used.unwrap_or(0).clamp(0, DAILY_MAX_ATTEMPTS),
+        attempts_used: attempts
+            .attempts_used
+            .unwrap_or(0)
+            .clamp(0, MAX_CHALLENGE_ATTEMPTS),
         has_active_attempt: attempts
             .active_attempt_token
             .as_deref()
@@ -1043,6 +2119,175 @@ async fn fetch_remote_daily_attempts(
     })
 }
 
+/// Attempt limit for one challenge: the `challenge_settings` override when the
+/// server has one, else the period default. Lookup failures fall back too.
+async fn resolve_challenge_max_attempts(
+    config: &SupabaseConfig,
+    period: ChallengePeriod,
+    challenge_key: &str,
+) -> i64 {
+    match fetch_remote_challenge_max_attempts(config, period, challenge_key).await {
+        Ok(Some(value)) => value,
+        Ok(None) => period.default_max_attempts(),
+        Err(error) => {
+            eprintln!("Failed to load challenge settings. Using default attempt limit. {error}");
+            period.default_max_attempts()
+        }
+    }
+}
+
+async fn fetch_remote_challenge_max_attempts(
+    config: &SupabaseConfig,
+    period: ChallengePeriod,
+    challenge_key: &str,
+) -> Result<Option<i64>, String> {
+    let mode = period.mode();
+    let endpoint = format!(
+        "{}/rest/v1/{}",
+        config.url.trim_end_matches('/'),
+        CHALLENGE_SETTINGS_TABLE_NAME
+    );
+    let mode_filter = format!("eq.{mode}");
+    let challenge_filter = format!("eq.{challenge_key}");
+    let client = &config.client;
+    let response = client
+        .get(endpoint)
+        .query(&[
+            ("select", "max_attempts"),
+            ("mode", mode_filter.as_str()),
+            ("challenge_key", challenge_filter.as_str()),
+            ("limit", "1"),
+        ])
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .send()
+        .await
+        .map_err(|error| format!("supabase {mode} settings fetch failed: {error}"))?;
+    observe_server_date(&response);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "supabase {mode} settings fetch failed with {status}: {body}"
+        ));
+    }
+
+    let rows = response
+        .json::<Vec<ChallengeSettingsRow>>()
+        .await
+        .map_err(|error| format!("failed to decode challenge settings response: {error}"))?;
+    match rows.into_iter().next() {
+        Some(row) => period.validate_max_attempts(row.max_attempts).map(Some),
+        None => Ok(None),
+    }
+}
+
+async fn fetch_remote_daily_history_rows(
+    config: &SupabaseConfig,
+    challenge_keys: Option<&[String]>,
+    owner_key: &str,
+) -> Result<Vec<DailyHistoryRow>, String> {
+    let endpoint = format!("{}/rest/v1/scores", config.url.trim_end_matches('/'));
+    let mode_filter = format!("eq.{DAILY_MODE}");
+    let uuid_filter = format!("eq.{owner_key}");
+    let limit = challenge_keys
+        .map(|keys| keys.len())
+        .unwrap_or(DAILY_SUBMISSION_HISTORY_LIMIT)
+        .to_string();
+    let mut query = vec![
+        (
+            "select",
+            "challenge_key,score,level,attempts_used,daily_has_submission".to_string(),
+        ),
+        ("mode", mode_filter),
+        ("client_uuid", uuid_filter),
+        ("order", "challenge_key.asc".to_string()),
+        ("limit", limit),
+    ];
+    if let Some(keys) = challenge_keys {
+        let challenge_filter = format!(
+            "in.({})",
+            keys.iter()
+                .map(|value| quote_postgrest_text(value))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        query.push(("challenge_key", challenge_filter));
+    }
+
+    let client = &config.client;
+    let response = client
+        .get(endpoint)
+        .query(&query)
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .send()
+        .await
+        .map_err(|error| format!("supabase daily history fetch failed: {error}"))?;
+    observe_server_date(&response);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "supabase daily history fetch failed with {status}: {body}"
+        ));
+    }
+
+    response
+        .json::<Vec<DailyHistoryRow>>()
+        .await
+        .map_err(|error| format!("failed to decode daily history response: {error}"))
+}
+
+/// Ranks of every submitted day in `challenge_keys` on its daily board, in one
+/// `daily_history_ranks` call instead of one count query per day.
+async fn fetch_remote_daily_ranks(
+    config: &SupabaseConfig,
+    owner_key: &str,
+    challenge_keys: &[String],
+) -> Result<HashMap<String, i64>, String> {
+    let endpoint = format!(
+        "{}/rest/v1/rpc/{}",
+        config.url.trim_end_matches('/'),
+        DAILY_HISTORY_RANKS_RPC_NAME
+    );
+    let payload = DailyHistoryRanksPayload {
+        p_client_uuid: owner_key,
+        p_challenge_keys: challenge_keys,
+    };
+
+    let client = &config.client;
+    let response = client
+        .post(endpoint)
+        .header("apikey", &config.anon_key)
+        .header("Authorization", format!("Bearer {}", config.anon_key))
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|error| format!("supabase daily rank fetch failed: {error}"))?;
+    observe_server_date(&response);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "supabase daily rank fetch failed with {status}: {body}\n\
+Ensure /supabase/schema.sql has been applied (including RPC {DAILY_HISTORY_RANKS_RPC_NAME})."
+        ));
+    }
+
+    let rows = response
+        .json::<Vec<DailyHistoryRankRow>>()
+        .await
+        .map_err(|error| format!("failed to decode daily rank response: {error}"))?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.challenge_key, row.rank))
+        .collect())
+}
+
 async fn fetch_remote_daily_streak_state(
     config: &SupabaseConfig,
     owner_key: &str,
@@ -1052,11 +2297,14 @@ async fn fetch_remote_daily_streak_state(
         config.url.trim_end_matches('/')
     );
     let uuid_filter = format!("eq.{owner_key}");
-    let client = create_http_client()?;
+    let client = &config.client;
     let response = client
         .get(endpoint)
         .query(&[
-            ("select", "current_streak,max_streak,last_submission_key"),
+            (
+                "select",
+                "current_streak,max_streak,last_submission_key,freeze_tokens",
+            ),
             ("client_uuid", uuid_filter.as_str()),
             ("limit", "1"),
         ])
@@ -1065,6 +2313,7 @@ async fn fetch_remote_daily_streak_state(
         .send()
         .await
         .map_err(|error| format!("supabase daily badge fetch failed: {error}"))?;
+    observe_server_date(&response);
 
     if !response.status().is_success() {
         let status = response.status();
@@ -1081,45 +2330,160 @@ async fn fetch_remote_daily_streak_state(
     Ok(rows.into_iter().next())
 }
 
-fn is_current_streak_alive(last_submission_key: Option<&str>, challenge_key: &str) -> bool {
+fn build_streak_calendar(rows: &[DailyHistoryRow], from_day: i64, to_day: i64) -> StreakCalendar {
+    let rows_by_day = rows
+        .iter()
+        .filter_map(|row| challenge_key_to_day_number(&row.challenge_key).map(|day| (day, row)))
+        .collect::<HashMap<_, _>>();
+    let best_score = rows
+        .iter()
+        .filter(|row| row.daily_has_submission.unwrap_or(false))
+        .map(|row| row.score.max(0))
+        .max()
+        .unwrap_or(0);
+
+    let days = (from_day..=to_day)
+        .map(|day| {
+            let row = rows_by_day.get(&day);
+            let submitted = row
+                .map(|value| value.daily_has_submission.unwrap_or(false))
+                .unwrap_or(false);
+            StreakCalendarDay {
+                challenge_key: day_number_to_challenge_key(day),
+                submitted,
+                attempts_used: row
+                    .and_then(|value| value.attempts_used)
+                    .unwrap_or(0)
+                    .clamp(0, MAX_CHALLENGE_ATTEMPTS),
+                score_tier: match row {
+                    Some(value) if submitted => score_tier(value.score, best_score),
+                    _ => 0,
+                },
+            }
+        })
+        .collect();
+
+    let mut submitted_days = rows_by_day
+        .iter()
+        .filter(|(_, row)| row.daily_has_submission.unwrap_or(false))
+        .map(|(day, _)| *day)
+        .collect::<Vec<_>>();
+    submitted_days.sort_unstable();
+
+    StreakCalendar {
+        days,
+        runs: collect_streak_runs(&submitted_days),
+    }
+}
+
+/// Buckets a score into `1..=STREAK_CALENDAR_MAX_TIER` relative to the
+/// device's best daily score, like contribution-graph intensity levels.
+fn score_tier(score: i64, best_score: i64) -> i64 {
+    if best_score <= 0 {
+        return 1;
+    }
+    let scaled = (score.max(0) * STREAK_CALENDAR_MAX_TIER + best_score - 1) / best_score;
+    scaled.clamp(1, STREAK_CALENDAR_MAX_TIER)
+}
+
+fn collect_streak_runs(sorted_days: &[i64]) -> Vec<StreakRun> {
+    let mut runs: Vec<StreakRun> = Vec::new();
+    let mut run_start: Option<(i64, i64)> = None;
+    for day in sorted_days.iter().copied() {
+        run_start = match run_start {
+            Some((start, end)) if day == end + 1 => Some((start, day)),
+            Some((_, end)) if day == end => run_start,
+            Some((start, end)) => {
+                runs.push(streak_run(start, end));
+                Some((day, day))
+            }
+            None => Some((day, day)),
+        };
+    }
+    if let Some((start, end)) = run_start {
+        runs.push(streak_run(start, end));
+    }
+    runs
+}
+
+fn streak_run(start_day: i64, end_day: i64) -> StreakRun {
+    StreakRun {
+        start_key: day_number_to_challenge_key(start_day),
+        end_key: day_number_to_challenge_key(end_day),
+        length: end_day - start_day + 1,
+    }
+}
+
+/// A streak survives one missed UTC day while a freeze token is banked; the
+/// server spends the token when the next submission lands.
+pub(crate) fn is_current_streak_alive(
+    last_submission_key: Option<&str>,
+    challenge_key: &str,
+    freeze_tokens: i64,
+) -> bool {
     let Some(last_key) = last_submission_key else {
         return false;
     };
-    last_key == challenge_key || is_next_challenge_day(last_key, challenge_key)
+    last_key == challenge_key
+        || is_next_challenge_day(last_key, challenge_key)
+        || (freeze_tokens > 0 && is_freezable_challenge_gap(Some(last_key), challenge_key))
 }
 
-fn badge_status_from_streaks(current_streak: i64, max_streak: i64) -> DailyBadgeStatus {
-    let highest_badge_power = resolve_badge_power(max_streak);
-    let highest_badge_days = highest_badge_power.map(|power| 2_i64.pow(power as u32));
-    let next_badge_power = match highest_badge_power {
-        None => Some(0),
-        Some(power) if power >= DAILY_BADGE_MAX_POWER => None,
-        Some(power) => Some(power + 1),
+fn is_freezable_challenge_gap(last_submission_key: Option<&str>, challenge_key: &str) -> bool {
+    let previous_day = last_submission_key.and_then(challenge_key_to_day_number);
+    let next_day = challenge_key_to_day_number(challenge_key);
+    matches!((previous_day, next_day), (Some(left), Some(right)) if right - left == 2)
+}
+
+fn badge_status_from_streak_state(
+    state: Option<&DailyStreakStateRow>,
+    challenge_key: &str,
+) -> DailyBadgeStatus {
+    let Some(value) = state else {
+        return badge_status_from_streaks(0, 0);
     };
-    let next_badge_days = next_badge_power.map(|power| 2_i64.pow(power as u32));
-    let days_to_next_badge = next_badge_days.map(|days| (days - current_streak).max(0));
+    let stored_current = value.current_streak.unwrap_or(0).max(0);
+    let stored_max = value.max_streak.unwrap_or(0).max(stored_current).max(0);
+    let freeze_tokens = value
+        .freeze_tokens
+        .unwrap_or(0)
+        .clamp(0, DAILY_STREAK_MAX_FREEZE_TOKENS);
+    let last_key = value.last_submission_key.as_deref();
+    let alive = is_current_streak_alive(last_key, challenge_key, freeze_tokens);
+    let effective_current = if alive { stored_current } else { 0 };
+    let mut status = badge_status_from_streaks(effective_current, stored_max);
+    status.freeze_tokens = freeze_tokens;
+    status.freeze_pending = alive && is_freezable_challenge_gap(last_key, challenge_key);
+    status.projected_streak_alive = alive;
+    status
+}
+
+fn badge_status_from_streaks(current_streak: i64, max_streak: i64) -> DailyBadgeStatus {
+    let tiers = badge_tiers();
+    let highest = tiers.highest_for(max_streak);
+    let next = tiers.next_after(highest);
+    let days_to_next_badge = next.map(|tier| (tier.days - current_streak).max(0));
 
     DailyBadgeStatus {
         current_streak,
         max_streak,
-        highest_badge_power,
-        highest_badge_days,
-        next_badge_power,
-        next_badge_days,
+        highest_badge_power: highest.map(|tier| tier.power),
+        highest_badge_days: highest.map(|tier| tier.days),
+        highest_badge_name: highest.map(|tier| tier.name.clone()),
+        highest_badge_icon_id: highest.map(|tier| tier.icon_id.clone()),
+        next_badge_power: next.map(|tier| tier.power),
+        next_badge_days: next.map(|tier| tier.days),
+        next_badge_name: next.map(|tier| tier.name.clone()),
         days_to_next_badge,
+        freeze_tokens: 0,
+        freeze_pending: false,
+        projected_streak_alive: false,
+        offline: false,
     }
 }
 
 fn resolve_badge_power(streak: i64) -> Option<i64> {
-    if streak < 1 {
-        return None;
-    }
-
-    let mut power = 0_i64;
-    while power < DAILY_BADGE_MAX_POWER && 2_i64.pow((power + 1) as u32) <= streak {
-        power += 1;
-    }
-    Some(power)
+    badge_tiers().highest_for(streak).map(|tier| tier.power)
 }
 
 fn is_next_challenge_day(previous: &str, next: &str) -> bool {
@@ -1128,71 +2492,14 @@ fn is_next_challenge_day(previous: &str, next: &str) -> bool {
     matches!((previous_day, next_day), (Some(left), Some(right)) if right - left == 1)
 }
 
-fn challenge_key_to_day_number(challenge_key: &str) -> Option<i64> {
-    if challenge_key.len() != 10 {
-        return None;
-    }
-    let bytes = challenge_key.as_bytes();
-    if bytes[4] != b'-' || bytes[7] != b'-' {
-        return None;
-    }
-
-    let year = parse_i32_digits(&challenge_key[0..4])?;
-    let month = parse_i32_digits(&challenge_key[5..7])?;
-    let day = parse_i32_digits(&challenge_key[8..10])?;
-    if month < 1 || month > 12 {
-        return None;
-    }
-
-    let max_day = days_in_month(year, month as u32);
-    if day < 1 || day as u32 > max_day {
-        return None;
-    }
-
-    Some(days_from_civil(year, month as u32, day as u32))
-}
-
-fn parse_i32_digits(raw: &str) -> Option<i32> {
-    if raw.is_empty() || !raw.bytes().all(|ch| ch.is_ascii_digit()) {
-        return None;
-    }
-    raw.parse::<i32>().ok()
-}
-
-fn is_leap_year(year: i32) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
-}
-
-fn days_in_month(year: i32, month: u32) -> u32 {
-    match month {
-        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
-        4 | 6 | 9 | 11 => 30,
-        2 => {
-            if is_leap_year(year) {
-                29
-            } else {
-                28
-            }
-        }
-        _ => 0,
-    }
-}
-
-fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
-    let year = year - if month <= 2 { 1 } else { 0 };
-    let era = if year >= 0 { year } else { year - 399 } / 400;
-    let yoe = year - era * 400;
-    let month_index = month as i32 + if month > 2 { -3 } else { 9 };
-    let doy = (153 * month_index + 2) / 5 + day as i32 - 1;
-    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
-    (era * 146_097 + doe - 719_468) as i64
-}
-
-async fn start_remote_daily_attempt(
+async fn start_remote_challenge_attempt(
     config: &SupabaseConfig,
+    period: ChallengePeriod,
     challenge_key: &str,
     owner_key: &str,
+    player_name: Option<&str>,
 ) -> Result<DailyAttemptStartResult, String> {
+    let mode = period.mode();
     let endpoint = format!(
         "{}/rest/v1/rpc/{}",
         config.url.trim_end_matches('/'),
@@ -1201,10 +2508,11 @@ async fn start_remote_daily_attempt(
     let payload = DailyStartPayload {
         p_client_uuid: owner_key,
         p_challenge_key: challenge_key,
-        p_player_name: "Pending",
+        p_player_name: player_name.unwrap_or("Pending"),
+        p_mode: mode,
     };
 
-    let client = create_http_client()?;
+    let client = &config.client;
     let response = client
         .post(endpoint)
         .header("apikey", &config.anon_key)
@@ -1212,7 +2520,8 @@ async fn start_remote_daily_attempt(
         .json(&payload)
         .send()
         .await
-        .map_err(|error| format!("supabase daily start failed: {error}"))?;
+        .map_err(|error| format!("supabase {mode} start failed: {error}"))?;
+    observe_server_date(&response);
 
     if !response.status().is_success() {
         let status = response.status();
@@ -1223,7 +2532,7 @@ async fn start_remote_daily_attempt(
             ""
         };
         return Err(format!(
-            "supabase daily start failed with {status}: {body}{overload_hint}\n\
+            "supabase {mode} start failed with {status}: {body}{overload_hint}\n\
 Ensure /supabase/schema.sql has been applied (including RPC {DAILY_START_RPC_NAME})."
         ));
     }
@@ -1231,7 +2540,7 @@ Ensure /supabase/schema.sql has been applied (including RPC {DAILY_START_RPC_NAM
     let result = response
         .json::<DailyAttemptStartResult>()
         .await
-        .map_err(|error| format!("failed to decode daily start response: {error}"))?;
+        .map_err(|error| format!("failed to decode {mode} start response: {error}"))?;
 
     let token = result
         .attempt_token
@@ -1240,15 +2549,16 @@ Ensure /supabase/schema.sql has been applied (including RPC {DAILY_START_RPC_NAM
         .filter(|value| !value.is_empty())
         .map(str::to_string);
 
-    let attempts_left = result.attempts_left.clamp(0, DAILY_MAX_ATTEMPTS);
+    let max_attempts = period.validate_max_attempts(result.max_attempts)?;
+    let attempts_left = result.attempts_left.clamp(0, max_attempts);
     Ok(DailyAttemptStartResult {
         accepted: result.accepted,
         resumed: result.resumed,
         attempt_token: token,
         challenge_key: challenge_key.to_string(),
-        attempts_used: result.attempts_used.clamp(0, DAILY_MAX_ATTEMPTS),
+        attempts_used: result.attempts_used.clamp(0, max_attempts),
         attempts_left,
-        max_attempts: DAILY_MAX_ATTEMPTS,
+        max_attempts,
         can_submit: attempts_left > 0,
         has_active_attempt: result.has_active_attempt,
     })
@@ -1259,7 +2569,8 @@ async fn submit_remote_global_score(
     entry: &ScoreEntry,
     replay_proof: &DailyReplayProof,
     owner_key: &str,
-) -> Result<(), String> {
+    device_secret: &str,
+) -> Result<GlobalSubmitResult, RemoteSubmitError> {
     let endpoint = format!(
         "{}/functions/v1/{}",
         config.url.trim_end_matches('/'),
@@ -1270,11 +2581,12 @@ async fn submit_remote_global_score(
         challenge_key: CLASSIC_CHALLENGE_KEY,
         attempt_token: None,
         client_uuid: owner_key,
+        device_secret,
         entry,
         replay_proof,
     };
 
-    let client = create_http_client()?;
+    let client = &config.client;
     let response = client
         .post(endpoint)
         .header("apikey", &config.anon_key)
@@ -1283,47 +2595,70 @@ async fn submit_remote_global_score(
         .send()
         .await
         .map_err(|error| format!("supabase global replay verify failed: {error}"))?;
+    observe_server_date(&response);
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        let summary = summarize_daily_verify_error_body(&body);
-        let hint = daily_verify_error_hint(&summary);
-        let hint_suffix = hint
+        let verify_error = VerifyErrorResponse::parse(&body);
+        let summary = verify_error.summary();
+        let hint_suffix = verify_error
+            .hint()
             .map(|value| format!("\nHint: {value}"))
             .unwrap_or_default();
-        return Err(format!(
+        let message = format!(
             "supabase global replay verify failed with {status}: {summary}{hint_suffix}\n\
 Ensure /supabase/schema.sql and /supabase/functions/verify-score are deployed."
+        );
+        return Err(RemoteSubmitError::from_response(
+            status,
+            &verify_error,
+            body,
+            summary,
+            message,
         ));
     }
 
-    Ok(())
+    let result = response
+        .json::<GlobalSubmitResult>()
+        .await
+        .map_err(|error| format!("failed to decode global verify response: {error}"))?;
+    Ok(GlobalSubmitResult {
+        rank: result.rank.filter(|rank| *rank > 0),
+        total_players: result.total_players.filter(|total| *total > 0),
+        local_only: false,
+        ..result
+    })
 }
 
-async fn submit_remote_daily_score(
+#[allow(clippy::too_many_arguments)]
+async fn submit_remote_challenge_score(
     config: &SupabaseConfig,
+    period: ChallengePeriod,
     challenge_key: &str,
     attempt_token: &str,
     entry: &ScoreEntry,
     replay_proof: &DailyReplayProof,
     owner_key: &str,
-) -> Result<DailySubmitResult, String> {
+    device_secret: &str,
+) -> Result<DailySubmitResult, RemoteSubmitError> {
+    let mode = period.mode();
     let endpoint = format!(
         "{}/functions/v1/{}",
         config.url.trim_end_matches('/'),
         VERIFY_SCORE_FUNCTION_NAME
     );
     let payload = VerifyScorePayload {
-        mode: DAILY_MODE,
+        mode,
         challenge_key,
         attempt_token: Some(attempt_token),
         client_uuid: owner_key,
+        device_secret,
         entry,
         replay_proof,
     };
 
-    let client = create_http_client()?;
+    let client = &config.client;
     let response = client
         .post(endpoint)
         .header("apikey", &config.anon_key)
@@ -1331,108 +2666,156 @@ async fn submit_remote_daily_score(
         .json(&payload)
         .send()
         .await
-        .map_err(|error| format!("supabase daily replay verify failed: {error}"))?;
+        .map_err(|error| format!("supabase {mode} replay verify failed: {error}"))?;
+    observe_server_date(&response);
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        let summary = summarize_daily_verify_error_body(&body);
-        let hint = daily_verify_error_hint(&summary);
-        let hint_suffix = hint
+        let verify_error = VerifyErrorResponse::parse(&body);
+        let summary = verify_error.summary();
+        let hint_suffix = verify_error
+            .hint()
             .map(|value| format!("\nHint: {value}"))
             .unwrap_or_default();
-        return Err(format!(
-            "supabase daily replay verify failed with {status}: {summary}{hint_suffix}\n\
+        let message = format!(
+            "supabase {mode} replay verify failed with {status}: {summary}{hint_suffix}\n\
 Ensure /supabase/schema.sql and /supabase/functions/verify-score are deployed."
+        );
+        return Err(RemoteSubmitError::from_response(
+            status,
+            &verify_error,
+            body,
+            summary,
+            message,
         ));
     }
 
     let result = response
         .json::<DailySubmitResult>()
         .await
-        .map_err(|error| format!("failed to decode daily verify response: {error}"))?;
+        .map_err(|error| format!("failed to decode {mode} verify response: {error}"))?;
+    let max_attempts = period.validate_max_attempts(result.max_attempts)?;
+    let attempts_left = result.attempts_left.clamp(0, max_attempts);
     Ok(DailySubmitResult {
         accepted: result.accepted,
         improved: result.improved,
         challenge_key: challenge_key.to_string(),
-        attempts_used: result.attempts_used.clamp(0, DAILY_MAX_ATTEMPTS),
-        attempts_left: result.attempts_left.clamp(0, DAILY_MAX_ATTEMPTS),
-        max_attempts: DAILY_MAX_ATTEMPTS,
-        can_submit: result.attempts_left.clamp(0, DAILY_MAX_ATTEMPTS) > 0,
+        attempts_used: result.attempts_used.clamp(0, max_attempts),
+        attempts_left,
+        max_attempts,
+        can_submit: attempts_left > 0,
         has_active_attempt: result.has_active_attempt,
+        local_only: false,
+        reason: None,
+        retry_after: None,
     })
 }
 
-fn summarize_daily_verify_error_body(body: &str) -> String {
-    let trimmed = body.trim();
-    if trimmed.is_empty() {
-        return "(empty response body)".to_string();
-    }
-
-    let parsed = match serde_json::from_str::<serde_json::Value>(trimmed) {
-        Ok(value) => value,
-        Err(_) => return trimmed.to_string(),
-    };
-
-    let mut parts: Vec<String> = Vec::new();
-    if let Some(value) = parsed.get("error").and_then(|value| value.as_str()) {
-        if !value.trim().is_empty() {
-            parts.push(value.trim().to_string());
-        }
-    }
-    if let Some(value) = parsed.get("reason").and_then(|value| value.as_str()) {
-        if !value.trim().is_empty() {
-            parts.push(format!("reason={}", value.trim()));
+/// Why a replay submission failed: the server never answered, or it answered
+/// with a verdict on the proof itself.
+#[derive(Debug)]
+enum RemoteSubmitError {
+    Transport(String),
+    /// `body` is the raw response, archived with the submission; `reason` is
+    /// its summary, kept on the cached entry.
+    Rejected {
+        status: u16,
+        body: String,
+        reason: String,
+        message: String,
+    },
+}
+
+impl RemoteSubmitError {
+    /// Only a 4xx answer carrying a verify-score verdict (a replay mismatch or
+    /// an `INVALID_*` code) judges the proof. Auth, routing, rate-limit and
+    /// server errors such as 401, 403 or 404 stay retryable.
+    fn from_response(
+        status: reqwest::StatusCode,
+        verify_error: &VerifyErrorResponse,
+        body: String,
+        reason: String,
+        message: String,
+    ) -> Self {
+        if !status.is_client_error() || !verify_error.rejects_submission() {
+            RemoteSubmitError::Transport(message)
+        } else {
+            RemoteSubmitError::Rejected {
+                status: status.as_u16(),
+                body,
+                reason,
+                message,
+            }
         }
     }
-    if let Some(value) = parsed.get("detail").and_then(|value| value.as_str()) {
-        if !value.trim().is_empty() {
-            parts.push(format!("detail={}", value.trim()));
-        }
+}
+
+impl From<String> for RemoteSubmitError {
+    fn from(message: String) -> Self {
+        RemoteSubmitError::Transport(message)
     }
-    if let Some(value) = parsed.get("code").and_then(|value| value.as_str()) {
-        if !value.trim().is_empty() {
-            parts.push(format!("code={}", value.trim()));
+}
+
+impl From<RemoteSubmitError> for String {
+    fn from(error: RemoteSubmitError) -> Self {
+        match error {
+            RemoteSubmitError::Transport(message) | RemoteSubmitError::Rejected { message, .. } => {
+                message
+            }
         }
     }
+}
 
-    if parts.is_empty() {
-        trimmed.to_string()
-    } else {
-        parts.join(", ")
+impl std::fmt::Display for RemoteSubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoteSubmitError::Transport(message) | RemoteSubmitError::Rejected { message, .. } => {
+                f.write_str(message)
+            }
+        }
     }
 }
 
-fn daily_verify_error_hint(summary: &str) -> Option<&'static str> {
-    if summary.contains("REPLAY_VERIFICATION_FAILED") {
-        return Some("Replay proof mismatch. Check function logs for reason/expected/actual.");
-    }
-    if summary.contains("CHALLENGE_KEY_MISMATCH") {
-        return Some("The UTC day changed during the run. Start a new Daily Challenge attempt.");
-    }
-    if summary.contains("INVALID_ATTEMPT_TOKEN") {
-        return Some("The attempt token is stale. Start a new Daily Challenge attempt.");
-    }
-    if summary.contains("MISSING_SUPABASE_ENV") {
-        return Some("Set SUPABASE_SERVICE_ROLE_KEY in Edge Function secrets.");
-    }
-    if summary.contains("RPC_SUBMIT_GLOBAL_SCORE_FAILED") {
-        return Some("Check submit_global_score RPC and service_role execute grant in schema.");
-    }
-    if summary.contains("permission denied") {
-        return Some(
-            "Grant execute on submit_daily_score/submit_global_score to service_role and redeploy schema.",
-        );
+/// Remembers the server's verdict on a proof and archives rejections;
+/// transport failures stay retryable.
+fn record_submit_outcome<R: Runtime, T>(
+    app: &AppHandle<R>,
+    ticket: &submission_guard::SubmissionTicket,
+    mode: &str,
+    challenge_key: &str,
+    entry: &ScoreEntry,
+    replay_proof: &DailyReplayProof,
+    result: &Result<T, RemoteSubmitError>,
+) {
+    match result {
+        Ok(_) => submission_guard::record_outcome(app, ticket, true),
+        Err(RemoteSubmitError::Rejected { status, body, .. }) => {
+            submission_guard::record_outcome(app, ticket, false);
+            rejects::archive_rejection(
+                app,
+                Rejection {
+                    mode,
+                    challenge_key,
+                    status: *status,
+                    body,
+                    entry,
+                    replay_proof,
+                },
+            );
+        }
+        Err(RemoteSubmitError::Transport(_)) => {}
     }
-    None
 }
 
-async fn forfeit_remote_daily_attempt(
+async fn forfeit_remote_challenge_attempt(
     config: &SupabaseConfig,
+    period: ChallengePeriod,
     challenge_key: &str,
     attempt_token: &str,
     owner_key: &str,
 ) -> Result<DailyForfeitResult, String> {
+    let mode = period.mode();
     let endpoint = format!(
         "{}/rest/v1/rpc/{}",
         config.url.trim_end_matches('/'),
@@ -1442,9 +2825,10 @@ async fn forfeit_remote_daily_attempt(
         p_client_uuid: owner_key,
         p_challenge_key: challenge_key,
         p_attempt_token: attempt_token,
+        p_mode: mode,
     };
 
-    let client = create_http_client()?;
+    let client = &config.client;
     let response = client
         .post(endpoint)
         .header("apikey", &config.anon_key)
@@ -1452,7 +2836,8 @@ async fn forfeit_remote_daily_attempt(
         .json(&payload)
         .send()
         .await
-        .map_err(|error| format!("supabase daily forfeit failed: {error}"))?;
+        .map_err(|error| format!("supabase {mode} forfeit failed: {error}"))?;
+    observe_server_date(&response);
 
     if !response.status().is_success() {
         let status = response.status();
@@ -1463,7 +2848,7 @@ async fn forfeit_remote_daily_attempt(
             ""
         };
         return Err(format!(
-            "supabase daily forfeit failed with {status}: {body}{overload_hint}\n\
+            "supabase {mode} forfeit failed with {status}: {body}{overload_hint}\n\
 Ensure /supabase/schema.sql has been applied (including RPC {DAILY_FORFEIT_RPC_NAME})."
         ));
     }
@@ -1471,26 +2856,29 @@ Ensure /supabase/schema.sql has been applied (including RPC {DAILY_FORFEIT_RPC_N
     let result = response
         .json::<DailyForfeitResult>()
         .await
-        .map_err(|error| format!("failed to decode daily forfeit response: {error}"))?;
+        .map_err(|error| format!("failed to decode {mode} forfeit response: {error}"))?;
 
-    let attempts_left = result.attempts_left.clamp(0, DAILY_MAX_ATTEMPTS);
+    let max_attempts = period.validate_max_attempts(result.max_attempts)?;
+    let attempts_left = result.attempts_left.clamp(0, max_attempts);
     Ok(DailyForfeitResult {
         accepted: result.accepted,
         challenge_key: challenge_key.to_string(),
-        attempts_used: result.attempts_used.clamp(0, DAILY_MAX_ATTEMPTS),
+        attempts_used: result.attempts_used.clamp(0, max_attempts),
         attempts_left,
-        max_attempts: DAILY_MAX_ATTEMPTS,
+        max_attempts,
         can_submit: attempts_left > 0,
         has_active_attempt: result.has_active_attempt,
     })
 }
 
-async fn rollback_remote_daily_attempt(
+async fn rollback_remote_challenge_attempt(
     config: &SupabaseConfig,
+    period: ChallengePeriod,
     challenge_key: &str,
     attempt_token: &str,
     owner_key: &str,
 ) -> Result<DailyForfeitResult, String> {
+    let mode = period.mode();
     let endpoint = format!(
         "{}/rest/v1/rpc/{}",
         config.url.trim_end_matches('/'),
@@ -1500,9 +2888,10 @@ async fn rollback_remote_daily_attempt(
         p_client_uuid: owner_key,
         p_challenge_key: challenge_key,
         p_attempt_token: attempt_token,
+        p_mode: mode,
     };
 
-    let client = create_http_client()?;
+    let client = &config.client;
     let response = client
         .post(endpoint)
         .header("apikey", &config.anon_key)
@@ -1510,7 +2899,8 @@ async fn rollback_remote_daily_attempt(
         .json(&payload)
         .send()
         .await
-        .map_err(|error| format!("supabase daily rollback failed: {error}"))?;
+        .map_err(|error| format!("supabase {mode} rollback failed: {error}"))?;
+    observe_server_date(&response);
 
     if !response.status().is_success() {
         let status = response.status();
@@ -1521,7 +2911,7 @@ async fn rollback_remote_daily_attempt(
             ""
         };
         return Err(format!(
-            "supabase daily rollback failed with {status}: {body}{overload_hint}\n\
+            "supabase {mode} rollback failed with {status}: {body}{overload_hint}\n\
 Ensure /supabase/schema.sql has been applied (including RPC {DAILY_ROLLBACK_RPC_NAME})."
         ));
     }
@@ -1529,16 +2919,63 @@ Ensure /supabase/schema.sql has been applied (including RPC {DAILY_ROLLBACK_RPC_
     let result = response
         .json::<DailyForfeitResult>()
         .await
-        .map_err(|error| format!("failed to decode daily rollback response: {error}"))?;
+        .map_err(|error| format!("failed to decode {mode} rollback response: {error}"))?;
 
-    let attempts_left = result.attempts_left.clamp(0, DAILY_MAX_ATTEMPTS);
+    let max_attempts = period.validate_max_attempts(result.max_attempts)?;
+    let attempts_left = result.attempts_left.clamp(0, max_attempts);
     Ok(DailyForfeitResult {
         accepted: result.accepted,
         challenge_key: challenge_key.to_string(),
-        attempts_used: result.attempts_used.clamp(0, DAILY_MAX_ATTEMPTS),
+        attempts_used: result.attempts_used.clamp(0, max_attempts),
         attempts_left,
-        max_attempts: DAILY_MAX_ATTEMPTS,
+        max_attempts,
         can_submit: attempts_left > 0,
         has_active_attempt: result.has_active_attempt,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_imf_fixdates() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784_111_777)
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 23:59:60 GMT"),
+            Some(1_709_251_200)
+        );
+    }
+
+    #[test]
+    fn rejects_bad_weekdays_months_and_dates() {
+        for raw in [
+            "Mon, 06 Nov 1994 08:49:37 GMT",
+            "Sun 06 Nov 1994 08:49:37 GMT",
+            "Sun, 06 Noe 1994 08:49:37 GMT",
+            "Sun, 06 nov 1994 08:49:37 GMT",
+            "Thu, 29 Feb 2023 12:00:00 GMT",
+            "Sun, 06 Nov 1994 24:00:00 GMT",
+            "Sun, 06 Nov 1994 08:49:37 UTC",
+        ] {
+            assert_eq!(parse_http_date(raw), None, "{raw}");
+        }
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for raw in [
+            "",
+            "yesterday",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+            "Sun, 06 Nov 1994 08:49 GMT",
+            "Sun, xx Nov 1994 08:49:37 GMT",
+        ] {
+            assert_eq!(parse_http_date(raw), None, "{raw:?}");
+        }
+    }
+}
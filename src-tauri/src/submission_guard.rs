@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+use torus_core::clock::current_utc_seconds;
+use torus_core::replay::{replay_fingerprint, DailyReplayProof};
+
+use crate::scoreboard::app_data_dir;
+
+const SUBMISSION_GUARD_FILE_NAME: &str = "submission-guard-v1.json";
+const SUBMISSION_WINDOW_SECONDS: i64 = 60;
+const MAX_SUBMISSIONS_PER_WINDOW: usize = 5;
+const MAX_REMEMBERED_PROOFS: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ProofOutcome {
+    Accepted,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SeenProof {
+    outcome: ProofOutcome,
+    #[serde(rename = "decidedAt")]
+    decided_at: i64,
+}
+
+/// Recent submission times per mode and the proofs the server already decided
+/// on, keyed by `<mode>:<fingerprint>`. The file lives in this device's app
+/// data dir, so limits are per device.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SubmissionGuardFile {
+    #[serde(default)]
+    windows: BTreeMap<String, Vec<i64>>,
+    #[serde(default)]
+    proofs: BTreeMap<String, SeenProof>,
+}
+
+/// A submission slot taken by `admit_submission`; report the server's verdict
+/// with `record_outcome`.
+pub(crate) struct SubmissionTicket {
+    proof_key: String,
+}
+
+/// Why `admit_submission` kept a run from the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Refusal {
+    /// The server already accepted or rejected this proof.
+    Duplicate,
+    /// The mode's window is full; a slot frees up after `retry_after` seconds.
+    RateLimited { retry_after: i64 },
+}
+
+impl Refusal {
+    /// Value of a submit result's `reason`.
+    pub(crate) fn code(self) -> &'static str {
+        match self {
+            Refusal::Duplicate => "duplicate",
+            Refusal::RateLimited { .. } => "rate_limited",
+        }
+    }
+
+    pub(crate) fn retry_after(self) -> Option<i64> {
+        match self {
+            Refusal::Duplicate => None,
+            Refusal::RateLimited { retry_after } => Some(retry_after),
+        }
+    }
+}
+
+pub(crate) enum Admission {
+    Admitted(SubmissionTicket),
+    Refused(Refusal),
+}
+
+/// Refuses a proof the server already accepted or rejected for `mode`, then
+/// takes a slot in the mode's sliding window. Errors only when the guard file
+/// cannot be read or written.
+pub(crate) fn admit_submission<R: Runtime>(
+    app: &AppHandle<R>,
+    mode: &str,
+    replay_proof: &DailyReplayProof,
+) -> Result<Admission, String> {
+    let proof_key = format!("{mode}:{}", replay_fingerprint(replay_proof));
+    let mut file = read_guard(app)?;
+    if file.proofs.contains_key(&proof_key) {
+        return Ok(Admission::Refused(Refusal::Duplicate));
+    }
+
+    let now = current_utc_seconds();
+    let window = file.windows.entry(mode.to_string()).or_default();
+    window.retain(|submitted_at| now - submitted_at < SUBMISSION_WINDOW_SECONDS);
+    if window.len() >= MAX_SUBMISSIONS_PER_WINDOW {
+        let oldest = window.iter().min().copied().unwrap_or(now);
+        let retry_after = (oldest + SUBMISSION_WINDOW_SECONDS - now).max(1);
+        return Ok(Admission::Refused(Refusal::RateLimited { retry_after }));
+    }
+    window.push(now);
+    write_guard(app, &file)?;
+    Ok(Admission::Admitted(SubmissionTicket { proof_key }))
+}
+
+/// Remembers the server's verdict so the same proof is never sent again.
+/// Transport failures are not recorded, so those proofs may be retried.
+pub(crate) fn record_outcome<R: Runtime>(
+    app: &AppHandle<R>,
+    ticket: &SubmissionTicket,
+    accepted: bool,
+) {
+    let result = read_guard(app).and_then(|mut file| {
+        file.proofs.insert(
+            ticket.proof_key.clone(),
+            SeenProof {
+                outcome: if accepted {
+                    ProofOutcome::Accepted
+                } else {
+                    ProofOutcome::Rejected
+                },
+                decided_at: current_utc_seconds(),
+            },
+        );
+        while file.proofs.len() > MAX_REMEMBERED_PROOFS {
+            let Some(oldest_key) = file
+                .proofs
+                .iter()
+                .min_by_key(|(_, seen)| seen.decided_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            file.proofs.remove(&oldest_key);
+        }
+        write_guard(app, &file)
+    });
+    if let Err(error) = result {
+        eprintln!("Failed to record submission outcome. {error}");
+    }
+}
+
+fn guard_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let mut dir = app_data_dir(app)?;
+    dir.push(SUBMISSION_GUARD_FILE_NAME);
+    Ok(dir)
+}
+
+fn read_guard<R: Runtime>(app: &AppHandle<R>) -> Result<SubmissionGuardFile, String> {
+    let path = guard_path(app)?;
+    if !path.exists() {
+        return Ok(SubmissionGuardFile::default());
+    }
+
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read submission guard: {error}"))?;
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn write_guard<R: Runtime>(app: &AppHandle<R>, file: &SubmissionGuardFile) -> Result<(), String> {
+    let path = guard_path(app)?;
+    let body = serde_json::to_string(file)
+        .map_err(|error| format!("failed to serialize submission guard: {error}"))?;
+    fs::write(path, body).map_err(|error| format!("failed to write submission guard: {error}"))
+}
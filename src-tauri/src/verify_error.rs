@@ -0,0 +1,492 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// PostgreSQL `insufficient_privilege`, reported when a grant is missing.
+const PG_INSUFFICIENT_PRIVILEGE: &str = "42501";
+
+/// The `error` codes `verify-score` answers with, plus the RPC exceptions it
+/// forwards in `detail`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum VerifyErrorCode {
+    MethodNotAllowed,
+    InvalidJson,
+    InvalidPayload,
+    InvalidMode,
+    InvalidClientUuid,
+    InvalidEntry,
+    InvalidPlayerName,
+    InvalidScore,
+    InvalidLevel,
+    InvalidCreatedAt,
+    InvalidReplayProof,
+    EntryReplayMismatch,
+    InvalidChallengeKey,
+    InvalidAttemptToken,
+    ChallengeKeyMismatch,
+    ReplayVerificationFailed,
+    MissingSupabaseEnv,
+    RpcSubmitDailyScoreFailed,
+    RpcSubmitGlobalScoreFailed,
+    Unknown(String),
+}
+
+impl VerifyErrorCode {
+    pub(crate) fn parse(raw: &str) -> Self {
+        match raw.trim() {
+            "METHOD_NOT_ALLOWED" => VerifyErrorCode::MethodNotAllowed,
+            "INVALID_JSON" => VerifyErrorCode::InvalidJson,
+            "INVALID_PAYLOAD" => VerifyErrorCode::InvalidPayload,
+            "INVALID_MODE" => VerifyErrorCode::InvalidMode,
+            "INVALID_CLIENT_UUID" => VerifyErrorCode::InvalidClientUuid,
+            "INVALID_ENTRY" => VerifyErrorCode::InvalidEntry,
+            "INVALID_PLAYER_NAME" => VerifyErrorCode::InvalidPlayerName,
+            "INVALID_SCORE" => VerifyErrorCode::InvalidScore,
+            "INVALID_LEVEL" => VerifyErrorCode::InvalidLevel,
+            "INVALID_CREATED_AT" => VerifyErrorCode::InvalidCreatedAt,
+            "INVALID_REPLAY_PROOF" => VerifyErrorCode::InvalidReplayProof,
+            "ENTRY_REPLAY_MISMATCH" => VerifyErrorCode::EntryReplayMismatch,
+            "INVALID_CHALLENGE_KEY" => VerifyErrorCode::InvalidChallengeKey,
+            "INVALID_ATTEMPT_TOKEN" => VerifyErrorCode::InvalidAttemptToken,
+            "CHALLENGE_KEY_MISMATCH" => VerifyErrorCode::ChallengeKeyMismatch,
+            "REPLAY_VERIFICATION_FAILED" => VerifyErrorCode::ReplayVerificationFailed,
+            "MISSING_SUPABASE_ENV" => VerifyErrorCode::MissingSupabaseEnv,
+            "RPC_SUBMIT_DAILY_SCORE_FAILED" => VerifyErrorCode::RpcSubmitDailyScoreFailed,
+            "RPC_SUBMIT_GLOBAL_SCORE_FAILED" => VerifyErrorCode::RpcSubmitGlobalScoreFailed,
+            other => VerifyErrorCode::Unknown(other.to_string()),
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        match self {
+            VerifyErrorCode::MethodNotAllowed => "METHOD_NOT_ALLOWED",
+            VerifyErrorCode::InvalidJson => "INVALID_JSON",
+            VerifyErrorCode::InvalidPayload => "INVALID_PAYLOAD",
+            VerifyErrorCode::InvalidMode => "INVALID_MODE",
+            VerifyErrorCode::InvalidClientUuid => "INVALID_CLIENT_UUID",
+            VerifyErrorCode::InvalidEntry => "INVALID_ENTRY",
+            VerifyErrorCode::InvalidPlayerName => "INVALID_PLAYER_NAME",
+            VerifyErrorCode::InvalidScore => "INVALID_SCORE",
+            VerifyErrorCode::InvalidLevel => "INVALID_LEVEL",
+            VerifyErrorCode::InvalidCreatedAt => "INVALID_CREATED_AT",
+            VerifyErrorCode::InvalidReplayProof => "INVALID_REPLAY_PROOF",
+            VerifyErrorCode::EntryReplayMismatch => "ENTRY_REPLAY_MISMATCH",
+            VerifyErrorCode::InvalidChallengeKey => "INVALID_CHALLENGE_KEY",
+            VerifyErrorCode::InvalidAttemptToken => "INVALID_ATTEMPT_TOKEN",
+            VerifyErrorCode::ChallengeKeyMismatch => "CHALLENGE_KEY_MISMATCH",
+            VerifyErrorCode::ReplayVerificationFailed => "REPLAY_VERIFICATION_FAILED",
+            VerifyErrorCode::MissingSupabaseEnv => "MISSING_SUPABASE_ENV",
+            VerifyErrorCode::RpcSubmitDailyScoreFailed => "RPC_SUBMIT_DAILY_SCORE_FAILED",
+            VerifyErrorCode::RpcSubmitGlobalScoreFailed => "RPC_SUBMIT_GLOBAL_SCORE_FAILED",
+            VerifyErrorCode::Unknown(raw) => raw,
+        }
+    }
+
+    /// Whether the code is a verdict on the submitted entry or replay proof,
+    /// rather than a deployment, routing or availability problem.
+    pub(crate) fn rejects_submission(&self) -> bool {
+        matches!(
+            self,
+            VerifyErrorCode::ReplayVerificationFailed
+                | VerifyErrorCode::EntryReplayMismatch
+                | VerifyErrorCode::InvalidJson
+                | VerifyErrorCode::InvalidPayload
+                | VerifyErrorCode::InvalidMode
+                | VerifyErrorCode::InvalidClientUuid
+                | VerifyErrorCode::InvalidEntry
+                | VerifyErrorCode::InvalidPlayerName
+                | VerifyErrorCode::InvalidScore
+                | VerifyErrorCode::InvalidLevel
+                | VerifyErrorCode::InvalidCreatedAt
+                | VerifyErrorCode::InvalidReplayProof
+                | VerifyErrorCode::InvalidChallengeKey
+                | VerifyErrorCode::InvalidAttemptToken
+        )
+    }
+
+    /// What the player or the deployer can do about this code.
+    pub(crate) fn hint(&self) -> Option<&'static str> {
+        match self {
+            VerifyErrorCode::ReplayVerificationFailed => {
+                Some("Replay proof mismatch. Compare the reason and expected/actual values.")
+            }
+            VerifyErrorCode::ChallengeKeyMismatch => {
+                Some("The UTC day changed during the run. Start a new Daily Challenge attempt.")
+            }
+            VerifyErrorCode::InvalidAttemptToken => {
+                Some("The attempt token is stale. Start a new Daily Challenge attempt.")
+            }
+            VerifyErrorCode::InvalidChallengeKey => {
+                Some("The challenge period is over. Start a run for the current period.")
+            }
+            VerifyErrorCode::EntryReplayMismatch => {
+                Some("The submitted score or level differs from the replay proof's final state.")
+            }
+            VerifyErrorCode::MissingSupabaseEnv => {
+                Some("Set SUPABASE_SERVICE_ROLE_KEY in Edge Function secrets.")
+            }
+            VerifyErrorCode::RpcSubmitDailyScoreFailed => {
+                Some("Check submit_daily_score RPC and service_role execute grant in schema.")
+            }
+            VerifyErrorCode::RpcSubmitGlobalScoreFailed => {
+                Some("Check submit_global_score RPC and service_role execute grant in schema.")
+            }
+            VerifyErrorCode::MethodNotAllowed
+            | VerifyErrorCode::InvalidJson
+            | VerifyErrorCode::InvalidPayload
+            | VerifyErrorCode::InvalidMode
+            | VerifyErrorCode::InvalidClientUuid
+            | VerifyErrorCode::InvalidEntry
+            | VerifyErrorCode::InvalidPlayerName
+            | VerifyErrorCode::InvalidScore
+            | VerifyErrorCode::InvalidLevel
+            | VerifyErrorCode::InvalidCreatedAt
+            | VerifyErrorCode::InvalidReplayProof
+            | VerifyErrorCode::Unknown(_) => None,
+        }
+    }
+}
+
+/// A `verify-score` error body. Replay mismatches carry the proof's claimed
+/// final state in `expected` and the re-simulated one in `actual`; RPC
+/// failures carry the Postgres message, hint and SQLSTATE.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VerifyError {
+    pub code: VerifyErrorCode,
+    /// The RPC exception named in `detail`, when it is a known code.
+    pub cause: Option<VerifyErrorCode>,
+    pub reason: Option<String>,
+    pub detail: Option<String>,
+    pub server_hint: Option<String>,
+    pub sql_state: Option<String>,
+    pub expected: Map<String, Value>,
+    pub actual: Map<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VerifyErrorBody {
+    error: String,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    detail: Option<String>,
+    #[serde(default)]
+    hint: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    expected: Option<Map<String, Value>>,
+    #[serde(default)]
+    actual: Option<Map<String, Value>>,
+}
+
+/// A parsed error response; bodies that do not match the edge function's
+/// schema (gateway errors, HTML, empty bodies) are kept as text.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum VerifyErrorResponse {
+    Structured(VerifyError),
+    Unstructured(String),
+}
+
+impl VerifyErrorResponse {
+    pub(crate) fn parse(body: &str) -> Self {
+        let trimmed = body.trim();
+        if trimmed.is_empty() {
+            return VerifyErrorResponse::Unstructured("(empty response body)".to_string());
+        }
+
+        let Ok(parsed) = serde_json::from_str::<VerifyErrorBody>(trimmed) else {
+            return VerifyErrorResponse::Unstructured(trimmed.to_string());
+        };
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|text| text.trim().to_string())
+                .filter(|text| !text.is_empty())
+        };
+        let detail = non_empty(parsed.detail);
+        let cause = detail
+            .as_deref()
+            .map(VerifyErrorCode::parse)
+            .filter(|code| !matches!(code, VerifyErrorCode::Unknown(_)));
+        VerifyErrorResponse::Structured(VerifyError {
+            code: VerifyErrorCode::parse(&parsed.error),
+            cause,
+            reason: non_empty(parsed.reason),
+            detail,
+            server_hint: non_empty(parsed.hint),
+            sql_state: non_empty(parsed.code),
+            expected: parsed.expected.unwrap_or_default(),
+            actual: parsed.actual.unwrap_or_default(),
+        })
+    }
+
+    /// One line for logs and cached entries, e.g.
+    /// `REPLAY_VERIFICATION_FAILED, reason=Replay final score mismatch., score=expected 1200 actual 1100`.
+    pub(crate) fn summary(&self) -> String {
+        let error = match self {
+            VerifyErrorResponse::Structured(error) => error,
+            VerifyErrorResponse::Unstructured(text) => return text.clone(),
+        };
+
+        let mut parts = vec![error.code.as_str().to_string()];
+        if let Some(reason) = &error.reason {
+            parts.push(format!("reason={reason}"));
+        }
+        if let Some(detail) = &error.detail {
+            parts.push(format!("detail={detail}"));
+        }
+        if let Some(sql_state) = &error.sql_state {
+            parts.push(format!("code={sql_state}"));
+        }
+        if let Some(server_hint) = &error.server_hint {
+            parts.push(format!("hint={server_hint}"));
+        }
+        for (key, expected, actual) in error.mismatches() {
+            parts.push(format!("{key}=expected {expected} actual {actual}"));
+        }
+        parts.join(", ")
+    }
+
+    /// Whether the server judged the submission itself, through its top-level
+    /// code or the RPC exception it forwards. Gateway, auth and routing errors
+    /// do not parse as verify-score bodies and never count.
+    pub(crate) fn rejects_submission(&self) -> bool {
+        let VerifyErrorResponse::Structured(error) = self else {
+            return false;
+        };
+        error.code.rejects_submission()
+            || error
+                .cause
+                .as_ref()
+                .is_some_and(VerifyErrorCode::rejects_submission)
+    }
+
+    /// Hint for the most specific known code: the RPC exception first, then a
+    /// missing grant, then the top-level code.
+    pub(crate) fn hint(&self) -> Option<&'static str> {
+        let VerifyErrorResponse::Structured(error) = self else {
+            return None;
+        };
+        if let Some(hint) = error.cause.as_ref().and_then(VerifyErrorCode::hint) {
+            return Some(hint);
+        }
+        let permission_denied = error.sql_state.as_deref() == Some(PG_INSUFFICIENT_PRIVILEGE)
+            || error
+                .detail
+                .as_deref()
+                .is_some_and(|detail| detail.contains("permission denied"));
+        if permission_denied {
+            return Some(
+                "Grant execute on submit_daily_score/submit_global_score to service_role and redeploy schema.",
+            );
+        }
+        error.code.hint()
+    }
+}
+
+impl VerifyError {
+    /// Keys reported in both `expected` and `actual` whose values differ.
+    pub(crate) fn mismatches(&self) -> Vec<(&str, &Value, &Value)> {
+        self.expected
+            .iter()
+            .filter_map(|(key, expected)| {
+                let actual = self.actual.get(key)?;
+                (actual != expected).then_some((key.as_str(), expected, actual))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KNOWN_CODES: [VerifyErrorCode; 19] = [
+        VerifyErrorCode::MethodNotAllowed,
+        VerifyErrorCode::InvalidJson,
+        VerifyErrorCode::InvalidPayload,
+        VerifyErrorCode::InvalidMode,
+        VerifyErrorCode::InvalidClientUuid,
+        VerifyErrorCode::InvalidEntry,
+        VerifyErrorCode::InvalidPlayerName,
+        VerifyErrorCode::InvalidScore,
+        VerifyErrorCode::InvalidLevel,
+        VerifyErrorCode::InvalidCreatedAt,
+        VerifyErrorCode::InvalidReplayProof,
+        VerifyErrorCode::EntryReplayMismatch,
+        VerifyErrorCode::InvalidChallengeKey,
+        VerifyErrorCode::InvalidAttemptToken,
+        VerifyErrorCode::ChallengeKeyMismatch,
+        VerifyErrorCode::ReplayVerificationFailed,
+        VerifyErrorCode::MissingSupabaseEnv,
+        VerifyErrorCode::RpcSubmitDailyScoreFailed,
+        VerifyErrorCode::RpcSubmitGlobalScoreFailed,
+    ];
+
+    fn structured(body: &str) -> VerifyError {
+        match VerifyErrorResponse::parse(body) {
+            VerifyErrorResponse::Structured(error) => error,
+            other => panic!("expected a structured error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn every_known_code_round_trips() {
+        for code in KNOWN_CODES {
+            assert_eq!(VerifyErrorCode::parse(code.as_str()), code);
+            let body = format!(r#"{{"error":"{}"}}"#, code.as_str());
+            assert_eq!(structured(&body).code, code);
+        }
+    }
+
+    #[test]
+    fn every_known_code_has_the_expected_hint() {
+        for code in KNOWN_CODES {
+            let has_hint = matches!(
+                code,
+                VerifyErrorCode::ReplayVerificationFailed
+                    | VerifyErrorCode::ChallengeKeyMismatch
+                    | VerifyErrorCode::InvalidAttemptToken
+                    | VerifyErrorCode::InvalidChallengeKey
+                    | VerifyErrorCode::EntryReplayMismatch
+                    | VerifyErrorCode::MissingSupabaseEnv
+                    | VerifyErrorCode::RpcSubmitDailyScoreFailed
+                    | VerifyErrorCode::RpcSubmitGlobalScoreFailed
+            );
+            assert_eq!(code.hint().is_some(), has_hint, "{}", code.as_str());
+            let body = format!(r#"{{"error":"{}"}}"#, code.as_str());
+            assert_eq!(VerifyErrorResponse::parse(&body).hint(), code.hint());
+        }
+    }
+
+    #[test]
+    fn only_verdict_codes_reject_the_submission() {
+        for code in KNOWN_CODES {
+            let is_verdict = code.as_str().starts_with("INVALID_")
+                || matches!(
+                    code,
+                    VerifyErrorCode::ReplayVerificationFailed
+                        | VerifyErrorCode::EntryReplayMismatch
+                );
+            assert_eq!(code.rejects_submission(), is_verdict, "{}", code.as_str());
+        }
+
+        let forwarded = VerifyErrorResponse::parse(
+            r#"{"error":"RPC_SUBMIT_DAILY_SCORE_FAILED","detail":"INVALID_ATTEMPT_TOKEN"}"#,
+        );
+        assert!(forwarded.rejects_submission());
+        let missing_grant = VerifyErrorResponse::parse(
+            r#"{"error":"RPC_SUBMIT_GLOBAL_SCORE_FAILED","detail":"permission denied","code":"42501"}"#,
+        );
+        assert!(!missing_grant.rejects_submission());
+        for body in [
+            r#"{"message":"Invalid JWT"}"#,
+            r#"{"code":"NOT_FOUND","message":"Requested function was not found"}"#,
+            "<html>Forbidden</html>",
+            "",
+        ] {
+            assert!(
+                !VerifyErrorResponse::parse(body).rejects_submission(),
+                "{body}"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_code_is_kept_verbatim() {
+        let error = structured(r#"{"error":"SOMETHING_NEW"}"#);
+        assert_eq!(error.code, VerifyErrorCode::Unknown("SOMETHING_NEW".into()));
+        assert_eq!(error.code.as_str(), "SOMETHING_NEW");
+        assert_eq!(error.code.hint(), None);
+    }
+
+    #[test]
+    fn replay_mismatch_exposes_expected_and_actual() {
+        let response = VerifyErrorResponse::parse(
+            r#"{
+                "error": "REPLAY_VERIFICATION_FAILED",
+                "reason": "Replay final score mismatch.",
+                "actual": {"score": 1100, "level": 4, "time": 900, "gameOn": false},
+                "expected": {"score": 1200, "level": 4, "time": 900, "difficulty": 2, "inputCount": 57}
+            }"#,
+        );
+        let VerifyErrorResponse::Structured(error) = &response else {
+            panic!("expected a structured error");
+        };
+        assert_eq!(
+            error.reason.as_deref(),
+            Some("Replay final score mismatch.")
+        );
+        assert_eq!(error.expected.get("inputCount"), Some(&Value::from(57)));
+        assert_eq!(error.actual.get("gameOn"), Some(&Value::from(false)));
+        assert_eq!(
+            error.mismatches(),
+            vec![("score", &Value::from(1200), &Value::from(1100))]
+        );
+        assert_eq!(
+            response.summary(),
+            "REPLAY_VERIFICATION_FAILED, reason=Replay final score mismatch., score=expected 1200 actual 1100"
+        );
+        assert_eq!(
+            response.hint(),
+            VerifyErrorCode::ReplayVerificationFailed.hint()
+        );
+    }
+
+    #[test]
+    fn rpc_failure_hint_follows_the_forwarded_exception() {
+        let response = VerifyErrorResponse::parse(
+            r#"{"error":"RPC_SUBMIT_DAILY_SCORE_FAILED","detail":"CHALLENGE_KEY_MISMATCH","hint":null,"code":"P0001"}"#,
+        );
+        let VerifyErrorResponse::Structured(error) = &response else {
+            panic!("expected a structured error");
+        };
+        assert_eq!(error.code, VerifyErrorCode::RpcSubmitDailyScoreFailed);
+        assert_eq!(error.cause, Some(VerifyErrorCode::ChallengeKeyMismatch));
+        assert_eq!(error.server_hint, None);
+        assert_eq!(error.sql_state.as_deref(), Some("P0001"));
+        assert_eq!(
+            response.hint(),
+            VerifyErrorCode::ChallengeKeyMismatch.hint()
+        );
+        assert_eq!(
+            response.summary(),
+            "RPC_SUBMIT_DAILY_SCORE_FAILED, detail=CHALLENGE_KEY_MISMATCH, code=P0001"
+        );
+    }
+
+    #[test]
+    fn missing_grant_gets_the_permission_hint() {
+        let by_state = VerifyErrorResponse::parse(
+            r#"{"error":"RPC_SUBMIT_GLOBAL_SCORE_FAILED","detail":"denied","code":"42501"}"#,
+        );
+        let by_message = VerifyErrorResponse::parse(
+            r#"{"error":"RPC_SUBMIT_GLOBAL_SCORE_FAILED","detail":"permission denied for function submit_global_score"}"#,
+        );
+        for response in [by_state, by_message] {
+            assert!(response.hint().unwrap().starts_with("Grant execute"));
+        }
+    }
+
+    #[test]
+    fn unknown_shapes_fall_back_to_text() {
+        for body in [
+            r#"{"code":401,"message":"Invalid JWT"}"#,
+            r#"{"error":"INVALID_JSON","extra":true}"#,
+            "<html>Bad Gateway</html>",
+            "[1,2,3]",
+        ] {
+            let response = VerifyErrorResponse::parse(body);
+            assert_eq!(
+                response,
+                VerifyErrorResponse::Unstructured(body.to_string())
+            );
+            assert_eq!(response.hint(), None);
+            assert_eq!(response.summary(), body);
+        }
+        assert_eq!(
+            VerifyErrorResponse::parse("  \n").summary(),
+            "(empty response body)"
+        );
+    }
+}
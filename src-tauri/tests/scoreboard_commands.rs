@@ -0,0 +1,1494 @@
+//! Drives the scoreboard commands through Tauri's mock runtime against the
+//! in-process Supabase stand-in in `support::mock_supabase`.
+
+mod support;
+
+use serde_json::{json, Value};
+use std::time::Duration;
+use support::mock_supabase::{
+    day_key, friend_code_for_device, now_seconds, Fault, MockSupabase, ANON_KEY, FORFEIT_RPC_PATH,
+    HISTORY_RANKS_RPC_PATH, REPORT_SCORE_RPC_PATH, ROLLBACK_RPC_PATH, SCORES_PATH, START_RPC_PATH,
+    STREAK_STATES_PATH, UPSERT_PROFILE_RPC_PATH, VERIFY_SCORE_PATH,
+};
+use support::{TestApp, CLIENT_TIMEOUT};
+
+/// Merges the mock server's URL and key into a command's arguments.
+fn remote(server: &MockSupabase, mut args: Value) -> Value {
+    args["supabaseUrl"] = json!(server.url());
+    args["supabaseAnonKey"] = json!(ANON_KEY);
+    args
+}
+
+fn entry(score: i64, level: i64) -> Value {
+    json!({
+        "user": "Tester",
+        "score": score,
+        "level": level,
+        "date": "2026-01-01T00:00:00.000Z",
+        "skillUsage": [],
+    })
+}
+
+/// A well-formed proof claiming `score` and `level`; `seed` keeps proofs
+/// distinct so the duplicate guard does not refuse them.
+fn proof(score: i64, level: i64, seed: u32) -> Value {
+    json!({
+        "version": 1,
+        "difficulty": 1,
+        "seed": seed,
+        "finalTime": 300,
+        "finalScore": score,
+        "finalLevel": level,
+        "inputs": [
+            { "time": 10, "move": "left" },
+            { "time": 40, "move": "up" },
+            { "time": 90, "move": "right" },
+        ],
+    })
+}
+
+/// A run on `seed` that plays no moves until the game ends, with the final
+/// state the simulator reaches, so local replay verification accepts it.
+fn finished_run(seed: u32) -> Value {
+    let mut run = json!({
+        "version": 1,
+        "difficulty": 1,
+        "seed": seed,
+        "finalTime": 2_000_000,
+        "finalScore": 0,
+        "finalLevel": 0,
+        "inputs": [],
+    });
+    let verification = torus_core::replay::verify_replay_json(&run.to_string()).unwrap();
+    assert!(!verification.actual.game_on, "an idle run should end");
+    run["finalTime"] = json!(verification.actual.time);
+    run["finalScore"] = json!(verification.actual.score);
+    run["finalLevel"] = json!(verification.actual.level);
+    run
+}
+
+fn rival_row(mode: &str, challenge_key: &str, score: i64, level: i64) -> Value {
+    json!({
+        "mode": mode,
+        "challenge_key": challenge_key,
+        "client_uuid": "rival-device",
+        "player_name": "Rival",
+        "score": score,
+        "level": level,
+        "created_at": "2026-01-01T00:00:00.000Z",
+        "skill_usage": [],
+        "attempts_used": 1,
+        "active_attempt_token": null,
+        "daily_has_submission": true,
+    })
+}
+
+fn current_key(app: &TestApp, server: &MockSupabase, mode: &str) -> String {
+    let period = app
+        .invoke::<Value>(
+            "get_challenge_period",
+            remote(server, json!({ "mode": mode })),
+        )
+        .unwrap();
+    period["challengeKey"].as_str().unwrap().to_string()
+}
+
+/// The device UUID the app sent with its first attempt RPC.
+fn device_uuid(server: &MockSupabase) -> String {
+    let request = server.requests_to(START_RPC_PATH).remove(0);
+    let body = serde_json::from_str::<Value>(&request.body).unwrap();
+    body["p_client_uuid"].as_str().unwrap().to_string()
+}
+
+fn start_daily(app: &TestApp, server: &MockSupabase, challenge_key: &str) -> Result<Value, String> {
+    app.invoke(
+        "start_daily_attempt",
+        remote(server, json!({ "challengeKey": challenge_key })),
+    )
+}
+
+#[test]
+fn classic_submit_returns_rank_and_syncs_cache() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    server.insert("scores", rival_row("classic", "classic", 500, 9));
+
+    let result = app
+        .invoke::<Value>(
+            "submit_global_score",
+            remote(
+                &server,
+                json!({ "entry": entry(300, 4), "replayProof": proof(300, 4, 1) }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(result["accepted"], true);
+    assert_eq!(result["improved"], true);
+    assert_eq!(result["rank"], 2);
+    assert_eq!(result["totalPlayers"], 2);
+    assert_eq!(result["localOnly"], false);
+
+    let verify = server.requests_to(VERIFY_SCORE_PATH).remove(0);
+    assert_eq!(verify.headers["apikey"], ANON_KEY);
+    assert_eq!(
+        verify.headers["authorization"],
+        format!("Bearer {ANON_KEY}")
+    );
+    assert_eq!(
+        verify.headers["user-agent"],
+        concat!("torus-app/", env!("CARGO_PKG_VERSION"))
+    );
+
+    let scores = app
+        .invoke::<Vec<Value>>("fetch_global_scores", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(scores.len(), 2);
+    assert_eq!(scores[0]["user"], "Rival");
+    assert_eq!(scores[0]["isMe"], false);
+    assert_eq!(scores[1]["isMe"], true);
+    assert_eq!(scores[1]["syncState"], "synced");
+}
+
+#[test]
+fn classic_submit_without_supabase_stays_local() {
+    let app = TestApp::new();
+
+    let first = app
+        .invoke::<Value>(
+            "submit_global_score",
+            json!({ "entry": entry(300, 4), "replayProof": proof(300, 4, 1) }),
+        )
+        .unwrap();
+    assert_eq!(first["localOnly"], true);
+    assert_eq!(first["accepted"], false);
+    assert_eq!(first["improved"], true);
+
+    let second = app
+        .invoke::<Value>(
+            "submit_global_score",
+            json!({ "entry": entry(100, 2), "replayProof": proof(100, 2, 2) }),
+        )
+        .unwrap();
+    assert_eq!(second["improved"], false);
+    assert_eq!(second["previousBest"], 300);
+
+    let cached = app
+        .invoke::<Vec<Value>>("fetch_global_scores", json!({}))
+        .unwrap();
+    assert_eq!(cached.len(), 2);
+    assert!(cached.iter().all(|score| score["syncState"] == "pending"));
+}
+
+#[test]
+fn classic_rejection_is_archived_and_never_resent() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let args = remote(
+        &server,
+        json!({ "entry": entry(900, 4), "replayProof": proof(300, 4, 1) }),
+    );
+
+    let result = app
+        .invoke::<Value>("submit_global_score", args.clone())
+        .unwrap();
+    assert_eq!(result["localOnly"], true);
+
+    let cached = app
+        .invoke::<Vec<Value>>("fetch_global_scores", json!({}))
+        .unwrap();
+    assert_eq!(cached[0]["syncState"], "rejected");
+    assert!(cached[0]["syncError"]
+        .as_str()
+        .unwrap()
+        .starts_with("ENTRY_REPLAY_MISMATCH"));
+
+    let rejects = app
+        .invoke::<Vec<Value>>("list_rejected_submissions", json!({}))
+        .unwrap();
+    assert_eq!(rejects.len(), 1);
+    assert_eq!(rejects[0]["status"], 400);
+    assert_eq!(rejects[0]["error"], "ENTRY_REPLAY_MISMATCH");
+
+    let again = app.invoke::<Value>("submit_global_score", args).unwrap();
+    assert_eq!(again["localOnly"], true);
+    assert_eq!(again["reason"], "duplicate");
+    assert_eq!(server.requests_to(VERIFY_SCORE_PATH).len(), 1);
+    let cached = app
+        .invoke::<Vec<Value>>("fetch_global_scores", json!({}))
+        .unwrap();
+    assert_eq!(cached.len(), 1);
+    assert_eq!(cached[0]["syncState"], "rejected");
+}
+
+#[test]
+fn rate_limited_runs_are_kept_pending() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    for seed in 1..=5 {
+        let result = app
+            .invoke::<Value>(
+                "submit_global_score",
+                remote(
+                    &server,
+                    json!({ "entry": entry(300 + seed, 4), "replayProof": proof(300 + seed, 4, seed as u32) }),
+                ),
+            )
+            .unwrap();
+        assert_eq!(result["localOnly"], false);
+    }
+
+    let result = app
+        .invoke::<Value>(
+            "submit_global_score",
+            remote(
+                &server,
+                json!({ "entry": entry(900, 4), "replayProof": proof(900, 4, 6) }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(result["localOnly"], true);
+    assert_eq!(result["reason"], "rate_limited");
+    assert!(result["retryAfter"].as_i64().unwrap() > 0);
+    assert_eq!(server.requests_to(VERIFY_SCORE_PATH).len(), 5);
+
+    let cached = app
+        .invoke::<Vec<Value>>("fetch_global_scores", json!({}))
+        .unwrap();
+    assert_eq!(cached[0]["score"], 900);
+    assert_eq!(cached[0]["syncState"], "pending");
+}
+
+#[test]
+fn classic_server_errors_stay_retryable() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let args = remote(
+        &server,
+        json!({ "entry": entry(300, 4), "replayProof": proof(300, 4, 1) }),
+    );
+
+    let faults = [
+        (503, String::new()),
+        (401, r#"{"message":"Invalid JWT"}"#.to_string()),
+        (403, "<html>Forbidden</html>".to_string()),
+        (
+            404,
+            r#"{"code":"NOT_FOUND","message":"Requested function was not found"}"#.to_string(),
+        ),
+    ];
+    for (status, body) in faults {
+        server.fail(VERIFY_SCORE_PATH, Fault::Status(status, body));
+        let result = app
+            .invoke::<Value>("submit_global_score", args.clone())
+            .unwrap();
+        assert_eq!(result["localOnly"], true, "{status}");
+        let rejects = app
+            .invoke::<Vec<Value>>("list_rejected_submissions", json!({}))
+            .unwrap();
+        assert!(rejects.is_empty(), "{status}");
+        let cached = app
+            .invoke::<Vec<Value>>("fetch_global_scores", json!({}))
+            .unwrap();
+        assert_ne!(cached[0]["syncState"], "rejected", "{status}");
+    }
+
+    server.clear_faults();
+    let result = app.invoke::<Value>("submit_global_score", args).unwrap();
+    assert_eq!(result["accepted"], true);
+    assert_eq!(result["localOnly"], false);
+}
+
+#[test]
+fn reconcile_matches_own_rows_by_date() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    server.fail(VERIFY_SCORE_PATH, Fault::Status(503, String::new()));
+    for (date, seed) in [
+        ("2026-01-01T00:00:00.000Z", 1),
+        ("2026-01-02T00:00:00.000Z", 2),
+    ] {
+        let mut run = entry(300, 4);
+        run["date"] = json!(date);
+        app.invoke::<Value>(
+            "submit_global_score",
+            remote(
+                &server,
+                json!({ "entry": run, "replayProof": proof(300, 4, seed) }),
+            ),
+        )
+        .unwrap();
+    }
+    let verify = server.requests_to(VERIFY_SCORE_PATH).remove(0);
+    let owner = serde_json::from_str::<Value>(&verify.body).unwrap()["clientUuid"].clone();
+
+    // Postgres echoes the first run's date in its own format.
+    server.clear_faults();
+    let mut row = rival_row("classic", "classic", 300, 4);
+    row["client_uuid"] = owner;
+    row["player_name"] = json!("Tester");
+    row["created_at"] = json!("2026-01-01T09:00:00+09:00");
+    server.insert("scores", row);
+
+    app.invoke::<Vec<Value>>("fetch_global_scores", remote(&server, json!({})))
+        .unwrap();
+    let scores = app
+        .invoke::<Vec<Value>>("fetch_global_scores", json!({}))
+        .unwrap();
+    let state = |date: &str| {
+        scores
+            .iter()
+            .find(|score| score["isMe"] == true && score["date"] == date)
+            .map(|score| score["syncState"].clone())
+    };
+    assert_eq!(state("2026-01-01T00:00:00.000Z"), Some(json!("synced")));
+    assert_eq!(state("2026-01-02T00:00:00.000Z"), Some(json!("pending")));
+}
+
+#[test]
+fn global_scores_fall_back_to_cache_on_malformed_json() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    app.invoke::<Value>(
+        "submit_global_score",
+        json!({ "entry": entry(300, 4), "replayProof": proof(300, 4, 1) }),
+    )
+    .unwrap();
+
+    server.fail(SCORES_PATH, Fault::MalformedJson);
+    let scores = app
+        .invoke::<Vec<Value>>("fetch_global_scores", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0]["score"], 300);
+}
+
+#[test]
+fn global_scores_fall_back_to_cache_on_timeout() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    app.invoke::<Value>(
+        "submit_global_score",
+        json!({ "entry": entry(300, 4), "replayProof": proof(300, 4, 1) }),
+    )
+    .unwrap();
+
+    server.fail(
+        SCORES_PATH,
+        Fault::Delay(CLIENT_TIMEOUT + Duration::from_secs(1)),
+    );
+    let scores = app
+        .invoke::<Vec<Value>>("fetch_global_scores", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(scores.len(), 1);
+}
+
+#[test]
+fn friends_board_requires_supabase_and_surfaces_http_errors() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+
+    let error = app
+        .invoke::<Vec<Value>>("fetch_global_scores", json!({ "friendsOnly": true }))
+        .unwrap_err();
+    assert_eq!(error, "friends leaderboard requires Supabase configuration");
+
+    server.fail(
+        SCORES_PATH,
+        Fault::Status(500, "{\"message\":\"boom\"}".into()),
+    );
+    let error = app
+        .invoke::<Vec<Value>>(
+            "fetch_global_scores",
+            remote(&server, json!({ "friendsOnly": true })),
+        )
+        .unwrap_err();
+    assert!(
+        error.starts_with("supabase request failed with 500"),
+        "{error}"
+    );
+}
+
+#[test]
+fn global_scores_attach_badges_from_streak_states() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    server.insert("scores", rival_row("classic", "classic", 500, 9));
+    server.insert(
+        "daily_streak_states",
+        json!({ "client_uuid": "rival-device", "current_streak": 2, "max_streak": 7 }),
+    );
+
+    let scores = app
+        .invoke::<Vec<Value>>("fetch_global_scores", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0]["badgeMaxStreak"], 7);
+    assert!(scores[0]["playerId"].is_string());
+
+    let lookup = server.requests_to(STREAK_STATES_PATH).remove(0);
+    assert_eq!(
+        lookup.query_value("client_uuid"),
+        Some("in.(\"rival-device\")")
+    );
+}
+
+#[test]
+fn daily_attempt_lifecycle() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "daily");
+    server.insert("scores", rival_row("daily", &key, 500, 9));
+
+    let started = start_daily(&app, &server, &key).unwrap();
+    assert_eq!(started["accepted"], true);
+    assert_eq!(started["resumed"], false);
+    assert_eq!(started["attemptsUsed"], 1);
+    let token = started["attemptToken"].as_str().unwrap().to_string();
+
+    let resumed = start_daily(&app, &server, &key).unwrap();
+    assert_eq!(resumed["resumed"], true);
+    assert_eq!(resumed["attemptToken"], token.as_str());
+    assert_eq!(resumed["attemptsUsed"], 1);
+
+    let status = app
+        .invoke::<Value>(
+            "fetch_daily_status",
+            remote(&server, json!({ "challengeKey": key })),
+        )
+        .unwrap();
+    assert_eq!(status["hasActiveAttempt"], true);
+    assert_eq!(status["attemptsLeft"], 2);
+    assert_eq!(status["maxAttempts"], 3);
+
+    let submitted = app
+        .invoke::<Value>(
+            "submit_daily_score",
+            remote(
+                &server,
+                json!({
+                    "challengeKey": key,
+                    "attemptToken": token,
+                    "entry": entry(300, 4),
+                    "replayProof": proof(300, 4, 1),
+                }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(submitted["accepted"], true);
+    assert_eq!(submitted["improved"], true);
+    assert_eq!(submitted["hasActiveAttempt"], false);
+    // A skill-free run that improved the day looks up its rank for achievements.
+    assert_eq!(server.requests_to(HISTORY_RANKS_RPC_PATH).len(), 1);
+
+    let board = app
+        .invoke::<Vec<Value>>(
+            "fetch_daily_scores",
+            remote(&server, json!({ "challengeKey": key })),
+        )
+        .unwrap();
+    assert_eq!(board.len(), 2);
+    assert_eq!(board[1]["score"], 300);
+    assert_eq!(board[1]["isMe"], true);
+
+    let history = app
+        .invoke::<Vec<Value>>(
+            "fetch_daily_history",
+            remote(&server, json!({ "from": key, "to": key })),
+        )
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["submitted"], true);
+    assert_eq!(history[0]["bestScore"], 300);
+    assert_eq!(history[0]["rank"], 2);
+    assert_eq!(server.requests_to(HISTORY_RANKS_RPC_PATH).len(), 2);
+
+    let calendar = app
+        .invoke::<Value>(
+            "fetch_streak_calendar",
+            remote(&server, json!({ "days": 7 })),
+        )
+        .unwrap();
+    let days = calendar["days"].as_array().unwrap();
+    assert_eq!(days.len(), 7);
+    assert_eq!(days[6]["challengeKey"], key.as_str());
+    assert_eq!(days[6]["submitted"], true);
+    assert_eq!(calendar["runs"][0]["length"], 1);
+
+    let forfeit = app
+        .invoke::<Value>(
+            "forfeit_daily_attempt",
+            remote(
+                &server,
+                json!({ "challengeKey": key, "attemptToken": token }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(forfeit["accepted"], false);
+}
+
+#[test]
+fn rollback_refunds_the_attempt() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "daily");
+
+    let started = start_daily(&app, &server, &key).unwrap();
+    let rolled_back = app
+        .invoke::<Value>(
+            "rollback_daily_attempt",
+            remote(
+                &server,
+                json!({ "challengeKey": key, "attemptToken": started["attemptToken"] }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(rolled_back["accepted"], true);
+    assert_eq!(rolled_back["attemptsUsed"], 0);
+    assert_eq!(rolled_back["attemptsLeft"], 3);
+    assert_eq!(rolled_back["hasActiveAttempt"], false);
+}
+
+#[test]
+fn attempt_limit_comes_from_challenge_settings() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "daily");
+    server.insert(
+        "challenge_settings",
+        json!({ "mode": "daily", "challenge_key": key, "max_attempts": 1 }),
+    );
+
+    let started = start_daily(&app, &server, &key).unwrap();
+    assert_eq!(started["maxAttempts"], 1);
+    app.invoke::<Value>(
+        "forfeit_daily_attempt",
+        remote(
+            &server,
+            json!({ "challengeKey": key, "attemptToken": started["attemptToken"] }),
+        ),
+    )
+    .unwrap();
+
+    let refused = start_daily(&app, &server, &key).unwrap();
+    assert_eq!(refused["accepted"], false);
+    assert_eq!(refused["canSubmit"], false);
+    assert_eq!(refused["attemptToken"], Value::Null);
+}
+
+#[test]
+fn challenge_status_clamps_attempts_to_the_limit() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let daily_key = current_key(&app, &server, "daily");
+    start_daily(&app, &server, &daily_key).unwrap();
+    let weekly_key = current_key(&app, &server, "weekly");
+    let mut row = rival_row("weekly", &weekly_key, 0, 0);
+    row["client_uuid"] = json!(device_uuid(&server));
+    row["attempts_used"] = json!(99);
+    server.insert("scores", row);
+
+    let status = app
+        .invoke::<Value>(
+            "fetch_challenge_status",
+            remote(
+                &server,
+                json!({ "mode": "weekly", "challengeKey": weekly_key }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(status["attemptsUsed"], 5);
+    assert_eq!(status["attemptsLeft"], 0);
+    assert_eq!(status["canSubmit"], false);
+}
+
+#[test]
+fn stale_attempt_is_refused_and_mismatch_is_archived() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "daily");
+    let stale = start_daily(&app, &server, &key).unwrap()["attemptToken"].clone();
+    app.invoke::<Value>(
+        "forfeit_daily_attempt",
+        remote(
+            &server,
+            json!({ "challengeKey": key, "attemptToken": stale }),
+        ),
+    )
+    .unwrap();
+    let submit = |token: &Value, score: i64, seed: u32| {
+        app.invoke::<Value>(
+            "submit_daily_score",
+            remote(
+                &server,
+                json!({
+                    "challengeKey": key,
+                    "attemptToken": token,
+                    "entry": entry(score, 4),
+                    "replayProof": proof(300, 4, seed),
+                }),
+            ),
+        )
+    };
+
+    let refused = submit(&stale, 300, 1).unwrap();
+    assert_eq!(refused["accepted"], false);
+    assert_eq!(refused["attemptsUsed"], 1);
+
+    let token = start_daily(&app, &server, &key).unwrap()["attemptToken"].clone();
+    let error = submit(&token, 900, 2).unwrap_err();
+    assert!(
+        error.contains("daily replay verify failed with 400"),
+        "{error}"
+    );
+    assert!(error.contains("ENTRY_REPLAY_MISMATCH"), "{error}");
+
+    let rejects = app
+        .invoke::<Vec<Value>>("list_rejected_submissions", json!({}))
+        .unwrap();
+    assert_eq!(rejects.len(), 1);
+    assert_eq!(rejects[0]["mode"], "daily");
+    assert_eq!(rejects[0]["challengeKey"], key.as_str());
+    assert_eq!(rejects[0]["score"], 900);
+}
+
+#[test]
+fn attempt_rpcs_explain_overload_errors() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "daily");
+
+    server.fail(START_RPC_PATH, Fault::rpc_overload());
+    let error = start_daily(&app, &server, &key).unwrap_err();
+    assert!(error.contains("daily start failed with 300"), "{error}");
+    assert!(
+        error.contains("Duplicate RPC overloads detected"),
+        "{error}"
+    );
+
+    server.fail(ROLLBACK_RPC_PATH, Fault::rpc_overload());
+    let error = app
+        .invoke::<Value>(
+            "rollback_daily_attempt",
+            remote(
+                &server,
+                json!({ "challengeKey": key, "attemptToken": "attempt-1" }),
+            ),
+        )
+        .unwrap_err();
+    assert!(
+        error.contains("Duplicate RPC overloads detected"),
+        "{error}"
+    );
+
+    server.fail(
+        FORFEIT_RPC_PATH,
+        Fault::Status(404, "{\"code\":\"PGRST202\"}".into()),
+    );
+    let error = app
+        .invoke::<Value>(
+            "forfeit_daily_attempt",
+            remote(
+                &server,
+                json!({ "challengeKey": key, "attemptToken": "attempt-1" }),
+            ),
+        )
+        .unwrap_err();
+    assert!(error.contains("daily forfeit failed with 404"), "{error}");
+    assert!(!error.contains("Duplicate RPC overloads"), "{error}");
+}
+
+#[test]
+fn daily_commands_surface_http_and_decode_errors() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "daily");
+
+    server.fail(
+        SCORES_PATH,
+        Fault::Status(500, "{\"message\":\"boom\"}".into()),
+    );
+    let error = app
+        .invoke::<Value>(
+            "fetch_daily_status",
+            remote(&server, json!({ "challengeKey": key })),
+        )
+        .unwrap_err();
+    assert!(
+        error.starts_with("supabase daily status fetch failed with 500"),
+        "{error}"
+    );
+
+    server.fail(SCORES_PATH, Fault::MalformedJson);
+    let error = app
+        .invoke::<Vec<Value>>(
+            "fetch_daily_scores",
+            remote(&server, json!({ "challengeKey": key })),
+        )
+        .unwrap_err();
+    assert!(
+        error.starts_with("failed to decode supabase response"),
+        "{error}"
+    );
+
+    server.fail(START_RPC_PATH, Fault::MalformedJson);
+    let error = start_daily(&app, &server, &key).unwrap_err();
+    assert!(
+        error.starts_with("failed to decode daily start response"),
+        "{error}"
+    );
+}
+
+#[test]
+fn challenge_commands_require_supabase_and_valid_keys() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "daily");
+
+    let error = app
+        .invoke::<Value>("fetch_daily_status", json!({ "challengeKey": key }))
+        .unwrap_err();
+    assert_eq!(
+        error,
+        "daily challenge sync requires Supabase configuration"
+    );
+
+    let error = app
+        .invoke::<Value>(
+            "fetch_daily_status",
+            remote(&server, json!({ "challengeKey": "2001-01-01" })),
+        )
+        .unwrap_err();
+    assert!(
+        error.starts_with("daily challenge key 2001-01-01 is not active"),
+        "{error}"
+    );
+
+    let error = app
+        .invoke::<Value>(
+            "fetch_challenge_status",
+            remote(&server, json!({ "mode": "hourly", "challengeKey": key })),
+        )
+        .unwrap_err();
+    assert_eq!(error, "challenge mode must be daily, weekly, or monthly");
+
+    let mut wrong_key = remote(&server, json!({ "challengeKey": key }));
+    wrong_key["supabaseAnonKey"] = json!("not-the-key");
+    let error = app
+        .invoke::<Value>("fetch_daily_status", wrong_key)
+        .unwrap_err();
+    assert!(error.contains("failed with 401"), "{error}");
+}
+
+#[test]
+fn badge_status_uses_streak_state_and_falls_back_to_cache() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "daily");
+    start_daily(&app, &server, &key).unwrap();
+    server.insert(
+        "daily_streak_states",
+        json!({
+            "client_uuid": device_uuid(&server),
+            "current_streak": 3,
+            "max_streak": 5,
+            "last_submission_key": key,
+            "freeze_tokens": 1,
+        }),
+    );
+
+    let args = remote(&server, json!({ "challengeKey": key }));
+    let status = app
+        .invoke::<Value>("fetch_daily_badge_status", args.clone())
+        .unwrap();
+    assert_eq!(status["currentStreak"], 3);
+    assert_eq!(status["maxStreak"], 5);
+    assert_eq!(status["freezeTokens"], 1);
+    assert_eq!(status["offline"], false);
+
+    server.fail(STREAK_STATES_PATH, Fault::Status(503, String::new()));
+    let status = app
+        .invoke::<Value>("fetch_daily_badge_status", args)
+        .unwrap();
+    assert_eq!(status["currentStreak"], 3);
+    assert_eq!(status["offline"], true);
+}
+
+#[test]
+fn clock_follows_the_server_date() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+
+    let clock = app
+        .invoke::<Value>("get_daily_clock", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(clock["serverSynced"], true);
+    assert_eq!(clock["challengeKey"], day_key(now_seconds()));
+    assert!(clock["serverOffsetSeconds"].as_i64().unwrap().abs() <= 1);
+}
+
+#[test]
+fn weekly_attempt_lifecycle() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "weekly");
+    server.insert("scores", rival_row("weekly", &key, 500, 9));
+    let start = || {
+        app.invoke::<Value>(
+            "start_challenge_attempt",
+            remote(
+                &server,
+                json!({ "mode": "weekly", "challengeKey": key, "displayName": "Tester" }),
+            ),
+        )
+        .unwrap()
+    };
+
+    let started = start();
+    assert_eq!(started["accepted"], true);
+    assert_eq!(started["maxAttempts"], 5);
+    let submitted = app
+        .invoke::<Value>(
+            "submit_challenge_score",
+            remote(
+                &server,
+                json!({
+                    "mode": "weekly",
+                    "challengeKey": key,
+                    "attemptToken": started["attemptToken"],
+                    "entry": entry(300, 4),
+                    "replayProof": proof(300, 4, 1),
+                }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(submitted["accepted"], true);
+    assert_eq!(submitted["improved"], true);
+
+    let board = app
+        .invoke::<Vec<Value>>(
+            "fetch_challenge_scores",
+            remote(&server, json!({ "mode": "weekly", "challengeKey": key })),
+        )
+        .unwrap();
+    assert_eq!(board.len(), 2);
+    assert_eq!(board[0]["user"], "Rival");
+    assert_eq!(board[1]["user"], "Tester");
+    assert_eq!(board[1]["isMe"], true);
+
+    let forfeited = app
+        .invoke::<Value>(
+            "forfeit_challenge_attempt",
+            remote(
+                &server,
+                json!({ "mode": "weekly", "challengeKey": key, "attemptToken": start()["attemptToken"] }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(forfeited["accepted"], true);
+    assert_eq!(forfeited["attemptsUsed"], 2);
+
+    let rolled_back = app
+        .invoke::<Value>(
+            "rollback_challenge_attempt",
+            remote(
+                &server,
+                json!({ "mode": "weekly", "challengeKey": key, "attemptToken": start()["attemptToken"] }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(rolled_back["accepted"], true);
+    assert_eq!(rolled_back["attemptsUsed"], 2);
+    assert_eq!(rolled_back["attemptsLeft"], 3);
+}
+
+#[test]
+fn daily_practice_opens_once_attempts_are_used() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    let key = current_key(&app, &server, "daily");
+    server.insert(
+        "challenge_settings",
+        json!({ "mode": "daily", "challenge_key": key, "max_attempts": 1 }),
+    );
+
+    let started = start_daily(&app, &server, &key).unwrap();
+    let error = app
+        .invoke::<Value>("start_daily_practice", remote(&server, json!({})))
+        .unwrap_err();
+    assert!(error.contains("after all daily attempts"), "{error}");
+    app.invoke::<Value>(
+        "forfeit_daily_attempt",
+        remote(
+            &server,
+            json!({ "challengeKey": key, "attemptToken": started["attemptToken"] }),
+        ),
+    )
+    .unwrap();
+
+    let practice = app
+        .invoke::<Value>("start_daily_practice", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(practice["challengeKey"], key.as_str());
+    let run = finished_run(practice["seed"].as_u64().unwrap() as u32);
+    let score = run["finalScore"].as_i64().unwrap();
+    let level = run["finalLevel"].as_i64().unwrap();
+    let result = app
+        .invoke::<Value>(
+            "submit_daily_practice",
+            json!({
+                "practiceToken": practice["practiceToken"],
+                "entry": entry(score, level),
+                "replayProof": run,
+            }),
+        )
+        .unwrap();
+    assert_eq!(result["rank"], 1);
+    // Practice stays local: nothing reaches verify-score.
+    assert!(server.requests_to(VERIFY_SCORE_PATH).is_empty());
+
+    let board = app
+        .invoke::<Vec<Value>>(
+            "fetch_daily_practice_scores",
+            json!({ "challengeKey": key }),
+        )
+        .unwrap();
+    assert_eq!(board.len(), 1);
+    assert_eq!(board[0]["replayId"], result["replayId"]);
+    assert_eq!(board[0]["isMe"], true);
+
+    let replay = app
+        .invoke::<Value>(
+            "fetch_daily_practice_replay",
+            json!({ "challengeKey": key, "replayId": result["replayId"] }),
+        )
+        .unwrap();
+    assert_eq!(replay, run);
+}
+
+#[test]
+fn friend_challenge_codes_keep_a_head_to_head_record() {
+    let challenger = TestApp::new();
+    let friend = TestApp::new();
+    let run = finished_run(7);
+    let score = run["finalScore"].as_i64().unwrap();
+    let level = run["finalLevel"].as_i64().unwrap();
+
+    let created = challenger
+        .invoke::<Value>(
+            "create_challenge_code",
+            json!({ "entry": entry(score, level), "replayProof": run }),
+        )
+        .unwrap();
+    assert_eq!(created["seed"], 7);
+    assert_eq!(created["targetScore"], score);
+    let challenger_code = challenger
+        .invoke::<Value>("get_friend_code", json!({}))
+        .unwrap();
+    assert_eq!(challenger_code["published"], false);
+
+    let accepted = friend
+        .invoke::<Value>("accept_challenge_code", json!({ "code": created["code"] }))
+        .unwrap();
+    assert_eq!(accepted["seed"], 7);
+    assert_eq!(accepted["challengerName"], "Tester");
+    assert_eq!(accepted["challengerId"], challenger_code["friendCode"]);
+
+    let outcome = friend
+        .invoke::<Value>(
+            "complete_challenge_run",
+            json!({
+                "challengeId": accepted["challengeId"],
+                "entry": entry(score, level),
+                "replayProof": run,
+            }),
+        )
+        .unwrap();
+    assert_eq!(outcome["result"], "tie");
+    let error = friend
+        .invoke::<Value>(
+            "complete_challenge_run",
+            json!({
+                "challengeId": accepted["challengeId"],
+                "entry": entry(score, level),
+                "replayProof": run,
+            }),
+        )
+        .unwrap_err();
+    assert!(error.contains("already finished"), "{error}");
+
+    let records = friend
+        .invoke::<Vec<Value>>("fetch_head_to_head", json!({}))
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["friendId"], challenger_code["friendCode"]);
+    assert_eq!(records[0]["ties"], 1);
+}
+
+#[test]
+fn friends_resolve_through_published_codes() {
+    let app = TestApp::new();
+    let friend = TestApp::new();
+    let server = MockSupabase::start();
+    server.insert("scores", rival_row("classic", "classic", 900, 9));
+
+    let friend_code = friend
+        .invoke::<Value>("get_friend_code", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(friend_code["published"], true);
+    friend
+        .invoke::<Value>(
+            "submit_global_score",
+            remote(
+                &server,
+                json!({ "entry": entry(300, 4), "replayProof": proof(300, 4, 1) }),
+            ),
+        )
+        .unwrap();
+
+    let own_code = app.invoke::<Value>("get_friend_code", json!({})).unwrap();
+    let error = app
+        .invoke::<Value>(
+            "add_friend",
+            json!({ "friendCode": own_code["friendCode"] }),
+        )
+        .unwrap_err();
+    assert!(error.contains("your own friend code"), "{error}");
+
+    let added = app
+        .invoke::<Value>(
+            "add_friend",
+            remote(
+                &server,
+                json!({ "friendCode": friend_code["friendCode"], "nickname": "Bee" }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(added["resolved"], true);
+    assert_eq!(added["nickname"], "Bee");
+    let friends = app.invoke::<Vec<Value>>("list_friends", json!({})).unwrap();
+    assert_eq!(friends.len(), 1);
+
+    let board = app
+        .invoke::<Vec<Value>>(
+            "fetch_global_scores",
+            remote(&server, json!({ "friendsOnly": true })),
+        )
+        .unwrap();
+    assert_eq!(board.len(), 1);
+    assert_eq!(board[0]["score"], 300);
+    assert_eq!(board[0]["isFriend"], true);
+
+    let remaining = app
+        .invoke::<Vec<Value>>(
+            "remove_friend",
+            json!({ "friendCode": friend_code["friendCode"] }),
+        )
+        .unwrap();
+    assert!(remaining.is_empty());
+}
+
+#[test]
+fn groups_scope_boards_and_streaks_to_members() {
+    let owner = TestApp::new();
+    let member = TestApp::new();
+    let server = MockSupabase::start();
+    server.insert("scores", rival_row("classic", "classic", 900, 9));
+
+    let error = owner
+        .invoke::<Value>(
+            "create_group",
+            json!({ "name": "Crew", "playerName": "Owner" }),
+        )
+        .unwrap_err();
+    assert_eq!(error, "groups require Supabase configuration");
+    let created = owner
+        .invoke::<Value>(
+            "create_group",
+            remote(&server, json!({ "name": "Crew", "playerName": "Owner" })),
+        )
+        .unwrap();
+    assert_eq!(created["isOwner"], true);
+    assert_eq!(created["memberCount"], 1);
+    let group_id = created["groupId"].as_str().unwrap().to_string();
+
+    let error = member
+        .invoke::<Value>(
+            "join_group",
+            remote(
+                &server,
+                json!({ "inviteCode": "0000-0000", "playerName": "Member" }),
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(error, "no group matches that invite code");
+    let joined = member
+        .invoke::<Value>(
+            "join_group",
+            remote(
+                &server,
+                json!({ "inviteCode": created["inviteCode"], "playerName": "Member" }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(joined["isOwner"], false);
+    assert_eq!(joined["memberCount"], 2);
+    member
+        .invoke::<Value>(
+            "submit_global_score",
+            remote(
+                &server,
+                json!({ "entry": entry(300, 4), "replayProof": proof(300, 4, 1) }),
+            ),
+        )
+        .unwrap();
+
+    let board = owner
+        .invoke::<Vec<Value>>(
+            "fetch_group_scores",
+            remote(&server, json!({ "groupId": group_id, "mode": "classic" })),
+        )
+        .unwrap();
+    assert_eq!(board.len(), 1);
+    assert_eq!(board[0]["score"], 300);
+
+    let streaks = owner
+        .invoke::<Value>(
+            "fetch_group_streaks",
+            remote(&server, json!({ "groupId": group_id })),
+        )
+        .unwrap();
+    assert_eq!(streaks["memberCount"], 2);
+    assert_eq!(streaks["playedTodayCount"], 0);
+
+    let groups = member
+        .invoke::<Vec<Value>>("list_groups", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["inviteCode"], created["inviteCode"]);
+
+    let left = member
+        .invoke::<Vec<Value>>(
+            "leave_group",
+            remote(&server, json!({ "groupId": group_id })),
+        )
+        .unwrap();
+    assert!(left.is_empty());
+    let groups = owner
+        .invoke::<Vec<Value>>("list_groups", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(groups[0]["memberCount"], 1);
+    let error = member
+        .invoke::<Value>(
+            "fetch_group_streaks",
+            remote(&server, json!({ "groupId": group_id })),
+        )
+        .unwrap_err();
+    assert_eq!(error, "this device is not a member of that group");
+}
+
+#[test]
+fn group_member_names_follow_the_display_name_rules() {
+    let owner = TestApp::new();
+    let member = TestApp::new();
+    let server = MockSupabase::start();
+
+    owner
+        .invoke::<Value>(
+            "update_profile",
+            remote(&server, json!({ "displayName": "Yoshi" })),
+        )
+        .unwrap();
+    let created = owner
+        .invoke::<Value>(
+            "create_group",
+            remote(&server, json!({ "name": "Crew", "playerName": "Someone" })),
+        )
+        .unwrap();
+    let request = server.requests_to("/rest/v1/rpc/create_group").remove(0);
+    let body = serde_json::from_str::<Value>(&request.body).unwrap();
+    assert_eq!(body["p_player_name"], "Yoshi");
+
+    let join = |player_name: &str| {
+        member.invoke::<Value>(
+            "join_group",
+            remote(
+                &server,
+                json!({ "inviteCode": created["inviteCode"], "playerName": player_name }),
+            ),
+        )
+    };
+    assert_eq!(join("Admin").unwrap_err(), "display name is reserved");
+    assert_eq!(
+        join(" \u{200B} ").unwrap_err(),
+        "display name has no visible characters"
+    );
+    assert!(server.requests_to("/rest/v1/rpc/join_group").is_empty());
+    assert_eq!(
+        join("yoshi").unwrap_err(),
+        "another player already uses that display name"
+    );
+
+    let joined = join("  Member   With  Spaces and more ").unwrap();
+    assert_eq!(joined["memberCount"], 2);
+    let request = server.requests_to("/rest/v1/rpc/join_group").pop().unwrap();
+    let body = serde_json::from_str::<Value>(&request.body).unwrap();
+    assert_eq!(body["p_player_name"], "Member With Spaces a");
+}
+
+#[test]
+fn profile_names_are_checked_before_they_are_saved() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    server.insert(
+        "profiles",
+        json!({ "client_uuid": "rival-device", "display_name": "Rival" }),
+    );
+
+    let reserved = app
+        .invoke::<Value>(
+            "check_display_name",
+            remote(&server, json!({ "displayName": "Admin" })),
+        )
+        .unwrap();
+    assert_eq!(reserved["ok"], false);
+    assert_eq!(reserved["reason"], "reserved");
+    let accepted = app
+        .invoke::<Value>(
+            "check_display_name",
+            remote(&server, json!({ "displayName": "  Yoshi " })),
+        )
+        .unwrap();
+    assert_eq!(accepted["ok"], true);
+    assert_eq!(accepted["name"], "Yoshi");
+
+    let error = app
+        .invoke::<Value>(
+            "update_profile",
+            remote(&server, json!({ "displayName": "rival" })),
+        )
+        .unwrap_err();
+    assert_eq!(error, "another player already uses that display name");
+    let profile = app
+        .invoke::<Value>("get_profile", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(profile, Value::Null);
+
+    let saved = app
+        .invoke::<Value>(
+            "update_profile",
+            remote(
+                &server,
+                json!({ "displayName": "Yoshi", "countryCode": "kr" }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(saved["synced"], true);
+    assert_eq!(saved["countryCode"], "KR");
+    let upsert = server.requests_to(UPSERT_PROFILE_RPC_PATH).pop().unwrap();
+    let body = serde_json::from_str::<Value>(&upsert.body).unwrap();
+    assert_eq!(body["p_display_name"], "Yoshi");
+    assert_eq!(body["p_device_secret"].as_str().unwrap().len(), 64);
+
+    let profile = app.invoke::<Value>("get_profile", json!({})).unwrap();
+    assert_eq!(profile["displayName"], "Yoshi");
+}
+
+#[test]
+fn a_second_secret_cannot_claim_an_existing_scorer() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    server.insert("scores", rival_row("classic", "classic", 900, 9));
+    let claim = |client_uuid: &str| {
+        server.post(
+            UPSERT_PROFILE_RPC_PATH,
+            json!({
+                "p_client_uuid": client_uuid,
+                "p_device_secret": "f".repeat(64),
+                "p_display_name": "Mallory",
+            }),
+        )
+    };
+
+    // The rival scored before registering, so only a verified run may bind
+    // its secret.
+    let (status, body) = claim("rival-device");
+    assert_eq!(status, 400);
+    assert_eq!(body["message"], "DEVICE_NOT_REGISTERED");
+
+    let run = proof(500, 5, 1);
+    let result = app
+        .invoke::<Value>(
+            "submit_global_score",
+            remote(
+                &server,
+                json!({ "entry": entry(500, 5), "replayProof": run }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(result["accepted"], true);
+    let submitted = server.requests_to(VERIFY_SCORE_PATH).pop().unwrap();
+    let mut payload = serde_json::from_str::<Value>(&submitted.body).unwrap();
+    assert_eq!(payload["deviceSecret"].as_str().unwrap().len(), 64);
+    let owner = payload["clientUuid"].as_str().unwrap().to_string();
+
+    let (status, body) = claim(&owner);
+    assert_eq!(status, 400);
+    assert_eq!(body["message"], "DEVICE_AUTH_FAILED");
+    payload["deviceSecret"] = json!("f".repeat(64));
+    let (status, body) = server.post(VERIFY_SCORE_PATH, payload.clone());
+    assert_eq!(status, 400);
+    assert_eq!(body["detail"], "DEVICE_AUTH_FAILED");
+    payload.as_object_mut().unwrap().remove("deviceSecret");
+    let (_, body) = server.post(VERIFY_SCORE_PATH, payload);
+    assert_eq!(body["detail"], "DEVICE_AUTH_FAILED");
+
+    let saved = app
+        .invoke::<Value>(
+            "update_profile",
+            remote(&server, json!({ "displayName": "Yoshi" })),
+        )
+        .unwrap();
+    assert_eq!(saved["synced"], true);
+}
+
+#[test]
+fn reported_players_stay_hidden_until_unhidden() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    server.insert("scores", rival_row("classic", "classic", 900, 9));
+
+    let board = app
+        .invoke::<Vec<Value>>("fetch_global_scores", remote(&server, json!({})))
+        .unwrap();
+    let rival = board[0].clone();
+    assert_eq!(
+        rival["playerId"],
+        friend_code_for_device("rival-device").as_str()
+    );
+
+    let report = app
+        .invoke::<Value>(
+            "report_score",
+            remote(
+                &server,
+                json!({ "entry": rival, "mode": "classic", "reason": "Cheating" }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(report["reported"], true);
+    assert_eq!(report["hidden"], true);
+    let request = server.requests_to(REPORT_SCORE_RPC_PATH).remove(0);
+    let mut body = serde_json::from_str::<Value>(&request.body).unwrap();
+    assert_eq!(body["p_reported_player_id"], rival["playerId"]);
+    assert_eq!(body["p_reason"], "cheating");
+    assert_eq!(body["p_device_secret"].as_str().unwrap().len(), 64);
+    body["p_device_secret"] = json!("f".repeat(64));
+    let (status, forged) = server.post(REPORT_SCORE_RPC_PATH, body);
+    assert_eq!(status, 400);
+    assert_eq!(forged["message"], "DEVICE_AUTH_FAILED");
+
+    // Player ids resolve on the server, so a fresh app can report a row it
+    // never loaded itself.
+    let fresh = TestApp::new();
+    let report = fresh
+        .invoke::<Value>(
+            "report_score",
+            remote(
+                &server,
+                json!({ "entry": rival, "mode": "classic", "reason": "other", "hide": false }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(report["hidden"], false);
+
+    let board = app
+        .invoke::<Vec<Value>>("fetch_global_scores", remote(&server, json!({})))
+        .unwrap();
+    assert!(board.is_empty());
+    let hidden = app
+        .invoke::<Vec<Value>>("list_hidden_players", json!({}))
+        .unwrap();
+    assert_eq!(hidden.len(), 1);
+    assert_eq!(hidden[0]["name"], "Rival");
+
+    let hidden = app
+        .invoke::<Vec<Value>>("unhide_player", json!({ "playerId": rival["playerId"] }))
+        .unwrap();
+    assert!(hidden.is_empty());
+    let board = app
+        .invoke::<Vec<Value>>("fetch_global_scores", remote(&server, json!({})))
+        .unwrap();
+    assert_eq!(board.len(), 1);
+}
+
+#[test]
+fn rejections_export_and_replays_analyze() {
+    let app = TestApp::new();
+    let server = MockSupabase::start();
+    app.invoke::<Value>(
+        "submit_global_score",
+        remote(
+            &server,
+            json!({ "entry": entry(900, 4), "replayProof": proof(300, 4, 1) }),
+        ),
+    )
+    .unwrap();
+    let rejects = app
+        .invoke::<Vec<Value>>("list_rejected_submissions", json!({}))
+        .unwrap();
+
+    let exported = app
+        .invoke::<String>(
+            "export_rejected_submissions",
+            json!({ "ids": [rejects[0]["id"]] }),
+        )
+        .unwrap();
+    let bundle = serde_json::from_str::<Value>(&exported).unwrap();
+    assert_eq!(bundle["rejects"].as_array().unwrap().len(), 1);
+    assert_eq!(bundle["rejects"][0]["id"], rejects[0]["id"]);
+    let error = app
+        .invoke::<String>("export_rejected_submissions", json!({ "ids": ["missing"] }))
+        .unwrap_err();
+    assert_eq!(error, "no rejected submissions match the given ids");
+
+    let report = app
+        .invoke::<Value>("analyze_replay", json!({ "replayProof": proof(300, 4, 1) }))
+        .unwrap();
+    assert_eq!(report["totalMoves"], 3);
+    assert_eq!(report["maxMovesPerTick"], 1);
+}
+
+#[test]
+fn achievements_count_locally_verified_runs() {
+    let app = TestApp::new();
+    let before = app
+        .invoke::<Vec<Value>>("fetch_achievements", json!({}))
+        .unwrap();
+    assert!(before
+        .iter()
+        .all(|achievement| achievement["progress"] == 0));
+
+    let run = finished_run(3);
+    let score = run["finalScore"].as_i64().unwrap();
+    let level = run["finalLevel"].as_i64().unwrap();
+    app.invoke::<Value>(
+        "submit_global_score",
+        json!({ "entry": entry(score, level), "replayProof": run }),
+    )
+    .unwrap();
+
+    let after = app
+        .invoke::<Vec<Value>>("fetch_achievements", json!({}))
+        .unwrap();
+    let played = after
+        .iter()
+        .find(|achievement| achievement["id"] == "play-7-days")
+        .unwrap();
+    assert_eq!(played["progress"], 1);
+    assert_eq!(played["unlocked"], false);
+    let level_five = after
+        .iter()
+        .find(|achievement| achievement["id"] == "reach-level-5")
+        .unwrap();
+    assert_eq!(level_five["progress"], level.min(5));
+}
@@ -0,0 +1,1388 @@
+//! In-process stand-in for the Supabase project the app talks to: PostgREST
+//! table reads, the attempt, friend code, group, profile and report RPCs and
+//! the `verify-score` function.
+//! Tables are plain JSON rows; faults can be injected per path.
+
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const ANON_KEY: &str = "mock-anon-key";
+pub const SCORES_PATH: &str = "/rest/v1/scores";
+pub const STREAK_STATES_PATH: &str = "/rest/v1/daily_streak_states";
+pub const START_RPC_PATH: &str = "/rest/v1/rpc/start_daily_attempt";
+pub const FORFEIT_RPC_PATH: &str = "/rest/v1/rpc/forfeit_daily_attempt";
+pub const ROLLBACK_RPC_PATH: &str = "/rest/v1/rpc/rollback_daily_attempt";
+pub const HISTORY_RANKS_RPC_PATH: &str = "/rest/v1/rpc/daily_history_ranks";
+pub const UPSERT_PROFILE_RPC_PATH: &str = "/rest/v1/rpc/upsert_profile";
+pub const REPORT_SCORE_RPC_PATH: &str = "/rest/v1/rpc/report_score";
+pub const VERIFY_SCORE_PATH: &str = "/functions/v1/verify-score";
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const FRIEND_CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// How a path misbehaves instead of answering normally.
+#[derive(Debug, Clone)]
+pub enum Fault {
+    /// Answer with this status and body.
+    Status(u16, String),
+    /// Answer normally, but only after sleeping this long.
+    Delay(Duration),
+    /// Answer 200 with a body that is not valid JSON.
+    MalformedJson,
+}
+
+impl Fault {
+    /// What PostgREST answers when two RPC overloads match the same call.
+    pub fn rpc_overload() -> Self {
+        Fault::Status(
+            300,
+            json!({
+                "code": "PGRST203",
+                "details": null,
+                "hint": "Try renaming the parameters or the function itself in the database so function overloading can be resolved",
+                "message": "Could not choose the best candidate function between: public.start_daily_attempt(p_client_uuid => text, p_challenge_key => text, p_player_name => text, p_mode => text), public.start_daily_attempt(p_client_uuid => uuid, p_challenge_key => text, p_player_name => text, p_mode => text)"
+            })
+            .to_string(),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// Header names are lowercased.
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    pub fn query_value(&self, key: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Default)]
+struct State {
+    tables: HashMap<String, Vec<Value>>,
+    /// Registered device secrets, as `device_credentials` keeps their hashes.
+    device_secrets: HashMap<String, String>,
+    faults: HashMap<String, Fault>,
+    requests: Vec<RecordedRequest>,
+    next_id: i64,
+}
+
+struct Response {
+    status: u16,
+    body: String,
+    headers: Vec<(String, String)>,
+}
+
+impl Response {
+    fn json(status: u16, body: Value) -> Self {
+        Response {
+            status,
+            body: body.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// The `{ "error": CODE }` body `verify-score` answers validation failures with.
+    fn error(status: u16, error: &str) -> Self {
+        Response::json(status, json!({ "error": error }))
+    }
+}
+
+/// A running mock server. The listener thread lives until the test process
+/// exits; each test starts its own server so state never leaks between tests.
+pub struct MockSupabase {
+    url: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockSupabase {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind mock supabase");
+        let url = format!(
+            "http://{}",
+            listener.local_addr().expect("mock supabase has no address")
+        );
+        let state = Arc::new(Mutex::new(State::default()));
+        let shared = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let state = Arc::clone(&shared);
+                thread::spawn(move || serve(stream, &state));
+            }
+        });
+        MockSupabase { url, state }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Adds a row to `table`, filling in an `id` when it has none.
+    pub fn insert(&self, table: &str, mut row: Value) {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        let id = state.next_id;
+        if let Some(fields) = row.as_object_mut() {
+            fields.entry("id").or_insert(json!(id));
+        }
+        state.tables.entry(table.to_string()).or_default().push(row);
+    }
+
+    /// Makes every later request to `path` misbehave until `clear_faults`.
+    pub fn fail(&self, path: &str, fault: Fault) {
+        let mut state = self.state.lock().unwrap();
+        state.faults.insert(path.to_string(), fault);
+    }
+
+    pub fn clear_faults(&self) {
+        self.state.lock().unwrap().faults.clear();
+    }
+
+    /// Answers a POST to `path` as if another client had sent it, without
+    /// going through the app. Returns the status and the decoded body.
+    pub fn post(&self, path: &str, body: Value) -> (u16, Value) {
+        let headers = HashMap::from([
+            ("apikey".to_string(), ANON_KEY.to_string()),
+            ("authorization".to_string(), format!("Bearer {ANON_KEY}")),
+        ]);
+        let request = RecordedRequest {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            query: Vec::new(),
+            headers,
+            body: body.to_string(),
+        };
+        let response = route(&request, &self.state);
+        let body = serde_json::from_str(&response.body).unwrap_or(Value::Null);
+        (response.status, body)
+    }
+
+    pub fn requests_to(&self, path: &str) -> Vec<RecordedRequest> {
+        let state = self.state.lock().unwrap();
+        state
+            .requests
+            .iter()
+            .filter(|request| request.path == path)
+            .cloned()
+            .collect()
+    }
+}
+
+fn serve(stream: TcpStream, state: &Mutex<State>) {
+    let Some(request) = read_request(&stream) else {
+        return;
+    };
+    let fault = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+        state.faults.get(&request.path).cloned()
+    };
+    let response = match fault {
+        Some(Fault::Status(status, body)) => Response {
+            status,
+            body,
+            headers: Vec::new(),
+        },
+        Some(Fault::MalformedJson) => Response {
+            status: 200,
+            body: "[{\"player_name\": \"Trunc".to_string(),
+            headers: Vec::new(),
+        },
+        Some(Fault::Delay(delay)) => {
+            thread::sleep(delay);
+            route(&request, state)
+        }
+        None => route(&request, state),
+    };
+    // The client may have hung up already (timeouts); nothing left to do then.
+    let _ = write_response(stream, &request.method, response);
+}
+
+fn read_request(stream: &TcpStream) -> Option<RecordedRequest> {
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = headers
+        .get("content-length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+
+    let (path, raw_query) = target.split_once('?').unwrap_or((&target, ""));
+    Some(RecordedRequest {
+        method,
+        path: path.to_string(),
+        query: parse_query(raw_query),
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn write_response(mut stream: TcpStream, method: &str, response: Response) -> std::io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nDate: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len(),
+        http_date(now_seconds())
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    if method != "HEAD" {
+        stream.write_all(response.body.as_bytes())?;
+    }
+    stream.flush()
+}
+
+fn route(request: &RecordedRequest, state: &Mutex<State>) -> Response {
+    let authorized = request.headers.get("apikey").map(String::as_str) == Some(ANON_KEY)
+        && request.headers.get("authorization").map(String::as_str)
+            == Some(format!("Bearer {ANON_KEY}").as_str());
+    if !authorized {
+        return Response::json(401, json!({ "message": "Invalid API key" }));
+    }
+
+    let mut state = state.lock().unwrap();
+    if request.path == VERIFY_SCORE_PATH {
+        return verify_score(&mut state, &request.body);
+    }
+    if let Some(function) = request.path.strip_prefix("/rest/v1/rpc/") {
+        let payload = serde_json::from_str::<Value>(&request.body).unwrap_or(Value::Null);
+        return match function {
+            "start_daily_attempt" => start_attempt(&mut state, &payload),
+            "forfeit_daily_attempt" => end_attempt(&mut state, &payload, false),
+            "rollback_daily_attempt" => end_attempt(&mut state, &payload, true),
+            "daily_history_ranks" => history_ranks(&state, &payload),
+            "publish_friend_code" => publish_friend_code(&mut state, &payload),
+            "resolve_friend_codes" => resolve_friend_codes(&state, &payload),
+            "create_group" => create_group(&mut state, &payload),
+            "join_group" => join_group(&mut state, &payload),
+            "leave_group" => leave_group(&mut state, &payload),
+            "list_group_members" => list_group_members(&mut state, &payload),
+            "list_my_groups" => list_my_groups(&mut state, &payload),
+            "upsert_profile" => upsert_profile(&mut state, &payload),
+            "report_score" => report_score(&mut state, &payload),
+            _ => Response::json(
+                404,
+                json!({
+                    "code": "PGRST202",
+                    "message": format!("Could not find the function public.{function}"),
+                }),
+            ),
+        };
+    }
+    if let Some(table) = request.path.strip_prefix("/rest/v1/") {
+        if request.method == "GET" || request.method == "HEAD" {
+            return select_rows(&state, table, request);
+        }
+    }
+    Response::json(404, json!({ "message": "not found" }))
+}
+
+/// PostgREST reads: `col=op.value` filters, `or=(...)`, `order`, `limit`,
+/// `select` and `Prefer: count=exact`.
+fn select_rows(state: &State, table: &str, request: &RecordedRequest) -> Response {
+    let mut filters = Vec::new();
+    let mut select = None;
+    let mut order = None;
+    let mut limit = None;
+    for (key, value) in &request.query {
+        match key.as_str() {
+            "select" => select = Some(value.clone()),
+            "order" => order = Some(value.clone()),
+            "limit" => limit = value.parse::<usize>().ok(),
+            "or" => filters.push(Condition::Any(parse_condition_list(
+                value.trim_start_matches('(').trim_end_matches(')'),
+            ))),
+            column => match parse_filter(column, value) {
+                Some(condition) => filters.push(condition),
+                None => {
+                    return Response::json(
+                        400,
+                        json!({ "code": "PGRST100", "message": format!("failed to parse filter {column}={value}") }),
+                    );
+                }
+            },
+        }
+    }
+
+    let mut rows = state
+        .tables
+        .get(table)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|row| filters.iter().all(|filter| filter.matches(row)))
+        .collect::<Vec<_>>();
+    if let Some(order) = order {
+        let keys = order
+            .split(',')
+            .map(|item| match item.rsplit_once('.') {
+                Some((column, "desc")) => (column.to_string(), true),
+                Some((column, "asc")) => (column.to_string(), false),
+                _ => (item.to_string(), false),
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by(|left, right| {
+            keys.iter()
+                .map(|(column, descending)| {
+                    let ordering = compare_values(&left[column], &right[column]);
+                    if *descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+    let total = rows.len();
+    rows.truncate(limit.unwrap_or(total));
+
+    if let Some(select) = select.filter(|value| value != "*") {
+        let columns = select.split(',').map(str::trim).collect::<Vec<_>>();
+        rows = rows
+            .into_iter()
+            .map(|row| {
+                Value::Object(
+                    columns
+                        .iter()
+                        .map(|column| (column.to_string(), row[*column].clone()))
+                        .collect::<Map<_, _>>(),
+                )
+            })
+            .collect();
+    }
+
+    let mut response = Response::json(200, Value::Array(rows.clone()));
+    let wants_count = request
+        .headers
+        .get("prefer")
+        .is_some_and(|value| value.contains("count=exact"));
+    if wants_count {
+        let range = if rows.is_empty() {
+            format!("*/{total}")
+        } else {
+            format!("0-{}/{total}", rows.len() - 1)
+        };
+        response.headers.push(("Content-Range".to_string(), range));
+    }
+    response
+}
+
+enum Condition {
+    Compare {
+        column: String,
+        operator: String,
+        value: String,
+    },
+    Any(Vec<Condition>),
+    All(Vec<Condition>),
+}
+
+impl Condition {
+    fn matches(&self, row: &Value) -> bool {
+        match self {
+            Condition::Compare {
+                column,
+                operator,
+                value,
+            } => {
+                let ordering = compare_field(&row[column.as_str()], value);
+                match (operator.as_str(), ordering) {
+                    (_, None) => false,
+                    ("eq", Some(ordering)) => ordering == Ordering::Equal,
+                    ("neq", Some(ordering)) => ordering != Ordering::Equal,
+                    ("gt", Some(ordering)) => ordering == Ordering::Greater,
+                    ("gte", Some(ordering)) => ordering != Ordering::Less,
+                    ("lt", Some(ordering)) => ordering == Ordering::Less,
+                    ("lte", Some(ordering)) => ordering != Ordering::Greater,
+                    _ => false,
+                }
+            }
+            Condition::Any(conditions) => conditions.iter().any(|item| item.matches(row)),
+            Condition::All(conditions) => conditions.iter().all(|item| item.matches(row)),
+        }
+    }
+}
+
+/// `eq.value` or `in.("a","b")` on `column`.
+fn parse_filter(column: &str, raw: &str) -> Option<Condition> {
+    let (operator, value) = raw.split_once('.')?;
+    if operator == "in" {
+        let list = value.strip_prefix('(')?.strip_suffix(')')?;
+        return Some(Condition::Any(
+            split_top_level(list)
+                .into_iter()
+                .map(|item| Condition::Compare {
+                    column: column.to_string(),
+                    operator: "eq".to_string(),
+                    value: unquote(&item),
+                })
+                .collect(),
+        ));
+    }
+    Some(Condition::Compare {
+        column: column.to_string(),
+        operator: operator.to_string(),
+        value: unquote(value),
+    })
+}
+
+/// Items of an `or=(...)` list: `col.op.value`, `and(...)` or `or(...)`.
+fn parse_condition_list(raw: &str) -> Vec<Condition> {
+    split_top_level(raw)
+        .into_iter()
+        .filter_map(|item| {
+            if let Some(inner) = item
+                .strip_prefix("and(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                return Some(Condition::All(parse_condition_list(inner)));
+            }
+            if let Some(inner) = item
+                .strip_prefix("or(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                return Some(Condition::Any(parse_condition_list(inner)));
+            }
+            let (column, rest) = item.split_once('.')?;
+            parse_filter(column, rest)
+        })
+        .collect()
+}
+
+/// Splits on commas outside parentheses and double quotes.
+fn split_top_level(raw: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for ch in raw.chars() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' && quoted {
+            escaped = true;
+        } else if ch == '"' {
+            quoted = !quoted;
+        } else if !quoted && ch == '(' {
+            depth += 1;
+        } else if !quoted && ch == ')' {
+            depth -= 1;
+        } else if !quoted && depth == 0 && ch == ',' {
+            items.push(std::mem::take(&mut current));
+            continue;
+        }
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        items.push(current);
+    }
+    items
+}
+
+fn unquote(raw: &str) -> String {
+    let Some(inner) = raw
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return raw.to_string();
+    };
+    let mut value = String::new();
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            value.extend(chars.next());
+        } else {
+            value.push(ch);
+        }
+    }
+    value
+}
+
+/// Orders a row field against a filter literal the way Postgres would for
+/// the column's type; `None` when they cannot be compared (e.g. NULL).
+fn compare_field(field: &Value, literal: &str) -> Option<Ordering> {
+    match field {
+        Value::Number(number) => number.as_f64()?.partial_cmp(&literal.parse::<f64>().ok()?),
+        Value::String(text) => Some(text.as_str().cmp(literal)),
+        Value::Bool(flag) => Some(flag.to_string().as_str().cmp(literal)),
+        _ => None,
+    }
+}
+
+fn compare_values(left: &Value, right: &Value) -> Ordering {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left
+            .as_f64()
+            .partial_cmp(&right.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(left), Value::String(right)) => left.cmp(right),
+        (Value::Null, Value::Null) => Ordering::Equal,
+        // Postgres sorts NULLs last in ascending order.
+        (Value::Null, _) => Ordering::Greater,
+        (_, Value::Null) => Ordering::Less,
+        _ => Ordering::Equal,
+    }
+}
+
+fn default_max_attempts(mode: &str) -> i64 {
+    match mode {
+        "weekly" => 5,
+        "monthly" => 10,
+        _ => 3,
+    }
+}
+
+fn max_attempts(state: &State, mode: &str, challenge_key: &str) -> i64 {
+    state
+        .tables
+        .get("challenge_settings")
+        .into_iter()
+        .flatten()
+        .find(|row| row["mode"] == mode && row["challenge_key"] == challenge_key)
+        .and_then(|row| row["max_attempts"].as_i64())
+        .unwrap_or_else(|| default_max_attempts(mode))
+}
+
+fn find_score_row(
+    state: &State,
+    mode: &str,
+    challenge_key: &str,
+    client_uuid: &str,
+) -> Option<usize> {
+    state.tables.get("scores")?.iter().position(|row| {
+        row["mode"] == mode
+            && row["challenge_key"] == challenge_key
+            && row["client_uuid"] == client_uuid
+    })
+}
+
+fn attempt_summary(row: &Value, max_attempts: i64) -> Map<String, Value> {
+    let attempts_used = row["attempts_used"].as_i64().unwrap_or(0);
+    let attempts_left = (max_attempts - attempts_used).max(0);
+    let has_active_attempt = row["active_attempt_token"]
+        .as_str()
+        .is_some_and(|token| !token.is_empty());
+    let summary = json!({
+        "challengeKey": row["challenge_key"],
+        "attemptsUsed": attempts_used,
+        "attemptsLeft": attempts_left,
+        "maxAttempts": max_attempts,
+        "canSubmit": attempts_left > 0,
+        "hasActiveAttempt": has_active_attempt,
+    });
+    summary.as_object().cloned().unwrap_or_default()
+}
+
+fn start_attempt(state: &mut State, payload: &Value) -> Response {
+    let (Some(mode), Some(challenge_key), Some(client_uuid)) = (
+        payload["p_mode"].as_str(),
+        payload["p_challenge_key"].as_str(),
+        payload["p_client_uuid"].as_str(),
+    ) else {
+        return Response::json(
+            400,
+            json!({ "code": "PGRST202", "message": "missing arguments" }),
+        );
+    };
+    let max_attempts = max_attempts(state, mode, challenge_key);
+    let index = match find_score_row(state, mode, challenge_key, client_uuid) {
+        Some(index) => index,
+        None => {
+            state.next_id += 1;
+            let row = json!({
+                "id": state.next_id,
+                "mode": mode,
+                "challenge_key": challenge_key,
+                "client_uuid": client_uuid,
+                "player_name": payload["p_player_name"],
+                "score": 0,
+                "level": 0,
+                "created_at": iso_timestamp(now_seconds()),
+                "skill_usage": [],
+                "attempts_used": 0,
+                "active_attempt_token": null,
+                "daily_has_submission": false,
+            });
+            let rows = state.tables.entry("scores".to_string()).or_default();
+            rows.push(row);
+            rows.len() - 1
+        }
+    };
+
+    state.next_id += 1;
+    let next_token = format!("attempt-{}", state.next_id);
+    let row = &mut state.tables.get_mut("scores").unwrap()[index];
+    let active = row["active_attempt_token"]
+        .as_str()
+        .filter(|token| !token.is_empty())
+        .map(str::to_string);
+    let attempts_used = row["attempts_used"].as_i64().unwrap_or(0);
+    let (accepted, resumed, token) = match active {
+        Some(token) => (true, true, Some(token)),
+        None if attempts_used >= max_attempts => (false, false, None),
+        None => {
+            row["attempts_used"] = json!(attempts_used + 1);
+            row["active_attempt_token"] = json!(next_token);
+            (true, false, Some(next_token))
+        }
+    };
+
+    let mut body = attempt_summary(row, max_attempts);
+    body.insert("accepted".to_string(), json!(accepted));
+    body.insert("resumed".to_string(), json!(resumed));
+    body.insert("attemptToken".to_string(), json!(token));
+    Response::json(200, Value::Object(body))
+}
+
+/// Forfeit ends the active attempt; rollback also refunds it.
+fn end_attempt(state: &mut State, payload: &Value, refund: bool) -> Response {
+    let mode = payload["p_mode"].as_str().unwrap_or_default();
+    let challenge_key = payload["p_challenge_key"].as_str().unwrap_or_default();
+    let client_uuid = payload["p_client_uuid"].as_str().unwrap_or_default();
+    let token = payload["p_attempt_token"].as_str().unwrap_or_default();
+    let max_attempts = max_attempts(state, mode, challenge_key);
+    let Some(index) = find_score_row(state, mode, challenge_key, client_uuid) else {
+        let mut body = attempt_summary(&json!({ "challenge_key": challenge_key }), max_attempts);
+        body.insert("accepted".to_string(), json!(false));
+        return Response::json(200, Value::Object(body));
+    };
+
+    let row = &mut state.tables.get_mut("scores").unwrap()[index];
+    let accepted = row["active_attempt_token"].as_str() == Some(token);
+    if accepted {
+        row["active_attempt_token"] = Value::Null;
+        if refund {
+            let attempts_used = row["attempts_used"].as_i64().unwrap_or(0);
+            row["attempts_used"] = json!((attempts_used - 1).max(0));
+        }
+    }
+    let mut body = attempt_summary(row, max_attempts);
+    body.insert("accepted".to_string(), json!(accepted));
+    Response::json(200, Value::Object(body))
+}
+
+/// Rank of each of the caller's submitted days among that day's submissions.
+fn history_ranks(state: &State, payload: &Value) -> Response {
+    let client_uuid = payload["p_client_uuid"].as_str().unwrap_or_default();
+    let keys = payload["p_challenge_keys"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let rows = state.tables.get("scores").cloned().unwrap_or_default();
+    let board_order = |row: &Value, other: &Value| {
+        compare_values(&row["score"], &other["score"])
+            .then(compare_values(&row["level"], &other["level"]))
+            .then(compare_values(&row["created_at"], &other["created_at"]))
+    };
+    let ranks = rows
+        .iter()
+        .filter(|row| {
+            row["mode"] == "daily"
+                && row["client_uuid"] == client_uuid
+                && row["daily_has_submission"] == true
+                && keys.contains(&row["challenge_key"])
+        })
+        .map(|mine| {
+            let ahead = rows
+                .iter()
+                .filter(|other| {
+                    other["mode"] == "daily"
+                        && other["challenge_key"] == mine["challenge_key"]
+                        && other["daily_has_submission"] == true
+                        && board_order(other, mine) == Ordering::Greater
+                })
+                .count();
+            json!({ "challenge_key": mine["challenge_key"], "rank": ahead + 1 })
+        })
+        .collect();
+    Response::json(200, Value::Array(ranks))
+}
+
+/// What PostgREST answers when a function raises `exception`.
+fn raise(exception: &str) -> Response {
+    Response::json(
+        400,
+        json!({ "code": "P0001", "details": null, "hint": null, "message": exception }),
+    )
+}
+
+/// Like `authenticate_device`: the first secret seen for a device registers
+/// it, later calls must present the same one. A device that already has
+/// scores can only be registered by a verified submission.
+fn authenticate_device(state: &mut State, payload: &Value) -> Result<String, Response> {
+    let client_uuid = payload["p_client_uuid"]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let has_scores = state
+        .tables
+        .get("scores")
+        .is_some_and(|rows| rows.iter().any(|row| row["client_uuid"] == client_uuid));
+    if has_scores && !state.device_secrets.contains_key(&client_uuid) {
+        return Err(raise("DEVICE_NOT_REGISTERED"));
+    }
+    let secret = payload["p_device_secret"].as_str().unwrap_or_default();
+    bind_device_credential(state, &client_uuid, secret).map_err(raise)?;
+    Ok(client_uuid)
+}
+
+/// Like `bind_device_credential`: registers `secret` for a device that has
+/// none, then requires it. Errors are the exception the function raises.
+fn bind_device_credential(
+    state: &mut State,
+    client_uuid: &str,
+    secret: &str,
+) -> Result<(), &'static str> {
+    if client_uuid.chars().count() < 8 {
+        return Err("INVALID_CLIENT_UUID");
+    }
+    if secret.len() != 64
+        || !secret
+            .bytes()
+            .all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+    {
+        return Err("INVALID_DEVICE_SECRET");
+    }
+    let registered = state
+        .device_secrets
+        .entry(client_uuid.to_string())
+        .or_insert_with(|| secret.to_string());
+    if registered != secret {
+        return Err("DEVICE_AUTH_FAILED");
+    }
+    Ok(())
+}
+
+/// Same derivation as `public.friend_code_for_device`.
+pub fn friend_code_for_device(client_uuid: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update("torus-friend-v1:")
+        .chain_update(client_uuid)
+        .finalize();
+    let bits = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    let code = (0..10)
+        .map(|index| FRIEND_CODE_ALPHABET[((bits >> (59 - 5 * index)) & 31) as usize] as char)
+        .collect::<String>();
+    format!("{}-{}", &code[..5], &code[5..])
+}
+
+fn publish_friend_code(state: &mut State, payload: &Value) -> Response {
+    let client_uuid = match authenticate_device(state, payload) {
+        Ok(client_uuid) => client_uuid,
+        Err(response) => return response,
+    };
+    let friend_code = friend_code_for_device(&client_uuid);
+    let rows = state.tables.entry("friend_codes".to_string()).or_default();
+    rows.retain(|row| row["client_uuid"] != client_uuid.as_str());
+    rows.push(json!({ "friend_code": friend_code, "client_uuid": client_uuid }));
+    Response::json(200, json!({ "friendCode": friend_code }))
+}
+
+fn resolve_friend_codes(state: &State, payload: &Value) -> Response {
+    let codes = payload["p_friend_codes"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let rows = state
+        .tables
+        .get("friend_codes")
+        .into_iter()
+        .flatten()
+        .filter(|row| codes.contains(&row["friend_code"]))
+        .cloned()
+        .collect();
+    Response::json(200, Value::Array(rows))
+}
+
+fn group_members<'a>(state: &'a State, group_id: &'a str) -> impl Iterator<Item = &'a Value> {
+    state
+        .tables
+        .get("group_members")
+        .into_iter()
+        .flatten()
+        .filter(move |member| member["group_id"] == group_id)
+}
+
+fn is_group_member(state: &State, group_id: &str, client_uuid: &str) -> bool {
+    group_members(state, group_id).any(|member| member["client_uuid"] == client_uuid)
+}
+
+/// `group_summary(...) || {inviteCode}` for `create_group` and `join_group`.
+fn group_summary(state: &State, group_id: &str, with_invite_code: bool) -> Value {
+    let Some(group) = state
+        .tables
+        .get("groups")
+        .into_iter()
+        .flatten()
+        .find(|group| group["id"] == group_id)
+    else {
+        return Value::Null;
+    };
+    let mut summary = json!({
+        "groupId": group["id"],
+        "name": group["name"],
+        "ownerClientUuid": group["owner_client_uuid"],
+        "memberCount": group_members(state, group_id).count(),
+    });
+    if with_invite_code {
+        summary["inviteCode"] = group["invite_code"].clone();
+    }
+    summary
+}
+
+/// Like `group_member_name`: the profile name once the device has one,
+/// otherwise the requested name under the profile name checks.
+fn group_member_name(
+    state: &State,
+    client_uuid: &str,
+    payload: &Value,
+) -> Result<String, Response> {
+    let profile_name = state
+        .tables
+        .get("profiles")
+        .into_iter()
+        .flatten()
+        .find(|profile| profile["client_uuid"] == client_uuid)
+        .and_then(|profile| profile["display_name"].as_str());
+    if let Some(name) = profile_name {
+        return Ok(name.to_string());
+    }
+    let name = payload["p_player_name"]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(20)
+        .collect::<String>();
+    if name.is_empty() {
+        return Err(raise("INVALID_PLAYER_NAME"));
+    }
+    if name_taken(state, client_uuid, &name) {
+        return Err(raise("NAME_TAKEN"));
+    }
+    Ok(name)
+}
+
+/// Whether another device's profile uses `name` in any letter case.
+fn name_taken(state: &State, client_uuid: &str, name: &str) -> bool {
+    let folded = name.to_lowercase();
+    state
+        .tables
+        .get("profiles")
+        .into_iter()
+        .flatten()
+        .any(|profile| {
+            profile["client_uuid"] != client_uuid
+                && profile["display_name"].as_str().map(str::to_lowercase) == Some(folded.clone())
+        })
+}
+
+fn create_group(state: &mut State, payload: &Value) -> Response {
+    let client_uuid = match authenticate_device(state, payload) {
+        Ok(client_uuid) => client_uuid,
+        Err(response) => return response,
+    };
+    let player_name = match group_member_name(state, &client_uuid, payload) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+    let name = payload["p_name"].as_str().unwrap_or_default().trim();
+    if name.is_empty() || name.chars().count() > 32 {
+        return raise("INVALID_GROUP_NAME");
+    }
+    let group_id = uuid::Uuid::new_v4().to_string();
+    let hex = uuid::Uuid::new_v4()
+        .simple()
+        .to_string()
+        .to_ascii_uppercase();
+    let invite_code = format!("{}-{}", &hex[..4], &hex[4..8]);
+    state
+        .tables
+        .entry("groups".to_string())
+        .or_default()
+        .push(json!({
+            "id": group_id,
+            "name": name,
+            "invite_code": invite_code,
+            "owner_client_uuid": client_uuid,
+        }));
+    state
+        .tables
+        .entry("group_members".to_string())
+        .or_default()
+        .push(json!({
+            "group_id": group_id,
+            "client_uuid": client_uuid,
+            "player_name": player_name,
+        }));
+    Response::json(200, group_summary(state, &group_id, true))
+}
+
+fn join_group(state: &mut State, payload: &Value) -> Response {
+    let client_uuid = match authenticate_device(state, payload) {
+        Ok(client_uuid) => client_uuid,
+        Err(response) => return response,
+    };
+    let player_name = match group_member_name(state, &client_uuid, payload) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+    let invite_code = payload["p_invite_code"]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_uppercase();
+    let Some(group_id) = state
+        .tables
+        .get("groups")
+        .into_iter()
+        .flatten()
+        .find(|group| group["invite_code"] == invite_code.as_str())
+        .and_then(|group| group["id"].as_str())
+        .map(str::to_string)
+    else {
+        return raise("GROUP_NOT_FOUND");
+    };
+    let members = state.tables.entry("group_members".to_string()).or_default();
+    match members.iter_mut().find(|member| {
+        member["group_id"] == group_id.as_str() && member["client_uuid"] == client_uuid.as_str()
+    }) {
+        Some(member) => member["player_name"] = json!(player_name),
+        None => members.push(json!({
+            "group_id": group_id,
+            "client_uuid": client_uuid,
+            "player_name": player_name,
+        })),
+    }
+    Response::json(200, group_summary(state, &group_id, true))
+}
+
+/// Ownership passes to the longest-standing member; the last one out deletes
+/// the group.
+fn leave_group(state: &mut State, payload: &Value) -> Response {
+    let client_uuid = match authenticate_device(state, payload) {
+        Ok(client_uuid) => client_uuid,
+        Err(response) => return response,
+    };
+    let group_id = payload["p_group_id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    if group_summary(state, &group_id, false).is_null() {
+        return raise("GROUP_NOT_FOUND");
+    }
+    if !is_group_member(state, &group_id, &client_uuid) {
+        return raise("NOT_A_MEMBER");
+    }
+    let members = state.tables.entry("group_members".to_string()).or_default();
+    members.retain(|member| {
+        member["group_id"] != group_id.as_str() || member["client_uuid"] != client_uuid.as_str()
+    });
+    let next_owner = group_members(state, &group_id)
+        .next()
+        .map(|member| member["client_uuid"].clone());
+    let groups = state.tables.entry("groups".to_string()).or_default();
+    let deleted = match next_owner {
+        None => {
+            groups.retain(|group| group["id"] != group_id.as_str());
+            true
+        }
+        Some(next_owner) => {
+            for group in groups.iter_mut() {
+                if group["id"] == group_id.as_str()
+                    && group["owner_client_uuid"] == client_uuid.as_str()
+                {
+                    group["owner_client_uuid"] = next_owner.clone();
+                }
+            }
+            false
+        }
+    };
+    Response::json(200, json!({ "groupId": group_id, "deleted": deleted }))
+}
+
+fn list_group_members(state: &mut State, payload: &Value) -> Response {
+    let client_uuid = match authenticate_device(state, payload) {
+        Ok(client_uuid) => client_uuid,
+        Err(response) => return response,
+    };
+    let group_id = payload["p_group_id"].as_str().unwrap_or_default();
+    if !is_group_member(state, group_id, &client_uuid) {
+        return raise("NOT_A_MEMBER");
+    }
+    let members = group_members(state, group_id)
+        .map(|member| {
+            json!({ "client_uuid": member["client_uuid"], "player_name": member["player_name"] })
+        })
+        .collect();
+    Response::json(200, Value::Array(members))
+}
+
+fn list_my_groups(state: &mut State, payload: &Value) -> Response {
+    let client_uuid = match authenticate_device(state, payload) {
+        Ok(client_uuid) => client_uuid,
+        Err(response) => return response,
+    };
+    let groups = state
+        .tables
+        .get("group_members")
+        .into_iter()
+        .flatten()
+        .filter(|member| member["client_uuid"] == client_uuid.as_str())
+        .filter_map(|member| member["group_id"].as_str())
+        .map(|group_id| group_summary(state, group_id, false))
+        .collect();
+    Response::json(200, Value::Array(groups))
+}
+
+/// Name checks are reduced to `NAME_TAKEN` for another device's name in any
+/// letter case; renames carry over to the device's score rows.
+fn upsert_profile(state: &mut State, payload: &Value) -> Response {
+    let client_uuid = match authenticate_device(state, payload) {
+        Ok(client_uuid) => client_uuid,
+        Err(response) => return response,
+    };
+    let display_name = payload["p_display_name"]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if display_name.is_empty() {
+        return raise("INVALID_PLAYER_NAME");
+    }
+    if name_taken(state, &client_uuid, &display_name) {
+        return raise("NAME_TAKEN");
+    }
+    let profiles = state.tables.entry("profiles".to_string()).or_default();
+    profiles.retain(|profile| profile["client_uuid"] != client_uuid.as_str());
+    let created_at = iso_timestamp(now_seconds());
+    profiles.push(json!({
+        "client_uuid": client_uuid,
+        "display_name": display_name,
+        "country_code": payload["p_country_code"],
+        "created_at": created_at,
+    }));
+    for row in state.tables.entry("scores".to_string()).or_default() {
+        if row["client_uuid"] == client_uuid.as_str() {
+            row["player_name"] = json!(display_name);
+        }
+    }
+    Response::json(
+        200,
+        json!({
+            "displayName": display_name,
+            "countryCode": payload["p_country_code"],
+            "createdAt": created_at,
+        }),
+    )
+}
+
+/// Reports land in `score_reports`; the daily report limit is not modelled.
+fn report_score(state: &mut State, payload: &Value) -> Response {
+    let credentials = json!({
+        "p_client_uuid": payload["p_reporter_client_uuid"],
+        "p_device_secret": payload["p_device_secret"],
+    });
+    let reporter = match authenticate_device(state, &credentials) {
+        Ok(client_uuid) => client_uuid,
+        Err(response) => return response,
+    };
+    let player_id = payload["p_reported_player_id"]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_uppercase();
+    let mode = payload["p_mode"].as_str().unwrap_or_default();
+    let challenge_key = payload["p_challenge_key"].as_str().unwrap_or_default();
+    let Some(reported) = state
+        .tables
+        .get("scores")
+        .into_iter()
+        .flatten()
+        .filter(|row| row["mode"] == mode && row["challenge_key"] == challenge_key)
+        .filter_map(|row| row["client_uuid"].as_str())
+        .find(|client_uuid| friend_code_for_device(client_uuid) == player_id)
+        .map(str::to_string)
+    else {
+        return raise("SCORE_NOT_FOUND");
+    };
+    if reporter == reported {
+        return raise("CANNOT_REPORT_SELF");
+    }
+    let mut report = payload.clone();
+    report["reported_client_uuid"] = json!(reported);
+    state
+        .tables
+        .entry("score_reports".to_string())
+        .or_default()
+        .push(report);
+    Response::json(200, json!({ "reported": true }))
+}
+
+/// Accepts any proof whose final score and level match the entry; the real
+/// function also re-simulates the replay. Status codes and bodies follow
+/// `supabase/functions/verify-score` and the `submit_*_score` RPCs.
+fn verify_score(state: &mut State, raw: &str) -> Response {
+    let Ok(payload) = serde_json::from_str::<Value>(raw) else {
+        return Response::error(400, "INVALID_JSON");
+    };
+    let (Some(mode), Some(challenge_key), Some(client_uuid)) = (
+        payload["mode"].as_str(),
+        payload["challengeKey"].as_str(),
+        payload["clientUuid"].as_str(),
+    ) else {
+        return Response::error(400, "INVALID_PAYLOAD");
+    };
+    let entry = &payload["entry"];
+    let proof = &payload["replayProof"];
+    let score = entry["score"].as_i64().unwrap_or(-1);
+    let level = entry["level"].as_i64().unwrap_or(-1);
+    if proof["finalScore"].as_i64() != Some(score) || proof["finalLevel"].as_i64() != Some(level) {
+        return Response::error(400, "ENTRY_REPLAY_MISMATCH");
+    }
+    // `authenticate_submission`, which the submit RPCs run first.
+    let authenticated = match payload["deviceSecret"].as_str() {
+        Some(secret) => bind_device_credential(state, client_uuid, secret),
+        None if state.device_secrets.contains_key(client_uuid) => Err("DEVICE_AUTH_FAILED"),
+        None => Ok(()),
+    };
+    if let Err(exception) = authenticated {
+        let rpc = if mode == "classic" { "GLOBAL" } else { "DAILY" };
+        return Response::json(
+            400,
+            json!({
+                "error": format!("RPC_SUBMIT_{rpc}_SCORE_FAILED"),
+                "detail": exception,
+                "hint": null,
+                "code": "P0001",
+            }),
+        );
+    }
+
+    let existing = find_score_row(state, mode, challenge_key, client_uuid);
+    if mode != "classic" {
+        let token = payload["attemptToken"]
+            .as_str()
+            .map(str::trim)
+            .unwrap_or_default();
+        if token.is_empty() {
+            return Response::error(400, "INVALID_ATTEMPT_TOKEN");
+        }
+        // Like `submit_daily_score`, a missing or stale attempt is a 200 answer
+        // with `accepted: false`, not an error.
+        let max_attempts = max_attempts(state, mode, challenge_key);
+        let row = match existing {
+            Some(index) => state.tables["scores"][index].clone(),
+            None => json!({ "challenge_key": challenge_key }),
+        };
+        if row["active_attempt_token"].as_str() != Some(token) {
+            let mut body = attempt_summary(&row, max_attempts);
+            body.insert("accepted".to_string(), json!(false));
+            body.insert("improved".to_string(), json!(false));
+            return Response::json(200, Value::Object(body));
+        }
+    }
+
+    let index = match existing {
+        Some(index) => index,
+        None => {
+            state.next_id += 1;
+            let row = json!({
+                "id": state.next_id,
+                "mode": mode,
+                "challenge_key": challenge_key,
+                "client_uuid": client_uuid,
+                "attempts_used": 0,
+                "active_attempt_token": null,
+                "daily_has_submission": false,
+            });
+            let rows = state.tables.entry("scores".to_string()).or_default();
+            rows.push(row);
+            rows.len() - 1
+        }
+    };
+    let max_attempts = max_attempts(state, mode, challenge_key);
+    let rows = state.tables.get_mut("scores").unwrap();
+    let row = &mut rows[index];
+    let previous = row["daily_has_submission"]
+        .as_bool()
+        .unwrap_or(false)
+        .then(|| {
+            (
+                row["score"].as_i64().unwrap_or(0),
+                row["level"].as_i64().unwrap_or(0),
+            )
+        });
+    let improved = previous.is_none_or(|best| (score, level) > best);
+    if improved {
+        row["score"] = json!(score);
+        row["level"] = json!(level);
+        row["player_name"] = entry["user"].clone();
+        row["skill_usage"] = entry["skillUsage"].clone();
+        row["created_at"] = json!(iso_timestamp(now_seconds()));
+    }
+    row["daily_has_submission"] = json!(true);
+    row["active_attempt_token"] = Value::Null;
+
+    if mode == "classic" {
+        let (best_score, best_level) = (row["score"].clone(), row["level"].clone());
+        let board = rows
+            .iter()
+            .filter(|row| row["mode"] == "classic" && row["daily_has_submission"] == true);
+        let ahead = board
+            .clone()
+            .filter(|row| {
+                compare_values(&row["score"], &best_score)
+                    .then(compare_values(&row["level"], &best_level))
+                    == Ordering::Greater
+            })
+            .count();
+        return Response::json(
+            200,
+            json!({
+                "accepted": true,
+                "improved": improved,
+                "previousBest": previous.map(|(score, _)| score),
+                "rank": ahead + 1,
+                "totalPlayers": board.count(),
+            }),
+        );
+    }
+
+    let mut body = attempt_summary(&rows[index], max_attempts);
+    body.insert("accepted".to_string(), json!(true));
+    body.insert("improved".to_string(), json!(improved));
+    Response::json(200, Value::Object(body))
+}
+
+fn parse_query(raw: &str) -> Vec<(String, String)> {
+    raw.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' if index + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        index += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+pub fn now_seconds() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// `YYYY-MM-DD` key of the UTC day `seconds` falls in.
+pub fn day_key(seconds: i64) -> String {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn iso_timestamp(seconds: i64) -> String {
+    let clock = seconds.rem_euclid(SECONDS_PER_DAY);
+    format!(
+        "{}T{:02}:{:02}:{:02}.000Z",
+        day_key(seconds),
+        clock / 3_600,
+        clock % 3_600 / 60,
+        clock % 60
+    )
+}
+
+fn http_date(seconds: i64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let days = seconds.div_euclid(SECONDS_PER_DAY);
+    let clock = seconds.rem_euclid(SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{}, {day:02} {} {year:04} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[days.rem_euclid(7) as usize],
+        MONTHS[month as usize - 1],
+        clock / 3_600,
+        clock % 3_600 / 60,
+        clock % 60
+    )
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,80 @@
+// Only some test binaries talk to the Supabase stand-in.
+#[allow(dead_code)]
+pub mod mock_supabase;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+use std::time::Duration;
+use tauri::ipc::{CallbackFn, InvokeBody};
+use tauri::test::{
+    get_ipc_response, mock_builder, mock_context, noop_assets, MockRuntime, INVOKE_KEY,
+};
+use tauri::webview::InvokeRequest;
+use tauri::{App, WebviewWindow, WebviewWindowBuilder};
+use torus_app_lib::http_client::HttpClientSettings;
+
+static DATA_HOME: Once = Once::new();
+static NEXT_APP_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Request timeout for test apps, short so `Fault::Delay` tests stay quick.
+pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// An app on Tauri's mock runtime with every command registered. Each one gets
+/// its own identifier, hence its own app data dir, device UUID and caches.
+pub struct TestApp {
+    _app: App<MockRuntime>,
+    webview: WebviewWindow<MockRuntime>,
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        // Keep app data out of the real home directory. Set once, before any
+        // app exists, because tests run on parallel threads.
+        DATA_HOME.call_once(|| {
+            let root = std::env::temp_dir().join(format!("torus-app-tests-{}", std::process::id()));
+            std::fs::create_dir_all(&root).expect("failed to create test data dir");
+            std::env::set_var("HOME", &root);
+            std::env::set_var("XDG_DATA_HOME", root.join("data"));
+        });
+
+        let mut context = mock_context(noop_assets());
+        context.config_mut().identifier = format!(
+            "com.torus.tests.app{}",
+            NEXT_APP_ID.fetch_add(1, Ordering::SeqCst)
+        );
+        let http = HttpClientSettings {
+            timeout: CLIENT_TIMEOUT,
+            ..HttpClientSettings::default()
+        };
+        let app = torus_app_lib::with_commands(mock_builder(), http)
+            .build(context)
+            .expect("failed to build mock app");
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .expect("failed to create mock webview");
+        TestApp { _app: app, webview }
+    }
+
+    /// Invokes `command` with camelCase `args`, exactly as the frontend does.
+    /// Command errors come back as their message.
+    pub fn invoke<T: DeserializeOwned>(&self, command: &str, args: Value) -> Result<T, String> {
+        let request = InvokeRequest {
+            cmd: command.into(),
+            callback: CallbackFn(0),
+            error: CallbackFn(1),
+            url: "http://tauri.localhost".parse().unwrap(),
+            body: InvokeBody::Json(args),
+            headers: Default::default(),
+            invoke_key: INVOKE_KEY.to_string(),
+        };
+        match get_ipc_response(&self.webview, request) {
+            Ok(body) => Ok(body
+                .deserialize::<T>()
+                .unwrap_or_else(|error| panic!("{command} returned an unexpected shape: {error}"))),
+            Err(Value::String(message)) => Err(message),
+            Err(other) => Err(other.to_string()),
+        }
+    }
+}
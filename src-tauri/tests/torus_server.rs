@@ -0,0 +1,281 @@
+//! Drives the scoreboard commands through Tauri's mock runtime against a real
+//! `torus-server` on an in-memory SQLite store, so every accepted run is
+//! re-simulated the way `verify-score` does it.
+
+mod support;
+
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+use support::TestApp;
+use torus_server::{Store, TorusServer};
+
+const ANON_KEY: &str = "self-hosted-anon-key";
+
+/// A running server and the base URL the app should use for it.
+struct SelfHosted {
+    _server: TorusServer,
+    url: String,
+}
+
+impl SelfHosted {
+    fn start() -> Self {
+        let store = Store::open(Path::new(":memory:")).expect("failed to open the store");
+        let server = TorusServer::start("127.0.0.1:0", Arc::new(store), ANON_KEY, 2)
+            .expect("failed to start torus-server");
+        let url = format!("http://{}", server.address());
+        SelfHosted {
+            _server: server,
+            url,
+        }
+    }
+
+    /// Merges the server's URL and key into a command's arguments.
+    fn remote(&self, mut args: Value) -> Value {
+        args["supabaseUrl"] = json!(self.url);
+        args["supabaseAnonKey"] = json!(ANON_KEY);
+        args
+    }
+}
+
+/// A run on `seed` that plays no moves until the game ends, with the final
+/// state the simulator reaches, so `torus-server` accepts it.
+fn finished_run(seed: u32) -> Value {
+    let mut run = json!({
+        "version": 1,
+        "difficulty": 1,
+        "seed": seed,
+        "finalTime": 2_000_000,
+        "finalScore": 0,
+        "finalLevel": 0,
+        "inputs": [],
+    });
+    let verification = torus_core::replay::verify_replay_json(&run.to_string()).unwrap();
+    run["finalTime"] = json!(verification.actual.time);
+    run["finalScore"] = json!(verification.actual.score);
+    run["finalLevel"] = json!(verification.actual.level);
+    run
+}
+
+/// The leaderboard entry matching `run`.
+fn entry_for(user: &str, run: &Value) -> Value {
+    json!({
+        "user": user,
+        "score": run["finalScore"],
+        "level": run["finalLevel"],
+        "date": "2026-01-01T00:00:00.000Z",
+        "skillUsage": [],
+    })
+}
+
+fn current_key(app: &TestApp, server: &SelfHosted, mode: &str) -> String {
+    let period = app
+        .invoke::<Value>(
+            "get_challenge_period",
+            server.remote(json!({ "mode": mode })),
+        )
+        .unwrap();
+    period["challengeKey"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn classic_runs_are_resimulated_before_they_rank() {
+    let app = TestApp::new();
+    let rival = TestApp::new();
+    let server = SelfHosted::start();
+
+    let rival_run = finished_run(3);
+    let rival_result = rival
+        .invoke::<Value>(
+            "submit_global_score",
+            server.remote(
+                json!({ "entry": entry_for("Rival", &rival_run), "replayProof": rival_run }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(rival_result["accepted"], true);
+    assert_eq!(rival_result["totalPlayers"], 1);
+
+    let run = finished_run(7);
+    let result = app
+        .invoke::<Value>(
+            "submit_global_score",
+            server.remote(json!({ "entry": entry_for("Tester", &run), "replayProof": run })),
+        )
+        .unwrap();
+    assert_eq!(result["accepted"], true);
+    assert_eq!(result["improved"], true);
+    assert_eq!(result["totalPlayers"], 2);
+    assert_eq!(result["localOnly"], false);
+
+    let board = app
+        .invoke::<Vec<Value>>("fetch_global_scores", server.remote(json!({})))
+        .unwrap();
+    assert_eq!(board.len(), 2);
+    let mine = board.iter().find(|row| row["isMe"] == true).unwrap();
+    assert_eq!(mine["user"], "Tester");
+    assert_eq!(mine["syncState"], "synced");
+
+    let mut forged = finished_run(11);
+    forged["finalScore"] = json!(forged["finalScore"].as_i64().unwrap() + 300);
+    let result = app
+        .invoke::<Value>(
+            "submit_global_score",
+            server.remote(json!({ "entry": entry_for("Tester", &forged), "replayProof": forged })),
+        )
+        .unwrap();
+    assert_eq!(result["localOnly"], true);
+    let rejects = app
+        .invoke::<Vec<Value>>("list_rejected_submissions", json!({}))
+        .unwrap();
+    assert_eq!(rejects.len(), 1);
+    assert_eq!(rejects[0]["error"], "REPLAY_VERIFICATION_FAILED");
+}
+
+#[test]
+fn daily_attempts_run_against_the_server() {
+    let app = TestApp::new();
+    let server = SelfHosted::start();
+    let clock = app
+        .invoke::<Value>("get_daily_clock", server.remote(json!({})))
+        .unwrap();
+    assert_eq!(clock["serverSynced"], true);
+    let key = current_key(&app, &server, "daily");
+    let start = || {
+        app.invoke::<Value>(
+            "start_daily_attempt",
+            server.remote(json!({ "challengeKey": key })),
+        )
+        .unwrap()
+    };
+
+    let started = start();
+    assert_eq!(started["accepted"], true);
+    assert_eq!(started["attemptsUsed"], 1);
+    assert_eq!(start()["resumed"], true);
+    let status = app
+        .invoke::<Value>(
+            "fetch_daily_status",
+            server.remote(json!({ "challengeKey": key })),
+        )
+        .unwrap();
+    assert_eq!(status["hasActiveAttempt"], true);
+    assert_eq!(status["attemptsLeft"], 2);
+
+    let run = finished_run(5);
+    let submitted = app
+        .invoke::<Value>(
+            "submit_daily_score",
+            server.remote(json!({
+                "challengeKey": key,
+                "attemptToken": started["attemptToken"],
+                "entry": entry_for("Tester", &run),
+                "replayProof": run,
+            })),
+        )
+        .unwrap();
+    assert_eq!(submitted["accepted"], true);
+    assert_eq!(submitted["improved"], true);
+    assert_eq!(submitted["hasActiveAttempt"], false);
+
+    let board = app
+        .invoke::<Vec<Value>>(
+            "fetch_daily_scores",
+            server.remote(json!({ "challengeKey": key })),
+        )
+        .unwrap();
+    assert_eq!(board.len(), 1);
+    assert_eq!(board[0]["isMe"], true);
+
+    let history = app
+        .invoke::<Vec<Value>>(
+            "fetch_daily_history",
+            server.remote(json!({ "from": key, "to": key })),
+        )
+        .unwrap();
+    assert_eq!(history[0]["submitted"], true);
+    assert_eq!(history[0]["rank"], 1);
+
+    let badge = app
+        .invoke::<Value>(
+            "fetch_daily_badge_status",
+            server.remote(json!({ "challengeKey": key })),
+        )
+        .unwrap();
+    assert_eq!(badge["currentStreak"], 1);
+    assert_eq!(badge["offline"], false);
+
+    let rolled_back = app
+        .invoke::<Value>(
+            "rollback_daily_attempt",
+            server.remote(json!({ "challengeKey": key, "attemptToken": start()["attemptToken"] })),
+        )
+        .unwrap();
+    assert_eq!(rolled_back["accepted"], true);
+    assert_eq!(rolled_back["attemptsUsed"], 1);
+}
+
+#[test]
+fn weekly_attempts_run_against_the_server() {
+    let app = TestApp::new();
+    let server = SelfHosted::start();
+    let key = current_key(&app, &server, "weekly");
+    let start = || {
+        app.invoke::<Value>(
+            "start_challenge_attempt",
+            server
+                .remote(json!({ "mode": "weekly", "challengeKey": key, "displayName": "Tester" })),
+        )
+        .unwrap()
+    };
+
+    let started = start();
+    assert_eq!(started["maxAttempts"], 5);
+    let run = finished_run(9);
+    let submitted = app
+        .invoke::<Value>(
+            "submit_challenge_score",
+            server.remote(json!({
+                "mode": "weekly",
+                "challengeKey": key,
+                "attemptToken": started["attemptToken"],
+                "entry": entry_for("Tester", &run),
+                "replayProof": run,
+            })),
+        )
+        .unwrap();
+    assert_eq!(submitted["accepted"], true);
+
+    let board = app
+        .invoke::<Vec<Value>>(
+            "fetch_challenge_scores",
+            server.remote(json!({ "mode": "weekly", "challengeKey": key })),
+        )
+        .unwrap();
+    assert_eq!(board.len(), 1);
+    assert_eq!(board[0]["user"], "Tester");
+
+    let forfeited = app
+        .invoke::<Value>(
+            "forfeit_challenge_attempt",
+            server.remote(
+                json!({ "mode": "weekly", "challengeKey": key, "attemptToken": start()["attemptToken"] }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(forfeited["accepted"], true);
+    assert_eq!(forfeited["attemptsUsed"], 2);
+}
+
+#[test]
+fn unserved_features_report_errors() {
+    let app = TestApp::new();
+    let server = SelfHosted::start();
+    let error = app
+        .invoke::<Value>(
+            "create_group",
+            server.remote(json!({ "name": "Lab", "playerName": "Tester" })),
+        )
+        .unwrap_err();
+    assert!(error.contains("404"), "{error}");
+}
@@ -42,8 +42,12 @@ export interface ScoreEntry {
   skillUsage: SkillUsageEntry[];
   isMe?: boolean;
   replayProof?: DailyReplayProof;
+  syncState?: ScoreSyncState | null;
+  syncError?: string | null;
 }
 
+export type ScoreSyncState = "pending" | "synced" | "rejected";
+
 export interface DailyChallengeStatus {
   challengeKey: string;
   attemptsUsed: number;
@@ -63,9 +67,26 @@ export interface DailyAttemptForfeitResult extends DailyChallengeStatus {
   accepted: boolean;
 }
 
+/** Why the local submission guard kept a run from the server. */
+export type SubmitRefusal = "duplicate" | "rate_limited";
+
 export interface DailyChallengeSubmitResult extends DailyChallengeStatus {
   accepted: boolean;
   improved: boolean;
+  localOnly?: boolean;
+  reason?: SubmitRefusal;
+  retryAfter?: number;
+}
+
+export interface GlobalSubmitResult {
+  accepted: boolean;
+  improved: boolean;
+  previousBest: number | null;
+  rank: number | null;
+  totalPlayers: number | null;
+  localOnly: boolean;
+  reason?: SubmitRefusal;
+  retryAfter?: number;
 }
 
 export interface DailyBadgeStatus {
@@ -80,7 +101,7 @@ export interface DailyBadgeStatus {
 
 export interface ScoreboardStore {
   top(limit?: number): Promise<ScoreEntry[]>;
-  add(entry: ScoreEntry, replayProof: DailyReplayProof): Promise<void>;
+  add(entry: ScoreEntry, replayProof: DailyReplayProof): Promise<GlobalSubmitResult | null>;
   topPersonal(limit?: number): Promise<ScoreEntry[]>;
   addPersonal(entry: ScoreEntry): Promise<void>;
   topDaily(challengeKey: string, limit?: number): Promise<ScoreEntry[]>;
@@ -311,11 +332,12 @@ class LocalOnlyScoreboardStore implements ScoreboardStore {
     return this.globalStore.top(limit);
   }
 
-  public add(entry: ScoreEntry, _replayProof: DailyReplayProof): Promise<void> {
-    return this.globalStore.add({
+  public async add(entry: ScoreEntry, _replayProof: DailyReplayProof): Promise<GlobalSubmitResult | null> {
+    await this.globalStore.add({
       ...entry,
       isMe: true,
     });
+    return null;
   }
 
   public topPersonal(limit = 10): Promise<ScoreEntry[]> {
@@ -484,13 +506,13 @@ class TauriScoreboardStore implements ScoreboardStore {
     }
   }
 
-  public async add(entry: ScoreEntry, replayProof: DailyReplayProof): Promise<void> {
+  public async add(entry: ScoreEntry, replayProof: DailyReplayProof): Promise<GlobalSubmitResult | null> {
     await this.globalStore.add({
       ...entry,
       isMe: true,
     });
     try {
-      await invoke("submit_global_score", {
+      return await invoke<GlobalSubmitResult>("submit_global_score", {
         entry,
         replayProof,
         supabaseUrl: this.supabaseUrl || null,
@@ -498,6 +520,7 @@ class TauriScoreboardStore implements ScoreboardStore {
       });
     } catch (error) {
       console.warn("Failed to save score through Tauri backend. Score kept locally.", error);
+      return null;
     }
   }
 
@@ -620,6 +643,8 @@ class TauriScoreboardStore implements ScoreboardStore {
         badgeMaxStreak: normalizeOptionalBadgeMetric(entry.badgeMaxStreak),
         skillUsage: this.normalizeSkillUsage(entry.skillUsage),
         isMe: entry.isMe === true,
+        syncState: entry.syncState ?? null,
+        syncError: entry.syncError ?? null,
       }));
   }
 
@@ -4,7 +4,7 @@ import {
   verifyReplayProof,
 } from "./simulator.ts";
 
-type ScoreMode = "classic" | "daily";
+type ScoreMode = "classic" | "daily" | "weekly" | "monthly";
 
 interface SkillUsage {
   name: string;
@@ -25,12 +25,14 @@ interface VerifyScorePayload {
   challengeKey: string;
   attemptToken?: string | null;
   clientUuid: string;
+  deviceSecret?: string | null;
   entry: ScoreEntryPayload;
   replayProof: ReplayProof;
 }
 
 const CLASSIC_MODE: ScoreMode = "classic";
 const DAILY_MODE: ScoreMode = "daily";
+const PERIOD_MODES: ReadonlyArray<ScoreMode> = ["daily", "weekly", "monthly"];
 const CLASSIC_CHALLENGE_KEY = "classic";
 
 const corsHeaders = {
@@ -96,7 +98,7 @@ Deno.serve(async (request) => {
   const normalizedLevel = Math.trunc(parsed.payload.entry.level);
 
   let data: unknown = null;
-  if (parsed.payload.mode === DAILY_MODE) {
+  if (PERIOD_MODES.includes(parsed.payload.mode)) {
     const dailyAttemptToken = parsed.payload.attemptToken ?? "";
     const { data: rpcData, error } = await admin.rpc("submit_daily_score", {
       p_client_uuid: parsed.payload.clientUuid,
@@ -107,6 +109,8 @@ Deno.serve(async (request) => {
       p_level: normalizedLevel,
       p_created_at: parsed.payload.entry.date,
       p_skill_usage: normalizedSkillUsage,
+      p_mode: parsed.payload.mode,
+      p_device_secret: parsed.payload.deviceSecret ?? null,
     });
     if (error) {
       const failure = {
@@ -116,6 +120,7 @@ Deno.serve(async (request) => {
         code: error.code ?? null,
       };
       console.error("[verify-score] submit_daily_score RPC failed", {
+        mode: parsed.payload.mode,
         challengeKey: parsed.payload.challengeKey,
         clientUuid: parsed.payload.clientUuid,
         failure,
@@ -131,6 +136,7 @@ Deno.serve(async (request) => {
       p_level: normalizedLevel,
       p_created_at: parsed.payload.entry.date,
       p_skill_usage: normalizedSkillUsage,
+      p_device_secret: parsed.payload.deviceSecret ?? null,
     });
     if (error) {
       const failure = {
@@ -148,6 +154,27 @@ Deno.serve(async (request) => {
     data = rpcData;
   }
 
+  if ((data as { improved?: boolean } | null)?.improved) {
+    const mode = parsed.payload.mode;
+    const { error } = await admin.from("score_replays").upsert({
+      client_uuid: parsed.payload.clientUuid,
+      mode,
+      challenge_key: PERIOD_MODES.includes(mode) ? parsed.payload.challengeKey : CLASSIC_CHALLENGE_KEY,
+      score: normalizedScore,
+      level: normalizedLevel,
+      replay_proof: parsed.payload.replayProof,
+      created_at: new Date().toISOString(),
+    }, { onConflict: "client_uuid,mode,challenge_key" });
+    if (error) {
+      // The score is already recorded; only the audit copy is missing.
+      console.error("[verify-score] score_replays upsert failed", {
+        mode,
+        clientUuid: parsed.payload.clientUuid,
+        detail: error.message,
+      });
+    }
+  }
+
   return jsonResponse(data ?? {});
 });
 
@@ -167,12 +194,18 @@ function validatePayload(
   if (!payload || typeof payload !== "object") {
     return { ok: false, error: "INVALID_PAYLOAD" };
   }
-  if (payload.mode !== CLASSIC_MODE && payload.mode !== DAILY_MODE) {
+  if (payload.mode !== CLASSIC_MODE && !PERIOD_MODES.includes(payload.mode)) {
     return { ok: false, error: "INVALID_MODE" };
   }
   if (!isNonEmptyString(payload.clientUuid, 8, 80)) {
     return { ok: false, error: "INVALID_CLIENT_UUID" };
   }
+  if (
+    payload.deviceSecret != null &&
+    (typeof payload.deviceSecret !== "string" || !/^[0-9a-f]{64}$/.test(payload.deviceSecret))
+  ) {
+    return { ok: false, error: "INVALID_DEVICE_SECRET" };
+  }
   if (!payload.entry || typeof payload.entry !== "object") {
     return { ok: false, error: "INVALID_ENTRY" };
   }
@@ -204,7 +237,7 @@ function validatePayload(
       return { ok: false, error: "INVALID_CHALLENGE_KEY" };
     }
   } else {
-    if (!isDateKey(payload.challengeKey)) {
+    if (!isChallengeKey(payload.mode, payload.challengeKey)) {
       return { ok: false, error: "INVALID_CHALLENGE_KEY" };
     }
     if (!isNonEmptyString(payload.attemptToken, 1, 256)) {
@@ -215,8 +248,18 @@ function validatePayload(
   return { ok: true, payload };
 }
 
-function isDateKey(value: unknown): boolean {
-  return typeof value === "string" && /^[0-9]{4}-[0-9]{2}-[0-9]{2}$/.test(value.trim());
+function isChallengeKey(mode: ScoreMode, value: unknown): boolean {
+  if (typeof value !== "string") {
+    return false;
+  }
+  const key = value.trim();
+  if (mode === DAILY_MODE) {
+    return /^[0-9]{4}-[0-9]{2}-[0-9]{2}$/.test(key);
+  }
+  if (mode === "weekly") {
+    return /^[0-9]{4}-W[0-9]{2}$/.test(key);
+  }
+  return /^[0-9]{4}-[0-9]{2}$/.test(key);
 }
 
 function isNonEmptyString(value: unknown, min: number, max: number): boolean {
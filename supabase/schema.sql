@@ -76,6 +76,13 @@ alter table public.scores
 alter table public.scores
   alter column attempts_used set not null;
 
+alter table public.scores
+  drop constraint if exists scores_attempts_used_check;
+
+alter table public.scores
+  add constraint scores_attempts_used_check
+  check (attempts_used between 0 and 20);
+
 alter table public.scores
   drop constraint if exists scores_client_uuid_key;
 
@@ -97,6 +104,10 @@ create index if not exists idx_scores_daily_rank
   on public.scores (challenge_key, score desc, level desc, created_at desc)
   where mode = 'daily' and daily_has_submission = true;
 
+create index if not exists idx_scores_period_rank
+  on public.scores (mode, challenge_key, score desc, level desc, created_at desc)
+  where mode in ('weekly', 'monthly') and daily_has_submission = true;
+
 create table if not exists public.daily_streak_states (
   client_uuid text primary key check (char_length(trim(client_uuid)) between 8 and 80),
   current_streak integer not null default 0 check (current_streak >= 0),
@@ -109,6 +120,16 @@ create table if not exists public.daily_streak_states (
   updated_at timestamptz not null default now()
 );
 
+alter table public.daily_streak_states
+  add column if not exists freeze_tokens integer not null default 0;
+
+alter table public.daily_streak_states
+  drop constraint if exists daily_streak_states_freeze_tokens_check;
+
+alter table public.daily_streak_states
+  add constraint daily_streak_states_freeze_tokens_check
+  check (freeze_tokens between 0 and 2);
+
 do $$
 begin
   if to_regclass('public.daily_streak_submissions') is not null then
@@ -238,89 +259,1136 @@ on conflict (client_uuid) do update set
   last_submission_key = excluded.last_submission_key,
   updated_at = excluded.updated_at;
 
-alter table public.scores enable row level security;
+create table if not exists public.badge_tiers (
+  power integer primary key check (power >= 0),
+  days integer not null unique check (days >= 1),
+  name text not null check (char_length(trim(name)) between 1 and 40),
+  icon_id text not null check (char_length(trim(icon_id)) between 1 and 64)
+);
+
+-- Optional per-challenge overrides, e.g. a one-attempt "hardcore" day or a
+-- longer event. Challenges without a row use the mode default.
+create table if not exists public.challenge_settings (
+  mode text not null check (mode in ('daily', 'weekly', 'monthly')),
+  challenge_key text not null,
+  max_attempts integer not null check (max_attempts between 1 and 20),
+  primary key (mode, challenge_key)
+);
+
+-- Public friend codes: a salted hash of the device UUID that friends can
+-- resolve to the device's scoreboard rows. Written by publish_friend_code(...),
+-- which derives the code itself, and read only through resolve_friend_codes(...).
+create table if not exists public.friend_codes (
+  friend_code text primary key check (friend_code ~ '^[0-9A-HJKMNP-TV-Z]{5}-[0-9A-HJKMNP-TV-Z]{5}$'),
+  client_uuid text not null unique check (char_length(client_uuid) >= 8),
+  updated_at timestamptz not null default now()
+);
+
+-- Device UUIDs are public on every board, so RPCs that change what a device
+-- owns also take the random secret the app keeps next to its UUID. Only the
+-- SHA-256 of the secret is stored; the first authenticated call registers it.
+create table if not exists public.device_credentials (
+  client_uuid text primary key check (char_length(client_uuid) >= 8),
+  secret_hash text not null check (secret_hash ~ '^[0-9a-f]{64}$'),
+  created_at timestamptz not null default now()
+);
+
+-- Display-name blocklist, checked by upsert_profile(...) and merged into the
+-- app's copy: `term` matches at the start or end of a word, `word` only as a
+-- whole word, `reserved` only as the entire name.
+create table if not exists public.name_blocklist (
+  kind text not null check (kind in ('term', 'word', 'reserved')),
+  term text not null check (char_length(trim(term)) between 1 and 40),
+  primary key (kind, term)
+);
+
+-- Same entries as src-tauri/resources/name-blocklist.json.
+insert into public.name_blocklist (kind, term)
+values
+  ('term', 'fuck'),
+  ('term', 'shit'),
+  ('term', 'bitch'),
+  ('term', 'asshole'),
+  ('term', 'pussy'),
+  ('term', 'hitler'),
+  ('word', 'cunt'),
+  ('word', 'dick'),
+  ('word', 'whore'),
+  ('word', 'slut'),
+  ('word', 'nazi'),
+  ('word', 'rape'),
+  ('word', 'bastard'),
+  ('reserved', 'admin'),
+  ('reserved', 'administrator'),
+  ('reserved', 'moderator'),
+  ('reserved', 'mod'),
+  ('reserved', 'official'),
+  ('reserved', 'system'),
+  ('reserved', 'support'),
+  ('reserved', 'torus'),
+  ('reserved', 'pending')
+on conflict (kind, term) do nothing;
+
+-- One profile per device identity. The display name replaces whatever name a
+-- submission carries, so a device keeps a single name on every board.
+create table if not exists public.profiles (
+  client_uuid text primary key check (char_length(client_uuid) >= 8),
+  display_name text not null check (char_length(trim(display_name)) between 1 and 20),
+  country_code text check (country_code is null or country_code ~ '^[A-Z]{2}$'),
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now()
+);
+
+-- Player reports for moderation. Written only through report_score(...); one
+-- report per reporter and reported row.
+create table if not exists public.score_reports (
+  id bigint generated always as identity primary key,
+  reporter_client_uuid text not null check (char_length(reporter_client_uuid) >= 8),
+  reported_client_uuid text not null check (char_length(reported_client_uuid) >= 8),
+  mode text not null check (mode in ('classic', 'daily', 'weekly', 'monthly')),
+  challenge_key text not null,
+  player_name text not null,
+  score integer not null,
+  level integer not null,
+  reason text not null check (reason in ('cheating', 'offensive_name', 'other')),
+  detail text not null default '' check (char_length(detail) <= 280),
+  created_at timestamptz not null default now(),
+  unique (reporter_client_uuid, reported_client_uuid, mode, challenge_key)
+);
+
+create index if not exists idx_score_reports_reported
+  on public.score_reports (reported_client_uuid, created_at desc);
+
+-- Replay proof of each device's best verified run per board, kept by the
+-- verify-score function for `replay-audit --supabase`. Service role only: a
+-- published Daily replay could be replayed by anyone the same day.
+create table if not exists public.score_replays (
+  client_uuid text not null check (char_length(client_uuid) >= 8),
+  mode text not null check (mode in ('classic', 'daily', 'weekly', 'monthly')),
+  challenge_key text not null,
+  score integer not null,
+  level integer not null,
+  replay_proof jsonb not null,
+  created_at timestamptz not null default now(),
+  primary key (client_uuid, mode, challenge_key)
+);
+
+create index if not exists idx_score_replays_board
+  on public.score_replays (mode, challenge_key, score desc, level desc);
+
+-- Private groups (clubs). Only reachable through the group RPCs below, which
+-- all authenticate the device. The invite code is the shared secret that lets
+-- a device join; only create_group(...) and join_group(...) return it.
+create table if not exists public.groups (
+  id uuid primary key default gen_random_uuid(),
+  name text not null check (char_length(trim(name)) between 1 and 32),
+  invite_code text not null unique check (invite_code ~ '^[0-9A-F]{4}-[0-9A-F]{4}$'),
+  owner_client_uuid text not null check (char_length(owner_client_uuid) >= 8),
+  created_at timestamptz not null default now()
+);
+
+create table if not exists public.group_members (
+  group_id uuid not null references public.groups (id) on delete cascade,
+  client_uuid text not null check (char_length(client_uuid) >= 8),
+  player_name text not null default 'Player' check (char_length(player_name) between 1 and 32),
+  joined_at timestamptz not null default now(),
+  primary key (group_id, client_uuid)
+);
+
+create index if not exists idx_group_members_client_uuid
+  on public.group_members (client_uuid);
+
+alter table public.scores enable row level security;
+
+drop policy if exists scores_select_public on public.scores;
+create policy scores_select_public
+  on public.scores
+  for select
+  using (true);
+
+drop policy if exists scores_insert_public on public.scores;
+
+drop policy if exists scores_update_public on public.scores;
+
+alter table public.daily_streak_states enable row level security;
+
+drop policy if exists daily_streak_states_select_public on public.daily_streak_states;
+create policy daily_streak_states_select_public
+  on public.daily_streak_states
+  for select
+  using (true);
+
+grant select on public.daily_streak_states to anon, authenticated;
+
+alter table public.badge_tiers enable row level security;
+
+drop policy if exists badge_tiers_select_public on public.badge_tiers;
+create policy badge_tiers_select_public
+  on public.badge_tiers
+  for select
+  using (true);
+
+grant select on public.badge_tiers to anon, authenticated;
+
+alter table public.challenge_settings enable row level security;
+
+drop policy if exists challenge_settings_select_public on public.challenge_settings;
+create policy challenge_settings_select_public
+  on public.challenge_settings
+  for select
+  using (true);
+
+grant select on public.challenge_settings to anon, authenticated;
+
+alter table public.friend_codes enable row level security;
+
+drop policy if exists friend_codes_select_public on public.friend_codes;
+
+revoke select on public.friend_codes from anon, authenticated;
+
+-- No policies: credentials are only checked inside authenticate_device(...).
+alter table public.device_credentials enable row level security;
+
+alter table public.name_blocklist enable row level security;
+
+drop policy if exists name_blocklist_select_public on public.name_blocklist;
+create policy name_blocklist_select_public
+  on public.name_blocklist
+  for select
+  using (true);
+
+grant select on public.name_blocklist to anon, authenticated;
+
+alter table public.profiles enable row level security;
+
+drop policy if exists profiles_select_public on public.profiles;
+create policy profiles_select_public
+  on public.profiles
+  for select
+  using (true);
+
+grant select on public.profiles to anon, authenticated;
+
+-- Reports are write-only for clients and reviewed with the service role.
+alter table public.score_reports enable row level security;
+
+-- No policies: replays are read with the service role only.
+alter table public.score_replays enable row level security;
+
+-- No select policies: group rows are only exposed through the group RPCs.
+alter table public.groups enable row level security;
+alter table public.group_members enable row level security;
+
+drop function if exists public.submit_daily_score(
+  text,
+  text,
+  text,
+  integer,
+  integer,
+  timestamptz,
+  jsonb
+);
+
+drop function if exists public.submit_global_score(
+  text,
+  text,
+  integer,
+  integer,
+  timestamptz,
+  jsonb
+);
+
+drop function if exists public.start_daily_attempt(
+  text,
+  text,
+  text,
+  uuid
+);
+
+drop function if exists public.forfeit_daily_attempt(
+  text,
+  text,
+  text,
+  uuid
+);
+
+drop function if exists public.rollback_daily_attempt(
+  text,
+  text,
+  text,
+  uuid
+);
+
+drop function if exists public.submit_daily_score(
+  text,
+  text,
+  text,
+  text,
+  integer,
+  integer,
+  timestamptz,
+  jsonb,
+  uuid
+);
+
+drop function if exists public.submit_global_score(
+  text,
+  text,
+  integer,
+  integer,
+  timestamptz,
+  jsonb,
+  uuid
+);
+
+drop function if exists public.start_daily_attempt(
+  text,
+  text,
+  text
+);
+
+drop function if exists public.forfeit_daily_attempt(
+  text,
+  text,
+  text
+);
+
+drop function if exists public.rollback_daily_attempt(
+  text,
+  text,
+  text
+);
+
+drop function if exists public.submit_daily_score(
+  text,
+  text,
+  text,
+  text,
+  integer,
+  integer,
+  timestamptz,
+  jsonb
+);
+
+-- submit_daily_score(...) and submit_global_score(...) now take the device
+-- secret sent with a verified run.
+drop function if exists public.submit_daily_score(
+  text,
+  text,
+  text,
+  text,
+  integer,
+  integer,
+  timestamptz,
+  jsonb,
+  text
+);
+
+-- publish_friend_code(p_client_uuid, p_friend_code) took the code from the
+-- client; the replacement takes the device secret instead.
+drop function if exists public.publish_friend_code(
+  text,
+  text
+);
+
+-- report_score(...) now authenticates the reporter and takes the reported
+-- player's public id instead of their device UUID.
+drop function if exists public.report_score(
+  text,
+  text,
+  text,
+  text,
+  text,
+  integer,
+  integer,
+  text,
+  text
+);
+
+-- upsert_profile(...) and the group RPCs now authenticate the device with
+-- p_device_secret.
+drop function if exists public.upsert_profile(
+  text,
+  text,
+  text
+);
+
+drop function if exists public.create_group(
+  text,
+  text,
+  text
+);
+
+drop function if exists public.join_group(
+  text,
+  text,
+  text
+);
+
+drop function if exists public.leave_group(
+  text,
+  uuid
+);
+
+drop function if exists public.list_group_members(
+  text,
+  uuid
+);
+
+drop function if exists public.list_my_groups(
+  text
+);
+
+-- Challenge periods: 'daily' (YYYY-MM-DD), 'weekly' (ISO week, YYYY-Www)
+-- and 'monthly' (YYYY-MM). All share the attempt RPCs below via p_mode.
+create or replace function public.challenge_period_key(
+  p_mode text,
+  p_at timestamptz default now()
+) returns text
+language sql
+stable
+as $$
+  select case p_mode
+    when 'daily' then to_char((p_at at time zone 'utc')::date, 'YYYY-MM-DD')
+    when 'weekly' then to_char((p_at at time zone 'utc')::date, 'IYYY-"W"IW')
+    when 'monthly' then to_char((p_at at time zone 'utc')::date, 'YYYY-MM')
+    else null
+  end;
+$$;
+
+create or replace function public.challenge_key_pattern(
+  p_mode text
+) returns text
+language sql
+immutable
+as $$
+  select case p_mode
+    when 'daily' then '^[0-9]{4}-[0-9]{2}-[0-9]{2}$'
+    when 'weekly' then '^[0-9]{4}-W[0-9]{2}$'
+    when 'monthly' then '^[0-9]{4}-[0-9]{2}$'
+    else null
+  end;
+$$;
+
+drop function if exists public.challenge_max_attempts(text);
+
+create or replace function public.challenge_max_attempts(
+  p_mode text,
+  p_challenge_key text
+) returns integer
+language sql
+stable
+as $$
+  select coalesce(
+    (
+      select settings.max_attempts
+      from public.challenge_settings settings
+      where settings.mode = p_mode
+        and settings.challenge_key = p_challenge_key
+    ),
+    case p_mode
+      when 'daily' then 3
+      when 'weekly' then 5
+      when 'monthly' then 10
+      else null
+    end
+  );
+$$;
+
+-- Registers the device's secret if it has none yet, then requires the same
+-- secret. Only called where the caller has proven it runs the device: by
+-- authenticate_device(...) and by a verified score submission.
+create or replace function public.bind_device_credential(
+  p_client_uuid text,
+  p_device_secret text
+) returns void
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  v_secret_hash text;
+  v_stored_hash text;
+begin
+  if char_length(coalesce(p_client_uuid, '')) < 8 then
+    raise exception 'INVALID_CLIENT_UUID';
+  end if;
+
+  if coalesce(p_device_secret, '') !~ '^[0-9a-f]{64}$' then
+    raise exception 'INVALID_DEVICE_SECRET';
+  end if;
+
+  v_secret_hash := encode(sha256(convert_to(p_device_secret, 'UTF8')), 'hex');
+
+  insert into public.device_credentials (client_uuid, secret_hash)
+  values (p_client_uuid, v_secret_hash)
+  on conflict (client_uuid) do nothing;
+
+  select secret_hash
+  into v_stored_hash
+  from public.device_credentials
+  where client_uuid = p_client_uuid;
+
+  if v_stored_hash <> v_secret_hash then
+    raise exception 'DEVICE_AUTH_FAILED';
+  end if;
+end;
+$$;
+
+-- Registers the device's secret on first use, then requires the same secret.
+-- A device that already has scores exposes its UUID on the boards, so its
+-- secret is only bound by its next verified submission, never here.
+create or replace function public.authenticate_device(
+  p_client_uuid text,
+  p_device_secret text
+) returns void
+language plpgsql
+security definer
+set search_path = public
+as $$
+begin
+  if not exists (
+    select 1
+    from public.device_credentials
+    where client_uuid = p_client_uuid
+  ) and exists (
+    select 1
+    from public.scores
+    where client_uuid = p_client_uuid
+  ) then
+    raise exception 'DEVICE_NOT_REGISTERED';
+  end if;
+
+  perform public.bind_device_credential(p_client_uuid, p_device_secret);
+end;
+$$;
+
+-- Checks the secret sent with a verified submission: binds it for a device
+-- that has none, and requires it once the device has one.
+create or replace function public.authenticate_submission(
+  p_client_uuid text,
+  p_device_secret text
+) returns void
+language plpgsql
+security definer
+set search_path = public
+as $$
+begin
+  if p_device_secret is not null then
+    perform public.bind_device_credential(p_client_uuid, p_device_secret);
+  elsif exists (
+    select 1
+    from public.device_credentials
+    where client_uuid = p_client_uuid
+  ) then
+    raise exception 'DEVICE_AUTH_FAILED';
+  end if;
+end;
+$$;
+
+-- First 50 bits of sha256('torus-friend-v1:' || uuid) in Crockford base32,
+-- as `friend_code_for_device` in src-tauri/src/friends.rs.
+create or replace function public.friend_code_for_device(
+  p_client_uuid text
+) returns text
+language sql
+immutable
+set search_path = public
+as $$
+  select string_agg(
+    substr('0123456789ABCDEFGHJKMNPQRSTVWXYZ', (((digest.bits >> (59 - 5 * i)) & 31) + 1)::integer, 1)
+      || case when i = 4 then '-' else '' end,
+    ''
+    order by i
+  )
+  from (
+    select ('x' || left(encode(sha256(convert_to('torus-friend-v1:' || p_client_uuid, 'UTF8')), 'hex'), 16))::bit(64)::bigint as bits
+  ) as digest,
+  generate_series(0, 9) as i;
+$$;
+
+-- Codes published before they were derived here may belong to someone else.
+delete from public.friend_codes
+where friend_code <> public.friend_code_for_device(client_uuid);
+
+create or replace function public.publish_friend_code(
+  p_client_uuid text,
+  p_device_secret text
+) returns jsonb
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  v_client_uuid text := trim(coalesce(p_client_uuid, ''));
+  v_friend_code text;
+begin
+  perform public.authenticate_device(v_client_uuid, p_device_secret);
+  v_friend_code := public.friend_code_for_device(v_client_uuid);
+
+  insert into public.friend_codes (friend_code, client_uuid, updated_at)
+  values (v_friend_code, v_client_uuid, now())
+  on conflict (client_uuid) do update set
+    friend_code = excluded.friend_code,
+    updated_at = excluded.updated_at;
+
+  return jsonb_build_object('friendCode', v_friend_code);
+end;
+$$;
+
+-- Resolves only codes the caller already knows, so the mapping cannot be
+-- listed wholesale.
+create or replace function public.resolve_friend_codes(
+  p_friend_codes text[]
+) returns table (
+  friend_code text,
+  client_uuid text
+)
+language sql
+stable
+security definer
+set search_path = public
+as $$
+  select f.friend_code, f.client_uuid
+  from public.friend_codes f
+  where f.friend_code = any(coalesce(p_friend_codes[1:200], '{}'::text[]));
+$$;
+
+-- Blocklist form of a name, as `fold_for_blocklist` in
+-- src-tauri/src/name_policy.rs without the Unicode look-alike folding:
+-- lowercased, digit/symbol swaps undone, only letters and digits kept.
+create or replace function public.fold_display_name(
+  p_name text
+) returns text
+language sql
+immutable
+set search_path = public
+as $$
+  select regexp_replace(
+    translate(lower(coalesce(p_name, '')), '01!|34@5$7', 'oiiieaasst'),
+    '[^[:alnum:]]',
+    '',
+    'g'
+  );
+$$;
+
+-- Names that fold to the same key count as the same name. Names without
+-- letters or digits (emoji only) compare case-insensitively instead.
+create or replace function public.display_name_key(
+  p_name text
+) returns text
+language sql
+immutable
+set search_path = public
+as $$
+  select coalesce(nullif(public.fold_display_name(p_name), ''), lower(coalesce(p_name, '')));
+$$;
+
+alter table public.profiles
+  add column if not exists display_name_key text
+  generated always as (public.display_name_key(display_name)) stored;
+
+create index if not exists idx_profiles_display_name_key
+  on public.profiles (display_name_key);
+
+-- The app checks names before saving them; this repeats the blocklist and
+-- duplicate checks for clients that skip it.
+create or replace function public.check_display_name(
+  p_client_uuid text,
+  p_display_name text
+) returns void
+language plpgsql
+stable
+security definer
+set search_path = public
+as $$
+declare
+  v_folded text := public.fold_display_name(p_display_name);
+begin
+  if v_folded <> '' and exists (
+    select 1 from public.name_blocklist b
+    where b.kind = 'reserved' and public.fold_display_name(b.term) = v_folded
+  ) then
+    raise exception 'NAME_RESERVED';
+  end if;
+
+  if exists (
+    select 1
+    from regexp_split_to_table(p_display_name, '[^[:alnum:]!|@$]+') as raw_word,
+      lateral (select public.fold_display_name(raw_word) as word) as folded,
+      public.name_blocklist b,
+      lateral (select public.fold_display_name(b.term) as term) as blocked
+    where folded.word <> ''
+      and blocked.term <> ''
+      and (
+        (b.kind = 'word' and folded.word = blocked.term)
+        or (
+          b.kind = 'term'
+          and (folded.word like blocked.term || '%' or folded.word like '%' || blocked.term)
+        )
+      )
+  ) then
+    raise exception 'NAME_BLOCKED';
+  end if;
+
+  if exists (
+    select 1 from public.profiles p
+    where p.display_name_key = public.display_name_key(p_display_name)
+      and p.client_uuid <> p_client_uuid
+  ) then
+    raise exception 'NAME_TAKEN';
+  end if;
+end;
+$$;
+
+create or replace function public.upsert_profile(
+  p_client_uuid text,
+  p_device_secret text,
+  p_display_name text,
+  p_country_code text default null
+) returns jsonb
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  v_client_uuid text := trim(coalesce(p_client_uuid, ''));
+  -- Control, bidi and zero-width characters are dropped; U+200D stays for
+  -- emoji sequences.
+  v_display_name text := left(
+    normalize(
+      trim(regexp_replace(
+        coalesce(p_display_name, ''),
+        '[\x01-\x1f\x7f-\x9f\u00ad\u200b\u200c\u200e\u200f\u202a-\u202e\u2060-\u206f\ufeff]',
+        '',
+        'g'
+      )),
+      NFC
+    ),
+    20
+  );
+  v_country_code text := nullif(upper(trim(coalesce(p_country_code, ''))), '');
+  v_profile public.profiles;
+begin
+  perform public.authenticate_device(v_client_uuid, p_device_secret);
+
+  if v_display_name = '' then
+    raise exception 'INVALID_PLAYER_NAME';
+  end if;
+
+  perform public.check_display_name(v_client_uuid, v_display_name);
+
+  if v_country_code is not null and v_country_code !~ '^[A-Z]{2}$' then
+    raise exception 'INVALID_COUNTRY_CODE';
+  end if;
+
+  insert into public.profiles (client_uuid, display_name, country_code)
+  values (v_client_uuid, v_display_name, v_country_code)
+  on conflict (client_uuid) do update set
+    display_name = excluded.display_name,
+    country_code = excluded.country_code,
+    updated_at = now()
+  returning * into v_profile;
+
+  -- Renames apply to every record the device already owns.
+  update public.scores
+  set player_name = v_display_name
+  where client_uuid = v_client_uuid
+    and player_name <> v_display_name;
+
+  update public.group_members
+  set player_name = v_display_name
+  where client_uuid = v_client_uuid
+    and player_name <> v_display_name;
+
+  return jsonb_build_object(
+    'displayName', v_profile.display_name,
+    'countryCode', v_profile.country_code,
+    'createdAt', v_profile.created_at
+  );
+end;
+$$;
+
+-- Reports name the row by the public player id the boards show (the
+-- device's friend code); the device behind it is resolved here, so clients
+-- never need its UUID.
+create or replace function public.report_score(
+  p_reporter_client_uuid text,
+  p_device_secret text,
+  p_reported_player_id text,
+  p_mode text,
+  p_challenge_key text,
+  p_player_name text,
+  p_score integer,
+  p_level integer,
+  p_reason text,
+  p_detail text default ''
+) returns jsonb
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  v_reporter text := trim(coalesce(p_reporter_client_uuid, ''));
+  v_player_id text := upper(trim(coalesce(p_reported_player_id, '')));
+  v_reported text;
+  v_mode text := lower(trim(coalesce(p_mode, '')));
+  v_challenge_key text := trim(coalesce(p_challenge_key, ''));
+  v_reason text := lower(trim(coalesce(p_reason, '')));
+begin
+  perform public.authenticate_device(v_reporter, p_device_secret);
+
+  select client_uuid
+  into v_reported
+  from public.scores
+  where mode = v_mode
+    and challenge_key = v_challenge_key
+    and public.friend_code_for_device(client_uuid) = v_player_id
+  limit 1;
+
+  if v_reported is null then
+    raise exception 'SCORE_NOT_FOUND';
+  end if;
+
+  if v_reporter = v_reported then
+    raise exception 'CANNOT_REPORT_SELF';
+  end if;
+
+  if (
+    select count(*) from public.score_reports
+    where reporter_client_uuid = v_reporter
+      and created_at > now() - interval '1 day'
+  ) >= 20 then
+    raise exception 'REPORT_LIMIT_REACHED';
+  end if;
+
+  insert into public.score_reports (
+    reporter_client_uuid,
+    reported_client_uuid,
+    mode,
+    challenge_key,
+    player_name,
+    score,
+    level,
+    reason,
+    detail
+  ) values (
+    v_reporter,
+    v_reported,
+    v_mode,
+    v_challenge_key,
+    left(trim(coalesce(p_player_name, '')), 32),
+    greatest(coalesce(p_score, 0), 0),
+    greatest(coalesce(p_level, 0), 0),
+    v_reason,
+    left(trim(coalesce(p_detail, '')), 280)
+  )
+  on conflict (reporter_client_uuid, reported_client_uuid, mode, challenge_key) do update set
+    reason = excluded.reason,
+    detail = excluded.detail,
+    score = excluded.score,
+    level = excluded.level,
+    created_at = now();
+
+  return jsonb_build_object('reported', true);
+end;
+$$;
+
+create or replace function public.group_summary(
+  p_group_id uuid
+) returns jsonb
+language sql
+stable
+security definer
+set search_path = public
+as $$
+  select jsonb_build_object(
+    'groupId', g.id,
+    'name', g.name,
+    'ownerClientUuid', g.owner_client_uuid,
+    'memberCount', (select count(*) from public.group_members m where m.group_id = g.id)
+  )
+  from public.groups g
+  where g.id = p_group_id;
+$$;
+
+-- Name a device shows to its fellow group members: its profile name once it
+-- has one, otherwise the requested name under the display-name rules, as
+-- submit_global_score(...) names scores.
+create or replace function public.group_member_name(
+  p_client_uuid text,
+  p_player_name text
+) returns text
+language plpgsql
+stable
+security definer
+set search_path = public
+as $$
+declare
+  v_player_name text := left(trim(coalesce(p_player_name, '')), 20);
+  v_profile_name text;
+begin
+  select display_name
+  into v_profile_name
+  from public.profiles
+  where client_uuid = p_client_uuid;
+
+  if v_profile_name is not null then
+    return v_profile_name;
+  end if;
+
+  if v_player_name = '' then
+    raise exception 'INVALID_PLAYER_NAME';
+  end if;
+
+  perform public.check_display_name(p_client_uuid, v_player_name);
+  return v_player_name;
+end;
+$$;
+
+create or replace function public.create_group(
+  p_client_uuid text,
+  p_device_secret text,
+  p_name text,
+  p_player_name text
+) returns jsonb
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  v_client_uuid text := trim(coalesce(p_client_uuid, ''));
+  v_name text := trim(coalesce(p_name, ''));
+  v_player_name text;
+  v_invite_code text;
+  v_group_id uuid;
+begin
+  perform public.authenticate_device(v_client_uuid, p_device_secret);
+  v_player_name := public.group_member_name(v_client_uuid, p_player_name);
+
+  if char_length(v_name) not between 1 and 32 then
+    raise exception 'INVALID_GROUP_NAME';
+  end if;
+
+  if (select count(*) from public.group_members where client_uuid = v_client_uuid) >= 20 then
+    raise exception 'GROUP_LIMIT_REACHED';
+  end if;
+
+  loop
+    v_invite_code := upper(substr(md5(random()::text || clock_timestamp()::text), 1, 8));
+    v_invite_code := substr(v_invite_code, 1, 4) || '-' || substr(v_invite_code, 5, 4);
+    exit when not exists (
+      select 1 from public.groups where invite_code = v_invite_code
+    );
+  end loop;
+
+  insert into public.groups (name, invite_code, owner_client_uuid)
+  values (v_name, v_invite_code, v_client_uuid)
+  returning id into v_group_id;
+
+  insert into public.group_members (group_id, client_uuid, player_name)
+  values (v_group_id, v_client_uuid, v_player_name);
+
+  return public.group_summary(v_group_id) || jsonb_build_object('inviteCode', v_invite_code);
+end;
+$$;
+
+create or replace function public.join_group(
+  p_client_uuid text,
+  p_device_secret text,
+  p_invite_code text,
+  p_player_name text
+) returns jsonb
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  v_client_uuid text := trim(coalesce(p_client_uuid, ''));
+  v_invite_code text := upper(trim(coalesce(p_invite_code, '')));
+  v_player_name text;
+  v_group_id uuid;
+begin
+  perform public.authenticate_device(v_client_uuid, p_device_secret);
+  v_player_name := public.group_member_name(v_client_uuid, p_player_name);
+
+  select id
+  into v_group_id
+  from public.groups
+  where invite_code = v_invite_code
+  for update;
+
+  if not found then
+    raise exception 'GROUP_NOT_FOUND';
+  end if;
+
+  if exists (
+    select 1 from public.group_members
+    where group_id = v_group_id and client_uuid = v_client_uuid
+  ) then
+    update public.group_members
+    set player_name = v_player_name
+    where group_id = v_group_id and client_uuid = v_client_uuid;
+    return public.group_summary(v_group_id) || jsonb_build_object('inviteCode', v_invite_code);
+  end if;
+
+  if (select count(*) from public.group_members where group_id = v_group_id) >= 100 then
+    raise exception 'GROUP_FULL';
+  end if;
+
+  if (select count(*) from public.group_members where client_uuid = v_client_uuid) >= 20 then
+    raise exception 'GROUP_LIMIT_REACHED';
+  end if;
+
+  insert into public.group_members (group_id, client_uuid, player_name)
+  values (v_group_id, v_client_uuid, v_player_name);
+
+  return public.group_summary(v_group_id) || jsonb_build_object('inviteCode', v_invite_code);
+end;
+$$;
+
+create or replace function public.leave_group(
+  p_client_uuid text,
+  p_device_secret text,
+  p_group_id uuid
+) returns jsonb
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  v_client_uuid text := trim(coalesce(p_client_uuid, ''));
+  v_owner text;
+  v_next_owner text;
+begin
+  perform public.authenticate_device(v_client_uuid, p_device_secret);
+
+  select owner_client_uuid
+  into v_owner
+  from public.groups
+  where id = p_group_id
+  for update;
 
-drop policy if exists scores_select_public on public.scores;
-create policy scores_select_public
-  on public.scores
-  for select
-  using (true);
+  if not found then
+    raise exception 'GROUP_NOT_FOUND';
+  end if;
 
-drop policy if exists scores_insert_public on public.scores;
+  delete from public.group_members
+  where group_id = p_group_id and client_uuid = v_client_uuid;
 
-drop policy if exists scores_update_public on public.scores;
+  if not found then
+    raise exception 'NOT_A_MEMBER';
+  end if;
 
-alter table public.daily_streak_states enable row level security;
+  select client_uuid
+  into v_next_owner
+  from public.group_members
+  where group_id = p_group_id
+  order by joined_at asc, client_uuid asc
+  limit 1;
 
-drop policy if exists daily_streak_states_select_public on public.daily_streak_states;
-create policy daily_streak_states_select_public
-  on public.daily_streak_states
-  for select
-  using (true);
+  if v_next_owner is null then
+    delete from public.groups where id = p_group_id;
+    return jsonb_build_object('groupId', p_group_id, 'deleted', true);
+  end if;
 
-grant select on public.daily_streak_states to anon, authenticated;
+  if v_owner = v_client_uuid then
+    update public.groups
+    set owner_client_uuid = v_next_owner
+    where id = p_group_id;
+  end if;
 
-drop function if exists public.submit_daily_score(
-  text,
-  text,
-  text,
-  integer,
-  integer,
-  timestamptz,
-  jsonb
-);
+  return jsonb_build_object('groupId', p_group_id, 'deleted', false);
+end;
+$$;
 
-drop function if exists public.submit_global_score(
-  text,
-  text,
-  integer,
-  integer,
-  timestamptz,
-  jsonb
-);
+create or replace function public.list_group_members(
+  p_client_uuid text,
+  p_device_secret text,
+  p_group_id uuid
+) returns table (
+  client_uuid text,
+  player_name text,
+  joined_at timestamptz
+)
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  v_client_uuid text := trim(coalesce(p_client_uuid, ''));
+begin
+  perform public.authenticate_device(v_client_uuid, p_device_secret);
 
-drop function if exists public.start_daily_attempt(
-  text,
-  text,
-  text,
-  uuid
-);
+  if not exists (
+    select 1 from public.group_members m
+    where m.group_id = p_group_id and m.client_uuid = v_client_uuid
+  ) then
+    raise exception 'NOT_A_MEMBER';
+  end if;
 
-drop function if exists public.forfeit_daily_attempt(
-  text,
-  text,
-  text,
-  uuid
-);
+  return query
+  select m.client_uuid, m.player_name, m.joined_at
+  from public.group_members m
+  where m.group_id = p_group_id
+  order by m.joined_at asc, m.client_uuid asc;
+end;
+$$;
 
-drop function if exists public.rollback_daily_attempt(
-  text,
-  text,
-  text,
-  uuid
-);
+create or replace function public.list_my_groups(
+  p_client_uuid text,
+  p_device_secret text
+) returns jsonb
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  v_client_uuid text := trim(coalesce(p_client_uuid, ''));
+begin
+  perform public.authenticate_device(v_client_uuid, p_device_secret);
 
-drop function if exists public.submit_daily_score(
-  text,
-  text,
-  text,
-  text,
-  integer,
-  integer,
-  timestamptz,
-  jsonb,
-  uuid
-);
+  return (
+    select coalesce(
+      jsonb_agg(public.group_summary(m.group_id) order by m.joined_at asc),
+      '[]'::jsonb
+    )
+    from public.group_members m
+    where m.client_uuid = v_client_uuid
+  );
+end;
+$$;
 
-drop function if exists public.submit_global_score(
-  text,
-  text,
-  integer,
-  integer,
-  timestamptz,
-  jsonb,
-  uuid
-);
+create or replace function public.daily_history_ranks(
+  p_client_uuid text,
+  p_challenge_keys text[]
+) returns table (
+  challenge_key text,
+  rank bigint
+)
+language sql
+stable
+security definer
+set search_path = public
+as $$
+  -- One round-trip for a whole history range; ties order like the daily
+  -- board (score, level, created_at, all descending).
+  select mine.challenge_key,
+    1 + (
+      select count(*)
+      from public.scores other
+      where other.mode = 'daily'
+        and other.challenge_key = mine.challenge_key
+        and other.daily_has_submission
+        and (other.score, other.level, other.created_at)
+          > (mine.score, mine.level, mine.created_at)
+    )
+  from public.scores mine
+  where mine.mode = 'daily'
+    and mine.client_uuid = trim(coalesce(p_client_uuid, ''))
+    and mine.challenge_key = any(coalesce(p_challenge_keys, '{}'::text[]))
+    and mine.daily_has_submission;
+$$;
 
 create or replace function public.submit_global_score(
   p_client_uuid text,
@@ -328,7 +1396,8 @@ create or replace function public.submit_global_score(
   p_score integer,
   p_level integer,
   p_created_at timestamptz,
-  p_skill_usage jsonb
+  p_skill_usage jsonb,
+  p_device_secret text default null
 ) returns jsonb
 language plpgsql
 security definer
@@ -345,15 +1414,26 @@ declare
   v_existing_score integer := 0;
   v_existing_level integer := 0;
   v_is_better boolean := false;
+  v_best_score integer;
+  v_best_level integer;
+  v_rank integer;
+  v_total_players integer;
 begin
   if char_length(v_client_uuid) < 8 then
     raise exception 'INVALID_CLIENT_UUID';
   end if;
 
+  perform public.authenticate_submission(v_client_uuid, p_device_secret);
+
   if v_player_name = '' then
     raise exception 'INVALID_PLAYER_NAME';
   end if;
 
+  v_player_name := coalesce(
+    (select display_name from public.profiles where client_uuid = v_client_uuid),
+    v_player_name
+  );
+
   if jsonb_typeof(v_skill_usage) <> 'array' then
     v_skill_usage := '[]'::jsonb;
   end if;
@@ -395,17 +1475,14 @@ begin
       null,
       v_created_at
     );
-
-    return jsonb_build_object(
-      'accepted', true,
-      'improved', true
-    );
+    v_existing_score := null;
   end if;
 
-  v_is_better := v_score > v_existing_score
+  v_is_better := v_existing_score is null
+    or v_score > v_existing_score
     or (v_score = v_existing_score and v_level > v_existing_level);
 
-  if v_is_better then
+  if v_is_better and v_existing_score is not null then
     update public.scores
     set
       player_name = v_player_name,
@@ -420,9 +1497,31 @@ begin
     where id = v_existing_id;
   end if;
 
+  if v_is_better then
+    v_best_score := v_score;
+    v_best_level := v_level;
+  else
+    v_best_score := v_existing_score;
+    v_best_level := v_existing_level;
+  end if;
+
+  select
+    count(*) filter (
+      where score > v_best_score
+        or (score = v_best_score and level > v_best_level)
+    ) + 1,
+    count(*)
+  into v_rank, v_total_players
+  from public.scores
+  where mode = 'classic'
+    and challenge_key = 'classic';
+
   return jsonb_build_object(
     'accepted', true,
-    'improved', v_is_better
+    'improved', v_is_better,
+    'previousBest', v_existing_score,
+    'rank', v_rank,
+    'totalPlayers', v_total_players
   );
 end;
 $$;
@@ -430,7 +1529,8 @@ $$;
 create or replace function public.start_daily_attempt(
   p_client_uuid text,
   p_challenge_key text,
-  p_player_name text default null
+  p_player_name text default null,
+  p_mode text default 'daily'
 ) returns jsonb
 language plpgsql
 security definer
@@ -444,13 +1544,22 @@ declare
   v_attempts_used integer := 0;
   v_attempts_left integer := 0;
   v_active_attempt_token text := null;
-  v_today_key text := to_char((now() at time zone 'utc')::date, 'YYYY-MM-DD');
+  v_mode text := lower(trim(coalesce(p_mode, 'daily')));
+  v_max_attempts integer := public.challenge_max_attempts(
+    lower(trim(coalesce(p_mode, 'daily'))),
+    trim(coalesce(p_challenge_key, ''))
+  );
+  v_today_key text := public.challenge_period_key(lower(trim(coalesce(p_mode, 'daily'))));
 begin
   if char_length(v_client_uuid) < 8 then
     raise exception 'INVALID_CLIENT_UUID';
   end if;
 
-  if v_challenge_key !~ '^[0-9]{4}-[0-9]{2}-[0-9]{2}$' then
+  if v_today_key is null then
+    raise exception 'INVALID_MODE';
+  end if;
+
+  if v_challenge_key !~ public.challenge_key_pattern(v_mode) then
     raise exception 'INVALID_CHALLENGE_KEY';
   end if;
 
@@ -462,15 +1571,15 @@ begin
     v_player_name := 'Pending';
   end if;
 
-  -- Retain only today's Daily rows. Older Daily rows are not used by the app.
-  delete from public.scores
-  where mode = 'daily'
-    and challenge_key <> v_today_key;
+  v_player_name := coalesce(
+    (select display_name from public.profiles where client_uuid = v_client_uuid),
+    v_player_name
+  );
 
   select id, attempts_used, active_attempt_token
   into v_existing_id, v_attempts_used, v_active_attempt_token
   from public.scores
-  where mode = 'daily'
+  where mode = v_mode
     and challenge_key = v_challenge_key
     and client_uuid = v_client_uuid
   limit 1
@@ -496,7 +1605,7 @@ begin
       0,
       0,
       '[]'::jsonb,
-      'daily',
+      v_mode,
       v_challenge_key,
       0,
       false,
@@ -508,11 +1617,11 @@ begin
     into v_existing_id, v_attempts_used, v_active_attempt_token;
   end if;
 
-  v_attempts_used := least(3, greatest(0, coalesce(v_attempts_used, 0)));
+  v_attempts_used := least(v_max_attempts, greatest(0, coalesce(v_attempts_used, 0)));
   v_active_attempt_token := nullif(trim(coalesce(v_active_attempt_token, '')), '');
 
   if v_active_attempt_token is not null then
-    v_attempts_left := greatest(0, 3 - v_attempts_used);
+    v_attempts_left := greatest(0, v_max_attempts - v_attempts_used);
     return jsonb_build_object(
       'accepted', true,
       'resumed', true,
@@ -520,13 +1629,13 @@ begin
       'challengeKey', v_challenge_key,
       'attemptsUsed', v_attempts_used,
       'attemptsLeft', v_attempts_left,
-      'maxAttempts', 3,
+      'maxAttempts', v_max_attempts,
       'canSubmit', v_attempts_left > 0,
       'hasActiveAttempt', true
     );
   end if;
 
-  if v_attempts_used >= 3 then
+  if v_attempts_used >= v_max_attempts then
     return jsonb_build_object(
       'accepted', false,
       'resumed', false,
@@ -534,7 +1643,7 @@ begin
       'challengeKey', v_challenge_key,
       'attemptsUsed', v_attempts_used,
       'attemptsLeft', 0,
-      'maxAttempts', 3,
+      'maxAttempts', v_max_attempts,
       'canSubmit', false,
       'hasActiveAttempt', false
     );
@@ -552,7 +1661,7 @@ begin
     active_attempt_started_at = now()
   where id = v_existing_id;
 
-  v_attempts_left := greatest(0, 3 - v_attempts_used);
+  v_attempts_left := greatest(0, v_max_attempts - v_attempts_used);
   return jsonb_build_object(
     'accepted', true,
     'resumed', false,
@@ -560,7 +1669,7 @@ begin
     'challengeKey', v_challenge_key,
     'attemptsUsed', v_attempts_used,
     'attemptsLeft', v_attempts_left,
-    'maxAttempts', 3,
+    'maxAttempts', v_max_attempts,
     'canSubmit', v_attempts_left > 0,
     'hasActiveAttempt', true
   );
@@ -575,7 +1684,9 @@ create or replace function public.submit_daily_score(
   p_score integer,
   p_level integer,
   p_created_at timestamptz,
-  p_skill_usage jsonb
+  p_skill_usage jsonb,
+  p_mode text default 'daily',
+  p_device_secret text default null
 ) returns jsonb
 language plpgsql
 security definer
@@ -602,23 +1713,44 @@ declare
   v_previous_current_streak integer := 0;
   v_previous_max_streak integer := 0;
   v_previous_last_submission_key text := null;
+  v_previous_freeze_tokens integer := 0;
+  v_has_streak_state boolean := false;
   v_new_current_streak integer := 1;
   v_new_max_streak integer := 1;
-  v_today_key text := to_char((now() at time zone 'utc')::date, 'YYYY-MM-DD');
+  v_new_freeze_tokens integer := 0;
+  v_max_freeze_tokens constant integer := 2;
+  v_new_badge_tiers integer := 0;
+  v_mode text := lower(trim(coalesce(p_mode, 'daily')));
+  v_max_attempts integer := public.challenge_max_attempts(
+    lower(trim(coalesce(p_mode, 'daily'))),
+    trim(coalesce(p_challenge_key, ''))
+  );
+  v_today_key text := public.challenge_period_key(lower(trim(coalesce(p_mode, 'daily'))));
 begin
   if char_length(v_client_uuid) < 8 then
     raise exception 'INVALID_CLIENT_UUID';
   end if;
 
+  perform public.authenticate_submission(v_client_uuid, p_device_secret);
+
   if v_player_name = '' then
     raise exception 'INVALID_PLAYER_NAME';
   end if;
 
+  v_player_name := coalesce(
+    (select display_name from public.profiles where client_uuid = v_client_uuid),
+    v_player_name
+  );
+
   if v_attempt_token = '' then
     raise exception 'INVALID_ATTEMPT_TOKEN';
   end if;
 
-  if v_challenge_key !~ '^[0-9]{4}-[0-9]{2}-[0-9]{2}$' then
+  if v_today_key is null then
+    raise exception 'INVALID_MODE';
+  end if;
+
+  if v_challenge_key !~ public.challenge_key_pattern(v_mode) then
     raise exception 'INVALID_CHALLENGE_KEY';
   end if;
 
@@ -630,16 +1762,11 @@ begin
     v_skill_usage := '[]'::jsonb;
   end if;
 
-  -- Retain only today's Daily rows. Older Daily rows are not used by the app.
-  delete from public.scores
-  where mode = 'daily'
-    and challenge_key <> v_today_key;
-
   select id, score, level, attempts_used, active_attempt_token, daily_has_submission
   into v_existing_id, v_existing_score, v_existing_level, v_existing_attempts,
     v_existing_active_attempt_token, v_existing_has_submission
   from public.scores
-  where mode = 'daily'
+  where mode = v_mode
     and challenge_key = v_challenge_key
     and client_uuid = v_client_uuid
   limit 1
@@ -651,15 +1778,15 @@ begin
       'improved', false,
       'challengeKey', v_challenge_key,
       'attemptsUsed', 0,
-      'attemptsLeft', 3,
-      'maxAttempts', 3,
+      'attemptsLeft', v_max_attempts,
+      'maxAttempts', v_max_attempts,
       'canSubmit', true,
       'hasActiveAttempt', false
     );
   end if;
 
-  v_attempts_used := least(3, greatest(0, coalesce(v_existing_attempts, 0)));
-  v_attempts_left := greatest(0, 3 - v_attempts_used);
+  v_attempts_used := least(v_max_attempts, greatest(0, coalesce(v_existing_attempts, 0)));
+  v_attempts_left := greatest(0, v_max_attempts - v_attempts_used);
   v_existing_active_attempt_token := nullif(trim(coalesce(v_existing_active_attempt_token, '')), '');
 
   if v_existing_active_attempt_token is null then
@@ -669,7 +1796,7 @@ begin
       'challengeKey', v_challenge_key,
       'attemptsUsed', v_attempts_used,
       'attemptsLeft', v_attempts_left,
-      'maxAttempts', 3,
+      'maxAttempts', v_max_attempts,
       'canSubmit', v_attempts_left > 0,
       'hasActiveAttempt', false
     );
@@ -682,7 +1809,7 @@ begin
       'challengeKey', v_challenge_key,
       'attemptsUsed', v_attempts_used,
       'attemptsLeft', v_attempts_left,
-      'maxAttempts', 3,
+      'maxAttempts', v_max_attempts,
       'canSubmit', v_attempts_left > 0,
       'hasActiveAttempt', true
     );
@@ -705,60 +1832,105 @@ begin
     skill_usage = case when v_is_better then v_skill_usage else skill_usage end
   where id = v_existing_id;
 
-  select current_streak, max_streak, last_submission_key
-  into v_previous_current_streak, v_previous_max_streak, v_previous_last_submission_key
-  from public.daily_streak_states
-  where client_uuid = v_client_uuid
-  for update;
-
-  if not found then
-    v_new_current_streak := 1;
-    v_new_max_streak := 1;
-  elsif v_previous_last_submission_key = v_challenge_key then
-    v_new_current_streak := greatest(0, coalesce(v_previous_current_streak, 0));
-    v_new_max_streak := greatest(coalesce(v_previous_max_streak, 0), v_new_current_streak);
-  elsif
-    v_previous_last_submission_key is not null
-    and v_previous_last_submission_key ~ '^[0-9]{4}-[0-9]{2}-[0-9]{2}$'
-    and (
-      to_date(v_challenge_key, 'YYYY-MM-DD')
-      - to_date(v_previous_last_submission_key, 'YYYY-MM-DD')
-    ) = 1
-  then
-    v_new_current_streak := greatest(0, coalesce(v_previous_current_streak, 0)) + 1;
-    v_new_max_streak := greatest(coalesce(v_previous_max_streak, 0), v_new_current_streak);
-  else
-    v_new_current_streak := 1;
-    v_new_max_streak := greatest(coalesce(v_previous_max_streak, 0), 1);
+  -- Streaks and freeze tokens only track the Daily period.
+  if v_mode = 'daily' then
+    select current_streak, max_streak, last_submission_key, freeze_tokens
+    into v_previous_current_streak, v_previous_max_streak, v_previous_last_submission_key,
+      v_previous_freeze_tokens
+    from public.daily_streak_states
+    where client_uuid = v_client_uuid
+    for update;
+    v_has_streak_state := found;
+
+    v_previous_freeze_tokens := least(
+      v_max_freeze_tokens,
+      greatest(0, coalesce(v_previous_freeze_tokens, 0))
+    );
+    v_new_freeze_tokens := v_previous_freeze_tokens;
+
+    if not v_has_streak_state then
+      v_previous_max_streak := 0;
+      v_new_current_streak := 1;
+      v_new_max_streak := 1;
+      v_new_freeze_tokens := 0;
+    elsif v_previous_last_submission_key = v_challenge_key then
+      v_new_current_streak := greatest(0, coalesce(v_previous_current_streak, 0));
+      v_new_max_streak := greatest(coalesce(v_previous_max_streak, 0), v_new_current_streak);
+    elsif
+      v_previous_last_submission_key is not null
+      and v_previous_last_submission_key ~ '^[0-9]{4}-[0-9]{2}-[0-9]{2}$'
+      and (
+        to_date(v_challenge_key, 'YYYY-MM-DD')
+        - to_date(v_previous_last_submission_key, 'YYYY-MM-DD')
+      ) = 1
+    then
+      v_new_current_streak := greatest(0, coalesce(v_previous_current_streak, 0)) + 1;
+      v_new_max_streak := greatest(coalesce(v_previous_max_streak, 0), v_new_current_streak);
+    elsif
+      v_previous_freeze_tokens > 0
+      and v_previous_last_submission_key is not null
+      and v_previous_last_submission_key ~ '^[0-9]{4}-[0-9]{2}-[0-9]{2}$'
+      and (
+        to_date(v_challenge_key, 'YYYY-MM-DD')
+        - to_date(v_previous_last_submission_key, 'YYYY-MM-DD')
+      ) = 2
+    then
+      -- Exactly one missed UTC day: spend a freeze token to keep the streak.
+      v_new_current_streak := greatest(0, coalesce(v_previous_current_streak, 0)) + 1;
+      v_new_max_streak := greatest(coalesce(v_previous_max_streak, 0), v_new_current_streak);
+      v_new_freeze_tokens := v_previous_freeze_tokens - 1;
+    else
+      v_new_current_streak := 1;
+      v_new_max_streak := greatest(coalesce(v_previous_max_streak, 0), 1);
+    end if;
+
+    -- Earn one freeze token per newly reached badge tier past the first-day
+    -- tier. Tiers come from badge_tiers, or the bundled 2^0..2^9 ladder when
+    -- that table is empty.
+    select count(*)
+    into v_new_badge_tiers
+    from (
+      select days from public.badge_tiers
+      union all
+      select (2 ^ exponent)::integer
+      from generate_series(0, 9) as exponent
+      where not exists (select 1 from public.badge_tiers)
+    ) as tiers
+    where tiers.days > greatest(coalesce(v_previous_max_streak, 0), 1)
+      and tiers.days <= v_new_max_streak;
+    v_new_freeze_tokens := least(v_max_freeze_tokens, v_new_freeze_tokens + v_new_badge_tiers);
+
+    insert into public.daily_streak_states (
+      client_uuid,
+      current_streak,
+      max_streak,
+      last_submission_key,
+      freeze_tokens,
+      updated_at
+    )
+    values (
+      v_client_uuid,
+      v_new_current_streak,
+      v_new_max_streak,
+      v_challenge_key,
+      v_new_freeze_tokens,
+      now()
+    )
+    on conflict (client_uuid) do update set
+      current_streak = excluded.current_streak,
+      max_streak = greatest(public.daily_streak_states.max_streak, excluded.max_streak),
+      last_submission_key = excluded.last_submission_key,
+      freeze_tokens = excluded.freeze_tokens,
+      updated_at = excluded.updated_at;
   end if;
 
-  insert into public.daily_streak_states (
-    client_uuid,
-    current_streak,
-    max_streak,
-    last_submission_key,
-    updated_at
-  )
-  values (
-    v_client_uuid,
-    v_new_current_streak,
-    v_new_max_streak,
-    v_challenge_key,
-    now()
-  )
-  on conflict (client_uuid) do update set
-    current_streak = excluded.current_streak,
-    max_streak = greatest(public.daily_streak_states.max_streak, excluded.max_streak),
-    last_submission_key = excluded.last_submission_key,
-    updated_at = excluded.updated_at;
-
   return jsonb_build_object(
     'accepted', true,
     'improved', v_is_better,
     'challengeKey', v_challenge_key,
     'attemptsUsed', v_attempts_used,
     'attemptsLeft', v_attempts_left,
-    'maxAttempts', 3,
+    'maxAttempts', v_max_attempts,
     'canSubmit', v_attempts_left > 0,
     'hasActiveAttempt', false
   );
@@ -768,7 +1940,8 @@ $$;
 create or replace function public.forfeit_daily_attempt(
   p_client_uuid text,
   p_challenge_key text,
-  p_attempt_token text
+  p_attempt_token text,
+  p_mode text default 'daily'
 ) returns jsonb
 language plpgsql
 security definer
@@ -783,7 +1956,12 @@ declare
   v_existing_active_attempt_token text := null;
   v_attempts_used integer := 0;
   v_attempts_left integer := 0;
-  v_today_key text := to_char((now() at time zone 'utc')::date, 'YYYY-MM-DD');
+  v_mode text := lower(trim(coalesce(p_mode, 'daily')));
+  v_max_attempts integer := public.challenge_max_attempts(
+    lower(trim(coalesce(p_mode, 'daily'))),
+    trim(coalesce(p_challenge_key, ''))
+  );
+  v_today_key text := public.challenge_period_key(lower(trim(coalesce(p_mode, 'daily'))));
 begin
   if char_length(v_client_uuid) < 8 then
     raise exception 'INVALID_CLIENT_UUID';
@@ -793,7 +1971,11 @@ begin
     raise exception 'INVALID_ATTEMPT_TOKEN';
   end if;
 
-  if v_challenge_key !~ '^[0-9]{4}-[0-9]{2}-[0-9]{2}$' then
+  if v_today_key is null then
+    raise exception 'INVALID_MODE';
+  end if;
+
+  if v_challenge_key !~ public.challenge_key_pattern(v_mode) then
     raise exception 'INVALID_CHALLENGE_KEY';
   end if;
 
@@ -801,15 +1983,10 @@ begin
     raise exception 'CHALLENGE_KEY_MISMATCH';
   end if;
 
-  -- Retain only today's Daily rows. Older Daily rows are not used by the app.
-  delete from public.scores
-  where mode = 'daily'
-    and challenge_key <> v_today_key;
-
   select id, attempts_used, active_attempt_token
   into v_existing_id, v_existing_attempts, v_existing_active_attempt_token
   from public.scores
-  where mode = 'daily'
+  where mode = v_mode
     and challenge_key = v_challenge_key
     and client_uuid = v_client_uuid
   limit 1
@@ -820,15 +1997,15 @@ begin
       'accepted', false,
       'challengeKey', v_challenge_key,
       'attemptsUsed', 0,
-      'attemptsLeft', 3,
-      'maxAttempts', 3,
+      'attemptsLeft', v_max_attempts,
+      'maxAttempts', v_max_attempts,
       'canSubmit', true,
       'hasActiveAttempt', false
     );
   end if;
 
-  v_attempts_used := least(3, greatest(0, coalesce(v_existing_attempts, 0)));
-  v_attempts_left := greatest(0, 3 - v_attempts_used);
+  v_attempts_used := least(v_max_attempts, greatest(0, coalesce(v_existing_attempts, 0)));
+  v_attempts_left := greatest(0, v_max_attempts - v_attempts_used);
   v_existing_active_attempt_token := nullif(trim(coalesce(v_existing_active_attempt_token, '')), '');
 
   if v_existing_active_attempt_token is null or v_existing_active_attempt_token <> v_attempt_token then
@@ -837,7 +2014,7 @@ begin
       'challengeKey', v_challenge_key,
       'attemptsUsed', v_attempts_used,
       'attemptsLeft', v_attempts_left,
-      'maxAttempts', 3,
+      'maxAttempts', v_max_attempts,
       'canSubmit', v_attempts_left > 0,
       'hasActiveAttempt', v_existing_active_attempt_token is not null
     );
@@ -854,7 +2031,7 @@ begin
     'challengeKey', v_challenge_key,
     'attemptsUsed', v_attempts_used,
     'attemptsLeft', v_attempts_left,
-    'maxAttempts', 3,
+    'maxAttempts', v_max_attempts,
     'canSubmit', v_attempts_left > 0,
     'hasActiveAttempt', false
   );
@@ -864,7 +2041,8 @@ $$;
 create or replace function public.rollback_daily_attempt(
   p_client_uuid text,
   p_challenge_key text,
-  p_attempt_token text
+  p_attempt_token text,
+  p_mode text default 'daily'
 ) returns jsonb
 language plpgsql
 security definer
@@ -879,7 +2057,12 @@ declare
   v_existing_active_attempt_token text := null;
   v_attempts_used integer := 0;
   v_attempts_left integer := 0;
-  v_today_key text := to_char((now() at time zone 'utc')::date, 'YYYY-MM-DD');
+  v_mode text := lower(trim(coalesce(p_mode, 'daily')));
+  v_max_attempts integer := public.challenge_max_attempts(
+    lower(trim(coalesce(p_mode, 'daily'))),
+    trim(coalesce(p_challenge_key, ''))
+  );
+  v_today_key text := public.challenge_period_key(lower(trim(coalesce(p_mode, 'daily'))));
 begin
   if char_length(v_client_uuid) < 8 then
     raise exception 'INVALID_CLIENT_UUID';
@@ -889,7 +2072,11 @@ begin
     raise exception 'INVALID_ATTEMPT_TOKEN';
   end if;
 
-  if v_challenge_key !~ '^[0-9]{4}-[0-9]{2}-[0-9]{2}$' then
+  if v_today_key is null then
+    raise exception 'INVALID_MODE';
+  end if;
+
+  if v_challenge_key !~ public.challenge_key_pattern(v_mode) then
     raise exception 'INVALID_CHALLENGE_KEY';
   end if;
 
@@ -897,15 +2084,10 @@ begin
     raise exception 'CHALLENGE_KEY_MISMATCH';
   end if;
 
-  -- Retain only today's Daily rows. Older Daily rows are not used by the app.
-  delete from public.scores
-  where mode = 'daily'
-    and challenge_key <> v_today_key;
-
   select id, attempts_used, active_attempt_token
   into v_existing_id, v_existing_attempts, v_existing_active_attempt_token
   from public.scores
-  where mode = 'daily'
+  where mode = v_mode
     and challenge_key = v_challenge_key
     and client_uuid = v_client_uuid
   limit 1
@@ -916,15 +2098,15 @@ begin
       'accepted', false,
       'challengeKey', v_challenge_key,
       'attemptsUsed', 0,
-      'attemptsLeft', 3,
-      'maxAttempts', 3,
+      'attemptsLeft', v_max_attempts,
+      'maxAttempts', v_max_attempts,
       'canSubmit', true,
       'hasActiveAttempt', false
     );
   end if;
 
-  v_attempts_used := least(3, greatest(0, coalesce(v_existing_attempts, 0)));
-  v_attempts_left := greatest(0, 3 - v_attempts_used);
+  v_attempts_used := least(v_max_attempts, greatest(0, coalesce(v_existing_attempts, 0)));
+  v_attempts_left := greatest(0, v_max_attempts - v_attempts_used);
   v_existing_active_attempt_token := nullif(trim(coalesce(v_existing_active_attempt_token, '')), '');
 
   if v_existing_active_attempt_token is null or v_existing_active_attempt_token <> v_attempt_token then
@@ -933,14 +2115,14 @@ begin
       'challengeKey', v_challenge_key,
       'attemptsUsed', v_attempts_used,
       'attemptsLeft', v_attempts_left,
-      'maxAttempts', 3,
+      'maxAttempts', v_max_attempts,
       'canSubmit', v_attempts_left > 0,
       'hasActiveAttempt', v_existing_active_attempt_token is not null
     );
   end if;
 
   v_attempts_used := greatest(0, v_attempts_used - 1);
-  v_attempts_left := greatest(0, 3 - v_attempts_used);
+  v_attempts_left := greatest(0, v_max_attempts - v_attempts_used);
 
   update public.scores
   set
@@ -954,7 +2136,7 @@ begin
     'challengeKey', v_challenge_key,
     'attemptsUsed', v_attempts_used,
     'attemptsLeft', v_attempts_left,
-    'maxAttempts', 3,
+    'maxAttempts', v_max_attempts,
     'canSubmit', v_attempts_left > 0,
     'hasActiveAttempt', false
   );
@@ -962,6 +2144,7 @@ end;
 $$;
 
 grant execute on function public.start_daily_attempt(
+  text,
   text,
   text,
   text
@@ -973,7 +2156,8 @@ grant execute on function public.submit_global_score(
   integer,
   integer,
   timestamptz,
-  jsonb
+  jsonb,
+  text
 ) to service_role;
 
 revoke execute on function public.submit_global_score(
@@ -982,7 +2166,8 @@ revoke execute on function public.submit_global_score(
   integer,
   integer,
   timestamptz,
-  jsonb
+  jsonb,
+  text
 ) from anon, authenticated;
 
 grant execute on function public.submit_daily_score(
@@ -993,7 +2178,9 @@ grant execute on function public.submit_daily_score(
   integer,
   integer,
   timestamptz,
-  jsonb
+  jsonb,
+  text,
+  text
 ) to service_role;
 
 revoke execute on function public.submit_daily_score(
@@ -1004,10 +2191,13 @@ revoke execute on function public.submit_daily_score(
   integer,
   integer,
   timestamptz,
-  jsonb
+  jsonb,
+  text,
+  text
 ) from anon, authenticated;
 
 grant execute on function public.forfeit_daily_attempt(
+  text,
   text,
   text,
   text
@@ -1015,6 +2205,84 @@ grant execute on function public.forfeit_daily_attempt(
 
 grant execute on function public.rollback_daily_attempt(
   text,
+  text,
+  text,
+  text
+) to anon, authenticated;
+
+revoke execute on function public.bind_device_credential(text, text) from public, anon, authenticated;
+
+revoke execute on function public.authenticate_device(text, text) from public, anon, authenticated;
+
+revoke execute on function public.authenticate_submission(text, text) from public, anon, authenticated;
+
+grant execute on function public.publish_friend_code(
+  text,
+  text
+) to anon, authenticated;
+
+grant execute on function public.resolve_friend_codes(
+  text[]
+) to anon, authenticated;
+
+grant execute on function public.upsert_profile(
+  text,
+  text,
+  text,
+  text
+) to anon, authenticated;
+
+revoke execute on function public.check_display_name(text, text) from public, anon, authenticated;
+
+grant execute on function public.report_score(
+  text,
+  text,
+  text,
+  text,
+  text,
+  text,
+  integer,
+  integer,
+  text,
+  text
+) to anon, authenticated;
+
+revoke execute on function public.group_summary(uuid) from public, anon, authenticated;
+
+revoke execute on function public.group_member_name(text, text) from public, anon, authenticated;
+
+grant execute on function public.create_group(
+  text,
+  text,
+  text,
+  text
+) to anon, authenticated;
+
+grant execute on function public.join_group(
+  text,
+  text,
+  text,
+  text
+) to anon, authenticated;
+
+grant execute on function public.leave_group(
+  text,
+  text,
+  uuid
+) to anon, authenticated;
+
+grant execute on function public.list_group_members(
+  text,
+  text,
+  uuid
+) to anon, authenticated;
+
+grant execute on function public.list_my_groups(
   text,
   text
 ) to anon, authenticated;
+
+grant execute on function public.daily_history_ranks(
+  text,
+  text[]
+) to anon, authenticated;